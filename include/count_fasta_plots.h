@@ -0,0 +1,63 @@
+#ifndef COUNT_FASTA_PLOTS_H
+#define COUNT_FASTA_PLOTS_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Records read by [`cfp_load`].
+typedef struct CfpDataset CfpDataset;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Reads the CSV file, FASTA file, directory of either or glob pattern
+// `path`, grouped by the `group_by` and `color_by` of `options`. Free the
+// records with [`cfp_free_dataset`].
+//
+// # Safety
+//
+// `path` must be a NUL-terminated string, and `options` one or `NULL`.
+struct CfpDataset *cfp_load(const char *path, const char *options);
+
+// Draws the figure of `dataset` with `options` to `format`, `"png"`,
+// `"svg"` or `"pdf"`, and returns the contents of its file, `*len` bytes
+// long. Free them with [`cfp_free_buffer`].
+//
+// # Safety
+//
+// `dataset` must come from [`cfp_load`], `format` and `options` must be
+// NUL-terminated strings or `NULL`, for PNG and the defaults, and `len` must
+// point to a `size_t`.
+uint8_t *cfp_render(const struct CfpDataset *dataset,
+                    const char *format,
+                    const char *options,
+                    size_t *len);
+
+// Frees the `len` bytes of a file returned by [`cfp_render`]; nothing for
+// `NULL`.
+//
+// # Safety
+//
+// `buffer` must come from [`cfp_render`] with this `len`, and not be used
+// afterwards.
+void cfp_free_buffer(uint8_t *buffer, size_t len);
+
+// Frees records read by [`cfp_load`]; nothing for `NULL`.
+//
+// # Safety
+//
+// `dataset` must come from [`cfp_load`] and not be used afterwards.
+void cfp_free_dataset(struct CfpDataset *dataset);
+
+// Message of the last failure on the calling thread, valid until the next
+// one, or `NULL` when nothing failed.
+const char *cfp_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* COUNT_FASTA_PLOTS_H */
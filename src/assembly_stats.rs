@@ -0,0 +1,140 @@
+//! JSON written by `assembly-stats`, with the total length, count, longest and
+//! shortest sequence, N count, gaps and N50, N70 and N90 of each assembly.
+
+use crate::{GenomeStats, Invalid, InvalidInput, LoadOptions, Missing, SendError};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Whether `json` was written by `assembly-stats`: the statistics of one
+/// assembly, an array of them, or an object mapping file names to them, all
+/// with a `total_length` and a `number` of sequences.
+pub fn is_stats(json: &Value) -> bool {
+    let is_assembly = |value: &Value| value.get("total_length").is_some() && value.get("number").is_some();
+    match json {
+        Value::Array(items) => items.first().is_some_and(is_assembly),
+        Value::Object(object) => is_assembly(json) || object.values().next().is_some_and(is_assembly),
+        _ => false,
+    }
+}
+
+/// Reads the statistics of `json`, read from `path`. The GC content is not
+/// measured by assembly-stats and is left missing; `N_count` gives the N
+/// percentage.
+pub fn read_json(path: &Path, json: &Value, options: &LoadOptions) -> Result<Vec<GenomeStats>, SendError> {
+    let objects: Vec<(Option<String>, &Map<String, Value>)> = match json {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_object().map(|object| (None, object)))
+            .collect::<Option<_>>()
+            .ok_or_else(|| format!("{}: expected an array of objects", path.display()))?,
+        Value::Object(object) if object.contains_key("total_length") => vec![(None, object)],
+        Value::Object(object) => object
+            .iter()
+            .map(|(name, stats)| stats.as_object().map(|stats| (Some(name.clone()), stats)))
+            .collect::<Option<_>>()
+            .ok_or_else(|| format!("{}: expected the statistics of assemblies", path.display()))?,
+        _ => return Err(format!("{}: expected the statistics of assemblies", path.display()).into()),
+    };
+
+    let mut data = Vec::new();
+    let mut skipped = 0;
+    for (i, (name, object)) in objects.into_iter().enumerate() {
+        let name = name.or_else(|| object.get("filename").and_then(Value::as_str).map(str::to_owned));
+        let label = name.clone().unwrap_or_else(|| format!("assembly {}", i + 1));
+        let field = |key: &str| object.get(key).and_then(Value::as_f64);
+        let total = field("total_length");
+        let n = total.and_then(|total| Some(field("N_count")? / total * 100.0));
+
+        let mut missing = None;
+        let mut value = |key: &'static str, value: Option<f64>| {
+            value.unwrap_or_else(|| {
+                missing.get_or_insert(key);
+                0.0
+            })
+        };
+        let mut record = GenomeStats {
+            filename: name,
+            assembly_length: value("total_length", total),
+            number_of_sequences: value("number", field("number")),
+            n50: value("N50", field("N50")),
+            gc_percentage: f64::NAN,
+            n_percentage: value("N_count", n),
+            aun: None,
+            l50: field("N50n"),
+            l90: field("N90n"),
+            largest_contig: field("longest"),
+            shortest_contig: field("shortest"),
+            gaps: field("Gaps"),
+            gap_length: field("N_count"),
+            group: None,
+            color: None,
+            lengths: Vec::new(),
+            sequence_gc: Vec::new(),
+            extra: HashMap::new(),
+            source: None,
+            busco: None,
+            mean_quality: None,
+        };
+        if let Some(key) = missing {
+            let message = format!("{}: {}: no {:?} field", path.display(), label, key);
+            match options.missing {
+                Missing::Fail => return Err(InvalidInput::new(Invalid::MissingColumn, message).into()),
+                Missing::Skip => {
+                    tracing::info!("{message}, skipping it");
+                    skipped += 1;
+                    continue;
+                }
+                Missing::Zero => {}
+            }
+        }
+
+        if let Some(column) = &options.group_by {
+            match object.get(column.as_str()) {
+                Some(Value::String(group)) => record.group = Some(group.clone()),
+                Some(group) => record.group = Some(group.to_string()),
+                None if column == crate::SOURCE_COLUMN => {}
+                None => {
+                    return Err(InvalidInput::new(
+                        Invalid::MissingColumn,
+                        format!("{}: {}: no field named {:?} to group by", path.display(), label, column),
+                    )
+                    .into())
+                }
+            }
+        }
+        if let Some(column) = &options.color_by {
+            match object.get(column.as_str()) {
+                Some(Value::String(color)) => record.color = Some(color.clone()),
+                Some(color) => record.color = Some(color.to_string()),
+                None if column == crate::SOURCE_COLUMN => {}
+                None => {
+                    return Err(InvalidInput::new(
+                        Invalid::MissingColumn,
+                        format!("{}: {}: no field named {:?} to color by", path.display(), label, column),
+                    )
+                    .into())
+                }
+            }
+        }
+        for column in &options.extra_columns {
+            let value = field(column).ok_or_else(|| {
+                InvalidInput::new(
+                    Invalid::MissingColumn,
+                    format!("{}: {}: no numeric field named {:?} to plot", path.display(), label, column),
+                )
+            })?;
+            record.extra.insert(column.clone(), value);
+        }
+        data.push(record);
+    }
+    if skipped > 0 {
+        tracing::warn!(
+            "{}: skipped {} of {} assemblies with missing values",
+            path.display(),
+            skipped,
+            skipped + data.len()
+        );
+    }
+    Ok(data)
+}
@@ -0,0 +1,222 @@
+//! Counting of the GC, N and runs of N of sequence data. On x86-64 the bytes
+//! are compared a whole vector at a time, with AVX2 when the processor has it
+//! and SSE2 otherwise; elsewhere, and for the bytes left over at the end, one
+//! at a time.
+
+/// Counts of some sequence data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Counts {
+    /// Bytes other than whitespace.
+    pub bases: u64,
+    pub gc: u64,
+    pub n: u64,
+    /// Runs of N started in the data.
+    pub gaps: u64,
+}
+
+/// Counts of `bases`, `in_gap` telling whether the data before them ended in
+/// N and updated to whether they do. Whitespace neither counts nor ends a run.
+pub(crate) fn count(bases: &[u8], in_gap: &mut bool) -> Counts {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("popcnt") {
+            // SAFETY: the features of the kernel were just detected
+            return unsafe { x86::avx2(bases, in_gap) };
+        }
+        // SAFETY: SSE2 is part of x86-64
+        unsafe { x86::sse2(bases, in_gap) }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let mut counts = Counts::default();
+        scalar(bases, in_gap, &mut counts);
+        counts
+    }
+}
+
+/// Adds the counts of `bases` to `counts` one byte at a time.
+fn scalar(bases: &[u8], in_gap: &mut bool, counts: &mut Counts) {
+    for &base in bases {
+        match base {
+            b'N' | b'n' => {
+                counts.n += 1;
+                if !*in_gap {
+                    counts.gaps += 1;
+                }
+                *in_gap = true;
+            }
+            b'\n' | b'\r' | b' ' | b'\t' => continue,
+            b'G' | b'C' | b'g' | b'c' => {
+                counts.gc += 1;
+                *in_gap = false;
+            }
+            _ => *in_gap = false,
+        }
+        counts.bases += 1;
+    }
+}
+
+/// Adds to `counts` those of a `chunk` of `width` bytes whose N, whitespace
+/// and GC bytes are the bits of the masks `n`, `space` and `gc`, the first
+/// byte the lowest.
+#[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+#[inline(always)]
+fn tally(chunk: &[u8], width: u32, (n, space, gc): (u32, u32, u32), in_gap: &mut bool, counts: &mut Counts) {
+    counts.bases += u64::from(width - space.count_ones());
+    counts.gc += u64::from(gc.count_ones());
+    counts.n += u64::from(n.count_ones());
+    if n == 0 {
+        if space.count_ones() < width {
+            *in_gap = false;
+        }
+    } else if space == 0 {
+        // A run starts at each N after a byte other than N
+        let after_n = (n << 1) | u32::from(*in_gap);
+        counts.gaps += u64::from((n & !after_n).count_ones());
+        *in_gap = n >> (width - 1) == 1;
+    } else {
+        // Whitespace within runs of N, which the masks cannot skip over
+        let mut gaps = Counts::default();
+        scalar(chunk, in_gap, &mut gaps);
+        counts.gaps += gaps.gaps;
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::{scalar, tally, Counts};
+    use std::arch::x86_64::*;
+
+    /// Clears the bit telling lower from upper case ASCII letters.
+    const UPPER: i8 = !0x20u8 as i8;
+
+    #[target_feature(enable = "avx2,popcnt")]
+    pub(super) unsafe fn avx2(bases: &[u8], in_gap: &mut bool) -> Counts {
+        let mut counts = Counts::default();
+        let mut chunks = bases.chunks_exact(32);
+        for chunk in &mut chunks {
+            let bytes = _mm256_loadu_si256(chunk.as_ptr().cast());
+            let upper = _mm256_and_si256(bytes, _mm256_set1_epi8(UPPER));
+            let is = |vector, byte: u8| _mm256_cmpeq_epi8(vector, _mm256_set1_epi8(byte as i8));
+            let n = _mm256_movemask_epi8(is(upper, b'N')) as u32;
+            let gc = _mm256_movemask_epi8(_mm256_or_si256(is(upper, b'G'), is(upper, b'C'))) as u32;
+            let space = _mm256_or_si256(
+                _mm256_or_si256(is(bytes, b'\n'), is(bytes, b'\r')),
+                _mm256_or_si256(is(bytes, b' '), is(bytes, b'\t')),
+            );
+            let space = _mm256_movemask_epi8(space) as u32;
+            tally(chunk, 32, (n, space, gc), in_gap, &mut counts);
+        }
+        scalar(chunks.remainder(), in_gap, &mut counts);
+        counts
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn sse2(bases: &[u8], in_gap: &mut bool) -> Counts {
+        let mut counts = Counts::default();
+        let mut chunks = bases.chunks_exact(16);
+        for chunk in &mut chunks {
+            let bytes = _mm_loadu_si128(chunk.as_ptr().cast());
+            let upper = _mm_and_si128(bytes, _mm_set1_epi8(UPPER));
+            let is = |vector, byte: u8| _mm_cmpeq_epi8(vector, _mm_set1_epi8(byte as i8));
+            let n = _mm_movemask_epi8(is(upper, b'N')) as u32;
+            let gc = _mm_movemask_epi8(_mm_or_si128(is(upper, b'G'), is(upper, b'C'))) as u32;
+            let space = _mm_or_si128(
+                _mm_or_si128(is(bytes, b'\n'), is(bytes, b'\r')),
+                _mm_or_si128(is(bytes, b' '), is(bytes, b'\t')),
+            );
+            let space = _mm_movemask_epi8(space) as u32;
+            tally(chunk, 16, (n, space, gc), in_gap, &mut counts);
+        }
+        scalar(chunks.remainder(), in_gap, &mut counts);
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes of FASTA sequence lines: bases of either case, IUPAC codes, and
+    /// whitespace, often within runs of N.
+    const ALPHABET: &[u8] = b"ACGTacgtNNNNnnnnRYSWKMBDHVryswkmbdhv-*\r\n \t";
+
+    /// A random buffer of `len` bytes of `alphabet`, from a xorshift seeded
+    /// with `seed`.
+    fn random(alphabet: &[u8], len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                alphabet[(state % alphabet.len() as u64) as usize]
+            })
+            .collect()
+    }
+
+    fn scalar_counts(bases: &[u8], in_gap: &mut bool) -> Counts {
+        let mut counts = Counts::default();
+        scalar(bases, in_gap, &mut counts);
+        counts
+    }
+
+    /// Asserts that `kernel` counts as `scalar` does every length of up to
+    /// four vectors, from every offset within a vector, from inside a run of
+    /// N or not, with and without whitespace in the data.
+    fn assert_agrees(kernel: impl Fn(&[u8], &mut bool) -> Counts, width: usize) {
+        for seed in 1..=16 {
+            let alphabet = if seed % 2 == 0 { ALPHABET } else { &ALPHABET[..ALPHABET.len() - 4] };
+            let buffer = random(alphabet, 6 * width, seed);
+            for offset in 0..width {
+                for len in 0..=4 * width {
+                    let bases = &buffer[offset..offset + len];
+                    for start in [false, true] {
+                        let (mut expected_gap, mut gap) = (start, start);
+                        let expected = scalar_counts(bases, &mut expected_gap);
+                        assert_eq!(kernel(bases, &mut gap), expected, "{:?} from {start}", String::from_utf8_lossy(bases));
+                        assert_eq!(gap, expected_gap, "{:?} from {start}", String::from_utf8_lossy(bases));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_counts_bases() {
+        let mut in_gap = false;
+        let counts = scalar_counts(b"ACgtNn\r\nnNRY\nNGc", &mut in_gap);
+        assert_eq!(counts, Counts { bases: 13, gc: 4, n: 5, gaps: 2 });
+        assert!(!in_gap);
+    }
+
+    #[test]
+    fn whitespace_within_runs_of_n() {
+        let mut bases = b"ACGT".repeat(4);
+        bases.extend_from_slice(b"NNNN\r\nNNNN\nNNNN NNNN\tNNNNNNNNNNNNNNNNACGT");
+        assert_eq!(count(&bases, &mut false), scalar_counts(&bases, &mut false));
+        assert_eq!(count(&bases, &mut false).gaps, 1);
+    }
+
+    #[test]
+    fn count_agrees_with_scalar() {
+        assert_agrees(count, 32);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn sse2_agrees_with_scalar() {
+        // SAFETY: SSE2 is part of x86-64
+        assert_agrees(|bases, in_gap| unsafe { x86::sse2(bases, in_gap) }, 16);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn avx2_agrees_with_scalar() {
+        if !(is_x86_feature_detected!("avx2") && is_x86_feature_detected!("popcnt")) {
+            return;
+        }
+        // SAFETY: the features of the kernel were just detected
+        assert_agrees(|bases, in_gap| unsafe { x86::avx2(bases, in_gap) }, 32);
+    }
+}
@@ -0,0 +1,96 @@
+//! BUSCO short summaries, matched to the assemblies they were computed for.
+
+use crate::{fasta, gfa, reader, Invalid, InvalidInput, SendError};
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+/// Gene completeness of one assembly, as percentages of the BUSCO groups
+/// searched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Busco {
+    pub complete: f64,
+    pub single_copy: f64,
+    pub duplicated: f64,
+    pub fragmented: f64,
+    pub missing: f64,
+    /// Number of BUSCO groups searched.
+    pub total: u64,
+}
+
+/// Whether `path` is named like a BUSCO short summary.
+pub fn is_summary(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("short_summary") && name.ends_with(".txt"))
+}
+
+/// Short summaries directly inside `dir`, in name order. Those of the
+/// `run_*` subdirectories of a BUSCO run repeat the one beside them.
+pub fn summaries_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && is_summary(&path) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads a `short_summary*.txt`, returning the name of the assembly it was
+/// computed for with its completeness.
+pub fn read_summary(path: &Path) -> Result<(String, Busco), SendError> {
+    let invalid = |kind, message: &str| InvalidInput::new(kind, format!("{}: {}", path.display(), message));
+    let mut assembly = None;
+    let mut busco = None;
+    for line in reader::open(path)?.lines() {
+        let line = line?;
+        let line = line.trim();
+        if let Some(file) = line.strip_prefix("# Summarized benchmarking in BUSCO notation for file ") {
+            assembly = Path::new(file.trim()).file_name().map(|name| name.to_string_lossy().into_owned());
+        } else if line.starts_with("C:") {
+            busco = Some(parse_notation(line).ok_or_else(|| invalid(Invalid::UnparseableRow, &format!("could not read {line:?}")))?);
+        }
+    }
+    let busco = busco.ok_or_else(|| invalid(Invalid::MissingColumn, "no line in BUSCO notation, such as C:98.5%[S:97.9%,D:0.6%],F:0.4%,M:1.1%,n:5286"))?;
+    let assembly = assembly.or_else(|| name_from_path(path)).ok_or_else(|| invalid(Invalid::MissingColumn, "no assembly file named"))?;
+    Ok((assembly, busco))
+}
+
+/// Reads `C:98.5%[S:97.9%,D:0.6%],F:0.4%,M:1.1%,n:5286`.
+fn parse_notation(line: &str) -> Option<Busco> {
+    let field = |key: &str| -> Option<f64> {
+        let start = line.find(key)? + key.len();
+        let number: String = line[start..].chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+        number.parse().ok()
+    };
+    Some(Busco {
+        complete: field("C:")?,
+        single_copy: field("S:")?,
+        duplicated: field("D:")?,
+        fragmented: field("F:")?,
+        missing: field("M:")?,
+        total: field("n:")? as u64,
+    })
+}
+
+/// Assembly of a summary named `short_summary.specific.<lineage>.<name>.txt`
+/// by BUSCO 5.
+fn name_from_path(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?.strip_prefix("short_summary.")?.strip_suffix(".txt")?;
+    let mut parts = name.splitn(3, '.');
+    let (_kind, _lineage, assembly) = (parts.next()?, parts.next()?, parts.next()?);
+    Some(assembly.to_string())
+}
+
+/// Name of an assembly without directories, compression or FASTA or GFA
+/// extension, under which summaries and records are matched.
+pub fn key(name: &str) -> &str {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let name = [".gz", ".bgz"].iter().find_map(|ext| name.strip_suffix(ext)).unwrap_or(name);
+    match name.rsplit_once('.') {
+        Some((stem, _)) if fasta::is_fasta(Path::new(name)) || gfa::is_gfa(Path::new(name)) => stem,
+        _ => name,
+    }
+}
@@ -0,0 +1,93 @@
+//! Sidecar caches of the statistics computed from FASTA files, written next
+//! to each file as `genome.fa.stats.json` and reused while the file keeps its
+//! size and modification time, so that plotting a directory of genomes again
+//! does not read them again.
+
+use crate::fasta::Assembly;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Suffix appended to the name of a FASTA file to name its cache.
+const SUFFIX: &str = ".stats.json";
+
+/// Bumped whenever the cached statistics change, so that older caches are
+/// computed again.
+const VERSION: u32 = 1;
+
+/// Contents of a cache file.
+#[derive(Serialize, Deserialize)]
+struct Sidecar {
+    version: u32,
+    /// Size and modification time, in seconds and nanoseconds since the
+    /// epoch, of the file when its statistics were computed.
+    size: u64,
+    modified: (u64, u32),
+    lengths: Vec<u64>,
+    gc_count: u64,
+    n_count: u64,
+    gap_count: u64,
+    /// JSON has no NaN, which sequences without bases have.
+    sequence_gc: Vec<Option<f64>>,
+}
+
+/// Whether `path` is the cache of a FASTA file, which inputs skip.
+pub fn is_cache(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(SUFFIX))
+}
+
+fn cache_path(path: &Path) -> PathBuf {
+    let mut cache = path.as_os_str().to_owned();
+    cache.push(SUFFIX);
+    PathBuf::from(cache)
+}
+
+/// Size and modification time of `path`.
+fn stamp(path: &Path) -> io::Result<(u64, (u64, u32))> {
+    let metadata = std::fs::metadata(path)?;
+    let since_epoch = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Ok((metadata.len(), (since_epoch.as_secs(), since_epoch.subsec_nanos())))
+}
+
+/// Statistics of `path` from its cache, named `name`, when it has one that
+/// is still valid.
+pub fn read(path: &Path, name: &str) -> Option<Assembly> {
+    let sidecar: Sidecar = serde_json::from_slice(&std::fs::read(cache_path(path)).ok()?).ok()?;
+    let (size, modified) = stamp(path).ok()?;
+    if sidecar.version != VERSION || sidecar.size != size || sidecar.modified != modified {
+        return None;
+    }
+    Some(Assembly {
+        name: name.to_string(),
+        lengths: sidecar.lengths,
+        gc_count: sidecar.gc_count,
+        n_count: sidecar.n_count,
+        gap_count: sidecar.gap_count,
+        sequence_gc: sidecar.sequence_gc.into_iter().map(|gc| gc.unwrap_or(f64::NAN)).collect(),
+    })
+}
+
+/// Writes the cache of `path`, from which `assembly` was computed, through a
+/// temporary file so that concurrent runs never read half of one.
+pub fn write(path: &Path, assembly: &Assembly) -> io::Result<()> {
+    let (size, modified) = stamp(path)?;
+    let sidecar = Sidecar {
+        version: VERSION,
+        size,
+        modified,
+        lengths: assembly.lengths.clone(),
+        gc_count: assembly.gc_count,
+        n_count: assembly.n_count,
+        gap_count: assembly.gap_count,
+        sequence_gc: assembly.sequence_gc.iter().map(|&gc| (!gc.is_nan()).then_some(gc)).collect(),
+    };
+    let cache = cache_path(path);
+    let mut partial = cache.clone().into_os_string();
+    partial.push(format!(".{}", std::process::id()));
+    let written = std::fs::write(&partial, serde_json::to_vec(&sidecar)?).and_then(|()| std::fs::rename(&partial, &cache));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    written
+}
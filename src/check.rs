@@ -0,0 +1,114 @@
+//! Checking inputs without drawing them, for pipelines that stop on a table
+//! that could not be plotted: why an input could not be read, told apart from
+//! other failures, and the values read that cannot be right.
+
+use crate::{Dataset, GenomeStats};
+use std::fmt;
+
+/// Why an input is not a table of statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invalid {
+    /// A required column, or one named to group, color or plot by, is not
+    /// in the header.
+    MissingColumn,
+    /// A row could not be read, or one of its values parsed.
+    UnparseableRow,
+    /// No records were read from the inputs.
+    Empty,
+    /// Values were read that are out of their range, such as a GC content
+    /// over 100%.
+    OutOfRange,
+}
+
+/// An input that is not a table of statistics, and why.
+#[derive(Debug, Clone)]
+pub struct InvalidInput {
+    pub kind: Invalid,
+    message: String,
+}
+
+impl InvalidInput {
+    pub fn new(kind: Invalid, message: impl Into<String>) -> Self {
+        InvalidInput { kind, message: message.into() }
+    }
+}
+
+impl fmt::Display for InvalidInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for InvalidInput {}
+
+/// Checks that `dataset` has records and that their values are within their
+/// ranges, returning each value that is not, as a line naming its input,
+/// record and column.
+pub fn check_dataset(dataset: &Dataset) -> Result<(), Vec<InvalidInput>> {
+    let data = dataset.records();
+    if data.is_empty() {
+        return Err(vec![InvalidInput::new(Invalid::Empty, "the input contains no records")]);
+    }
+    let problems: Vec<InvalidInput> = data
+        .iter()
+        .enumerate()
+        .flat_map(|(row, record)| {
+            let at = match &record.source {
+                Some(source) => format!("{}: {}", source, record.name(row)),
+                None => record.name(row),
+            };
+            out_of_range(record).into_iter().map(move |problem| {
+                InvalidInput::new(Invalid::OutOfRange, format!("{at}: {problem}"))
+            })
+        })
+        .collect();
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(problems),
+    }
+}
+
+/// The values of `record` out of their range. NaN stands for a value not
+/// known, such as the GC content of a table of lengths, and is never out of
+/// range.
+fn out_of_range(record: &GenomeStats) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut at_least = |column: &str, value: Option<f64>, min: f64| match value {
+        Some(value) if value.is_infinite() || value < min => {
+            problems.push(format!("{column} is {value}, less than {min}"))
+        }
+        _ => {}
+    };
+    at_least("assembly_length", Some(record.assembly_length), 0.0);
+    at_least("number_of_sequences", Some(record.number_of_sequences), 0.0);
+    at_least("N50", Some(record.n50), 0.0);
+    at_least("auN", record.aun, 0.0);
+    at_least("L50", record.l50, 0.0);
+    at_least("L90", record.l90, 0.0);
+    at_least("largest_contig", record.largest_contig, 0.0);
+    at_least("shortest_contig", record.shortest_contig, 0.0);
+    at_least("Gaps", record.gaps, 0.0);
+    at_least("gap_length", record.gap_length, 0.0);
+    for (column, value) in [("GC_percentage", record.gc_percentage), ("N_percentage", record.n_percentage)] {
+        if !value.is_nan() && !(0.0..=100.0).contains(&value) {
+            problems.push(format!("{column} is {value}, not between 0 and 100"));
+        }
+    }
+    if record.n50 > record.assembly_length {
+        problems.push(format!("N50 is {}, longer than the assembly of {}", record.n50, record.assembly_length));
+    }
+    if let Some(largest) = record.largest_contig.filter(|&largest| largest > record.assembly_length) {
+        problems.push(format!("largest_contig is {largest}, longer than the assembly of {}", record.assembly_length));
+    }
+    if let (Some(shortest), Some(largest)) = (record.shortest_contig, record.largest_contig) {
+        if shortest > largest {
+            problems.push(format!("shortest_contig is {shortest}, longer than the largest of {largest}"));
+        }
+    }
+    let mut extra: Vec<_> = record.extra.iter().filter(|(_, value)| !value.is_finite()).collect();
+    extra.sort_by(|a, b| a.0.cmp(b.0));
+    for (column, value) in extra {
+        problems.push(format!("{column} is {value}"));
+    }
+    problems
+}
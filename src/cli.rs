@@ -0,0 +1,761 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use count_fasta_plots::{
+    fitted_height, Bootstrap, Correlation, HexColor, LegendPosition, LoadOptions, Locale, Metric, Missing, Notation, OutlierRule, OutputFormat, PlotConfig, PlotType, QuantileMethod, QuartilePosition, Style, Theme,
+};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Boxplots of assembly statistics produced by count-fasta-rs.
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write a single HTML file with the figures, summary statistics,
+    /// outliers and a sortable table of the samples, to share as is
+    Report(Args),
+    /// Browse and sort the samples in the terminal, choose the metrics and
+    /// render the figure on demand
+    #[cfg(feature = "tui")]
+    Explore(Args),
+    /// Print the statistics of each sample as text, as assembly-stats does,
+    /// or with --aggregate the summary of each metric per group
+    Stats(StatsArgs),
+}
+
+#[derive(Debug, clap::Args)]
+struct StatsArgs {
+    /// Print the summary of every metric of each group, or of all samples,
+    /// instead of a block per sample
+    #[arg(long)]
+    aggregate: bool,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+/// What a run produces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Figure,
+    Report,
+    #[cfg(feature = "tui")]
+    Explore,
+    /// Statistics printed as text, a block per sample or per group when
+    /// `aggregate`.
+    Stats { aggregate: bool },
+}
+
+/// What --background leaves behind the charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Background {
+    Transparent,
+    Color(HexColor),
+}
+
+impl Cli {
+    /// Options of the run, with the mode given by the subcommand and the
+    /// --sqlite database among the inputs.
+    pub fn into_args(self) -> Args {
+        let mut args = match self.command {
+            Some(Command::Report(args)) => Args { mode: Mode::Report, ..args },
+            #[cfg(feature = "tui")]
+            Some(Command::Explore(args)) => Args { mode: Mode::Explore, ..args },
+            Some(Command::Stats(StatsArgs { aggregate, args })) => Args { mode: Mode::Stats { aggregate }, ..args },
+            None => self.args,
+        };
+        args.inputs.extend(args.sqlite.clone());
+        args
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    /// CSV files produced by count-fasta-rs ("-" reads one from the standard
+    /// input), FASTA or GFA files whose statistics are computed directly,
+    /// directories of these, or glob patterns such as 'results/*/stats.csv'
+    #[arg(required_unless_present = "sqlite")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Also read the statistics from the --table of this SQLite database
+    /// (built with the sqlite feature)
+    #[arg(long, value_name = "DB", requires = "table")]
+    pub sqlite: Option<PathBuf>,
+
+    /// Table the statistics of SQLite inputs are read from, one row per
+    /// assembly with columns named as those of a CSV
+    #[arg(long, value_name = "NAME")]
+    pub table: Option<String>,
+
+    /// Also write the summary statistics of every metric into this table of
+    /// the --sqlite database, replacing it
+    #[arg(long, value_name = "NAME", requires = "sqlite")]
+    pub summary_table: Option<String>,
+
+    /// Path of the image to write, a template such as
+    /// "{stem}_{date}_{metric}.png" with the stem of the first input, the
+    /// date, and one file per metric or per {group} [default:
+    /// count-fasta.png, or count-fasta-report.html for a report]
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Also write summary statistics of every metric, as JSON for a `.json`
+    /// path and as CSV otherwise
+    #[arg(long, value_name = "PATH")]
+    pub stats_out: Option<PathBuf>,
+
+    /// Also write a report of every sample outside the usual range of a
+    /// metric in its group, as judged by --outlier-rule, as JSON for a
+    /// `.json` path and as CSV otherwise
+    #[arg(long, value_name = "PATH")]
+    pub flag_outliers: Option<PathBuf>,
+
+    /// Rule by which --flag-outliers judges a value an outlier
+    #[arg(long, value_enum, default_value_t = OutlierRule::Tukey)]
+    pub outlier_rule: OutlierRule,
+
+    /// Threshold of --outlier-rule: IQR past the quartiles for tukey and
+    /// z-score for zscore and mad [default: 1.5, 3 and 3.5]
+    #[arg(long, value_name = "F", value_parser = parse_scale)]
+    pub outlier_threshold: Option<f64>,
+
+    /// Also write, as JSON, rank tests of whether the groups of every metric
+    /// differ, as --significance draws them, with pairwise tests for more
+    /// than two groups
+    #[arg(long, value_name = "PATH")]
+    pub significance_out: Option<PathBuf>,
+
+    /// Also write a Markdown page with the summary statistics of every
+    /// metric and the figures embedded, for a wiki or lab notebook
+    #[arg(long, value_name = "PATH")]
+    pub markdown: Option<PathBuf>,
+
+    /// Color scheme of the figure, adjusted by --config
+    #[arg(long, value_enum, default_value_t = Theme::Light)]
+    pub theme: Theme,
+
+    /// Background of the figure, "transparent" to drop it onto slides of any
+    /// color or a color such as "#1e1e1e"; overrides the theme
+    #[arg(long, value_name = "transparent|#RRGGBB", value_parser = parse_background)]
+    pub background: Option<Background>,
+
+    /// TOML file with captions, colors, margins, fonts and axis ranges
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Title above the whole figure, e.g. "Lepidoptera assemblies 2024-06"
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Caption of the panel of a metric or column, as METRIC=TEXT, e.g.
+    /// "n50=Contig N50" (repeat for more panels)
+    #[arg(long, value_name = "METRIC=TEXT", value_parser = parse_caption)]
+    pub caption: Vec<(Metric, String)>,
+
+    /// Line written below the figure, for run metadata
+    #[arg(long)]
+    pub footer: Option<String>,
+
+    /// Font family of all text, e.g. "DejaVu Serif"; families that are not
+    /// installed are drawn with a bundled sans-serif font
+    #[arg(long, value_name = "FAMILY")]
+    pub font: Option<String>,
+
+    /// TrueType or OpenType file to draw the text from, instead of looking up
+    /// --font on the system
+    #[arg(long, value_name = "PATH")]
+    pub font_file: Option<PathBuf>,
+
+    /// Size of tick, axis and legend labels in points; captions keep their
+    /// size relative to it
+    #[arg(long, value_name = "PT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub font_size: Option<u32>,
+
+    /// Language whose decimal mark and thousands separator tick labels and
+    /// annotations are written with, e.g. "de" for 1.234.567 or "fr"; by
+    /// default numbers are written as 1234567.5
+    #[arg(long, value_name = "LOCALE", value_parser = |s: &str| Locale::try_from(s.to_string()))]
+    pub locale: Option<Locale>,
+
+    /// Where legends are drawn, in a corner of the plot, outside it on the
+    /// right, or not at all; each kind of chart has its own default corner
+    #[arg(long, value_enum, value_name = "POSITION")]
+    pub legend: Option<LegendPosition>,
+
+    /// Image format; guessed from the output extension when omitted
+    #[arg(short, long)]
+    pub format: Option<OutputFormat>,
+
+    /// Image width in pixels
+    #[arg(long, default_value_t = 800)]
+    pub width: u32,
+
+    /// Image height in pixels; by default 224 per metric panel (1120 per
+    /// ranked or dot chart), or 1120 for PDF pages and single-chart plot types
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Scale the image and everything drawn in it by this factor, e.g. 3 for
+    /// a print version of a screen-sized figure; overrides the `scale` of
+    /// --config
+    #[arg(long, value_parser = parse_scale)]
+    pub scale: Option<f64>,
+
+    /// Kind of plot drawn in every panel
+    #[arg(short, long, value_enum, default_value_t = PlotType::Boxplot)]
+    pub plot_type: PlotType,
+
+    /// Number of bins of histogram panels
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u16).range(1..))]
+    pub bins: u16,
+
+    /// Draw the boxplot on top of violin panels
+    #[arg(long)]
+    pub violin_box: bool,
+
+    /// Multiply the bandwidth of the density and violin estimates, from
+    /// Silverman's rule of thumb unless set per metric by --config, e.g. 0.5
+    /// for more detail or 2 for smoother curves
+    #[arg(long, value_name = "F", default_value_t = 1.0, value_parser = parse_scale)]
+    pub bandwidth_adjust: f64,
+
+    /// Overlay every sample as a jittered point on boxplots and violins
+    #[arg(long)]
+    pub points: bool,
+
+    /// Seed of the jitter of --points: the same seed draws every point in
+    /// the same place on every run and machine, another moves them
+    #[arg(long, default_value_t = 0, requires = "points")]
+    pub seed: u64,
+
+    /// Mark the mean of each box with a diamond
+    #[arg(long)]
+    pub mean: bool,
+
+    /// Notch each box around the median to show a 95% confidence interval
+    /// of the median
+    #[arg(long)]
+    pub notch: bool,
+
+    /// Bootstrap the confidence interval of each median from N resamples,
+    /// drawn as the notches of --notch and as an error bar otherwise, and
+    /// exported by --stats-out
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub bootstrap: Option<u32>,
+
+    /// Confidence level of --bootstrap intervals
+    #[arg(long, value_name = "LEVEL", default_value_t = 0.95, value_parser = parse_level)]
+    pub confidence: f64,
+
+    /// Definition of the sample quartiles, numbered as in R's quantile()
+    #[arg(long, value_enum, default_value_t = QuantileMethod::R7)]
+    pub quantile_method: QuantileMethod,
+
+    /// How `--plot-type correlation` measures the correlation of two metrics
+    #[arg(long, value_enum, default_value_t = Correlation::Pearson)]
+    pub correlation: Correlation,
+
+    /// Draw the standardized metrics of each sample as a heatmap next to
+    /// `--plot-type dendrogram`
+    #[arg(long)]
+    pub heatmap: bool,
+
+    /// Do not write file names next to outliers
+    #[arg(long = "no-outlier-labels", action = clap::ArgAction::SetFalse)]
+    pub outlier_labels: bool,
+
+    /// Write the values of the quartiles and median of each box above it, or
+    /// below it
+    #[arg(long, value_name = "POSITION", num_args = 0..=1, default_missing_value = "above")]
+    pub quartile_labels: Option<QuartilePosition>,
+
+    /// Font size of the --quartile-labels, that of the outlier labels by
+    /// default
+    #[arg(long, value_name = "PT", requires = "quartile_labels")]
+    pub quartile_label_size: Option<u32>,
+
+    /// Test whether the groups of each grouped boxplot differ, by
+    /// Mann-Whitney U for two groups and Kruskal-Wallis for more, and print
+    /// the p-value on the panel
+    #[arg(long)]
+    pub significance: bool,
+
+    /// CSV column whose values split each panel into one box per group;
+    /// "source" groups by the file each record was read from
+    #[arg(short, long, value_name = "COLUMN")]
+    pub group_by: Option<String>,
+
+    /// Draw one box per input, named after the file, instead of pooling the
+    /// inputs, e.g. to compare the runs of two assemblers
+    #[arg(long, conflicts_with = "group_by")]
+    pub compare: bool,
+
+    /// CSV column whose values color the points of --points and of the
+    /// scatter plots, with a legend, e.g. to tell ONT from HiFi assemblies;
+    /// "source" colors by the file each record was read from
+    #[arg(long, value_name = "COLUMN")]
+    pub color_by: Option<String>,
+
+    /// Print compact Unicode boxplots, or histograms, to the standard output
+    /// instead of writing an image
+    #[arg(long)]
+    pub terminal: bool,
+
+    /// Write one image per metric, named like count-fasta_n50.png, or
+    /// n50.png inside --output when it is a directory
+    #[arg(long)]
+    pub split: bool,
+
+    /// Panels per page or image as ROWSxCOLS, e.g. 3x2; panels that do not
+    /// fit flow onto further PDF pages, or images named like
+    /// count-fasta_page2.png. By default images hold a single column of every
+    /// panel and PDF pages one panel each
+    #[arg(long, alias = "page-grid", value_name = "ROWSxCOLS", value_parser = parse_grid)]
+    pub grid: Option<(usize, usize)>,
+
+    /// Cells of the grid covered by the panel of a metric, as
+    /// METRIC=ROWSxCOLS, e.g. "n50=1x2" for a panel twice as wide (repeat for
+    /// more panels)
+    #[arg(long, value_name = "METRIC=ROWSxCOLS", value_parser = parse_span)]
+    pub span: Vec<(Metric, (usize, usize))>,
+
+    /// Field delimiter of the input CSV (a single character, or "tab");
+    /// guessed among ";", "," and tab when omitted
+    #[arg(short, long, value_parser = parse_delimiter)]
+    pub delimiter: Option<u8>,
+
+    /// Comma-separated list of metrics to plot, in panel order [default:
+    /// assembly_length,number_of_sequences,n50,gc_percentage,n_percentage, or
+    /// read_length,mean_quality with --reads]
+    #[arg(short, long, value_delimiter = ',')]
+    pub metrics: Vec<Metric>,
+
+    /// Read a CSV header as one of the expected columns, e.g.
+    /// "Scaffold N50=N50" (repeat for more headers); common spellings such
+    /// as n50, sum_len or GC(%) are recognised anyway
+    #[arg(long, value_name = "HEADER=COLUMN", value_parser = parse_alias)]
+    pub alias: Vec<(String, String)>,
+
+    /// What to do with rows that leave a numeric column empty
+    #[arg(long, value_enum, default_value_t = Missing::Skip)]
+    pub missing: Missing,
+
+    /// Numeric CSV column plotted in a panel of its own after the metrics,
+    /// captioned after its header (repeat for more columns)
+    #[arg(short = 'x', long = "extra-column", value_name = "HEADER")]
+    pub extra_columns: Vec<String>,
+
+    /// BUSCO short_summary*.txt files, directories holding them or glob
+    /// patterns, matched to the assemblies by name; adds panels of the
+    /// complete, fragmented and missing percentages (repeat for more paths)
+    #[arg(long, value_name = "PATH")]
+    pub busco: Vec<PathBuf>,
+
+    /// Only plot the sample of this name, with or without directories and
+    /// FASTA extension, e.g. to overlay the curves of a few assemblies
+    /// (repeat for more samples)
+    #[arg(long = "sample", value_name = "NAME")]
+    pub samples: Vec<String>,
+
+    /// Read FASTQ files, or directories of them, one record per read, and
+    /// plot their length and mean quality with one box per file
+    #[arg(long, conflicts_with_all = ["group_by", "busco"])]
+    pub reads: bool,
+
+    /// Read the inputs in a single pass, keeping a quantile sketch of each
+    /// metric instead of every row, for tables too large for memory such as
+    /// read-level statistics; only boxplots are drawn, without their
+    /// outliers, and the quartiles of large groups are estimates. JSON
+    /// tables, and those that may be QUAST reports, are still read whole
+    #[arg(
+        long,
+        conflicts_with_all = ["points", "significance", "bootstrap", "flag_outliers", "significance_out", "markdown", "terminal", "samples", "busco"]
+    )]
+    pub stream: bool,
+
+    /// Comma-separated list of metrics drawn on a log10 axis
+    #[arg(long, value_delimiter = ',', value_name = "METRICS")]
+    pub log: Vec<Metric>,
+
+    /// Print tick labels as plain numbers instead of 15 Mb or 2.5k
+    #[arg(long)]
+    pub raw_ticks: bool,
+
+    /// About how many ticks the value axis of a metric has, as METRIC=N,
+    /// e.g. "gc_percentage=4" (repeat for more panels)
+    #[arg(long, value_name = "METRIC=N", value_parser = parse_count)]
+    pub ticks: Vec<(Metric, usize)>,
+
+    /// Decimal places of the tick labels of a metric, as METRIC=N, e.g.
+    /// "gc_percentage=1" (repeat for more panels)
+    #[arg(long, value_name = "METRIC=N", value_parser = parse_count)]
+    pub decimals: Vec<(Metric, usize)>,
+
+    /// How the tick labels of a metric are written, as METRIC=NOTATION with
+    /// units (15 Mb), plain (15000000), scientific (1.5×10⁷), e (1.5e7) or si
+    /// (15M), e.g. "n50=scientific" (repeat for more panels)
+    #[arg(long, value_name = "METRIC=NOTATION", value_parser = parse_notation)]
+    pub notation: Vec<(Metric, Notation)>,
+
+    /// Write the same bytes for the same inputs on any machine, for diffing
+    /// figures in regression tests: text is drawn with the bundled font, a
+    /// {date} in --output is that of SOURCE_DATE_EPOCH, and terminal
+    /// output is 80 columns wide
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Read and check the inputs without drawing them, exiting with 3 when a
+    /// column is missing, 4 when a row cannot be parsed, 5 when no records
+    /// are read and 6 when values are out of range, such as a GC content
+    /// over 100%
+    #[arg(long, conflicts_with_all = ["watch", "follow", "serve", "terminal"])]
+    pub check: bool,
+
+    /// Keep running and render again whenever an input changes
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Read the single input as it is written to, a CSV appended to or JSON
+    /// Lines piped to the standard input, and render again every --interval
+    /// seconds while records arrive
+    #[arg(long, conflicts_with_all = ["watch", "stream", "terminal"])]
+    pub follow: bool,
+
+    /// Serve the figure, drawn again from the inputs when the page is
+    /// loaded, and the report at /report over HTTP on this address, such as
+    /// 0.0.0.0:8080, instead of writing them
+    #[arg(long, value_name = "ADDRESS", conflicts_with_all = ["watch", "follow", "stream", "terminal"])]
+    pub serve: Option<String>,
+
+    /// Largest table, in MiB, that may be POSTed to /render of --serve
+    #[arg(long, value_name = "MIB", default_value_t = 8, requires = "serve")]
+    pub max_body: u64,
+
+    /// Seconds between the renders of --follow
+    #[arg(long, value_name = "SECONDS", default_value_t = 5.0, value_parser = parse_seconds, requires = "follow")]
+    pub interval: f64,
+
+    /// Number of threads used to read inputs and draw panels (0 uses every core)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    pub threads: usize,
+
+    /// Log the files read and written, the rows skipped and how long each
+    /// step took on stderr; -vv also logs how each input is read (RUST_LOG,
+    /// as "count_fasta_plots=debug", overrides the level)
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only print errors, without the warnings about skipped rows
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Do not draw progress bars while reading the inputs; they are never
+    /// drawn when stderr is not a terminal, or with --verbose or --quiet
+    #[arg(long)]
+    pub no_progress: bool,
+
+    /// Compute the statistics of FASTA files again rather than reuse the
+    /// genome.fa.stats.json cache next to each, and write none
+    #[arg(long)]
+    pub no_cache: bool,
+
+    #[arg(skip)]
+    pub mode: Mode,
+}
+
+impl Args {
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            delimiter: self.delimiter,
+            group_by: self.group_by.clone(),
+            group_by_input: self.compare,
+            color_by: self.color_by.clone(),
+            extra_columns: self.extra_columns.clone(),
+            aliases: self.alias.clone(),
+            missing: self.missing,
+            progress: !self.no_progress && !self.quiet && self.verbose == 0,
+            busco: self.busco.clone(),
+            reads: self.reads,
+            samples: self.samples.clone(),
+            cache: !self.no_cache,
+            table: self.table.clone(),
+            // The statistics printed are all of them, base composition and gaps
+            // included, which a FASTA index does not have
+            lengths_only: !matches!(self.mode, Mode::Stats { .. })
+                && !matches!(self.plot_type, PlotType::SequenceGc | PlotType::Snail)
+                && !self.metrics().iter().any(|metric| {
+                    matches!(
+                        metric,
+                        Metric::GcPercentage | Metric::NPercentage | Metric::GapCount | Metric::GapLength | Metric::MeanGap
+                    )
+                }),
+        }
+    }
+
+    /// The selected metrics, or the default ones of assemblies or reads,
+    /// followed by the BUSCO results, when given, and the extra columns,
+    /// without repetitions, in the order first given.
+    pub fn metrics(&self) -> Vec<Metric> {
+        let selected = if !self.metrics.is_empty() {
+            self.metrics.clone()
+        } else if self.reads {
+            vec![Metric::ReadLength, Metric::MeanQuality]
+        } else {
+            PlotConfig::default().metrics
+        };
+        let busco = match self.busco.is_empty() {
+            true => Vec::new(),
+            false => vec![Metric::BuscoComplete, Metric::BuscoFragmented, Metric::BuscoMissing],
+        };
+        let columns = self.extra_columns.iter().map(|header| Metric::Column(header.clone()));
+        let mut metrics = Vec::new();
+        for metric in selected.into_iter().chain(busco).chain(columns) {
+            if !metrics.contains(&metric) {
+                metrics.push(metric);
+            }
+        }
+        metrics
+    }
+
+    pub fn output(&self) -> PathBuf {
+        let default = if self.mode == Mode::Report { "count-fasta-report.html" } else { "count-fasta.png" };
+        let output = self.output.clone().unwrap_or_else(|| default.into());
+        let template = output.to_string_lossy();
+        if !template.contains("{stem}") && !template.contains("{date}") {
+            return output;
+        }
+        // {metric} and {group} are left to each figure
+        let input = self.inputs.first().map(PathBuf::as_path).unwrap_or(Path::new("stdin"));
+        // Both extensions of compressed inputs such as genome.fa.gz
+        let compressed = input.extension().is_some_and(|ext| ext == "gz" || ext == "bgz");
+        let uncompressed = if compressed { Path::new(input.file_stem().unwrap_or_default()) } else { input };
+        let stem = uncompressed.file_stem().unwrap_or_default().to_string_lossy();
+        PathBuf::from(template.replace("{stem}", &stem).replace("{date}", &today()))
+    }
+
+    /// Bootstrap of the confidence intervals of the medians, when asked for.
+    pub fn bootstrap(&self) -> Option<Bootstrap> {
+        self.bootstrap.map(|resamples| Bootstrap { resamples: resamples as usize, level: self.confidence })
+    }
+
+    /// Height of a figure of `panels` rows of metrics: the one given, or the
+    /// [`fitted_height`]. Reports draw every metric in a figure of its own.
+    pub fn figure_height(&self, panels: usize) -> u32 {
+        let format = self.format.unwrap_or_else(|| OutputFormat::from_path(&self.output()));
+        match self.height {
+            Some(height) => height,
+            None if self.mode == Mode::Report => fitted_height(self.plot_type, OutputFormat::Html, true, None, panels),
+            None => fitted_height(self.plot_type, format, self.split, self.grid, panels),
+        }
+    }
+
+    pub fn plot_config(&self) -> Result<PlotConfig, Box<dyn Error>> {
+        let dated = self.output.as_ref().is_some_and(|output| output.to_string_lossy().contains("{date}"));
+        if self.deterministic && dated && source_date_epoch().is_none() {
+            return Err("--deterministic needs SOURCE_DATE_EPOCH for the {date} of --output".into());
+        }
+        let mut style = match &self.config {
+            Some(path) => Style::from_file(path, self.theme.style())?,
+            None => self.theme.style(),
+        };
+        if let Some(scale) = self.scale {
+            style.scale = scale;
+        }
+        if self.title.is_some() {
+            style.title.clone_from(&self.title);
+        }
+        if self.footer.is_some() {
+            style.footer.clone_from(&self.footer);
+        }
+        if self.legend.is_some() {
+            style.legend = self.legend;
+        }
+        match self.background {
+            Some(Background::Transparent) => style.transparent = true,
+            Some(Background::Color(color)) => {
+                style.background = color;
+                style.transparent = false;
+            }
+            None => {}
+        }
+        if let Some(locale) = self.locale {
+            style.locale = locale;
+        }
+        if let Some(font) = &self.font {
+            style.font.clone_from(font);
+        }
+        if self.font_file.is_some() {
+            style.font_file.clone_from(&self.font_file);
+        }
+        if let Some(size) = self.font_size {
+            style.caption_size = style.caption_size * size / style.label_size.max(1);
+            style.label_size = size;
+        }
+        if let Some(position) = self.quartile_labels {
+            style.quartile_labels.position = position;
+        }
+        if self.quartile_label_size.is_some() {
+            style.quartile_labels.size = self.quartile_label_size;
+        }
+        for (metric, caption) in &self.caption {
+            style.metrics.entry(metric.clone()).or_default().caption = Some(caption.clone());
+        }
+        for (metric, span) in &self.span {
+            style.metrics.entry(metric.clone()).or_default().span = Some(*span);
+        }
+        for (metric, ticks) in &self.ticks {
+            style.metrics.entry(metric.clone()).or_default().ticks = Some(*ticks);
+        }
+        for (metric, decimals) in &self.decimals {
+            style.metrics.entry(metric.clone()).or_default().decimals = Some(*decimals);
+        }
+        for (metric, notation) in &self.notation {
+            style.metrics.entry(metric.clone()).or_default().notation = Some(*notation);
+        }
+        Ok(PlotConfig {
+            output: self.output(),
+            format: self.format,
+            width: self.width,
+            height: self.figure_height(self.metrics().iter().map(|metric| style.span(metric).0).sum()),
+            plot_type: self.plot_type,
+            bins: self.bins,
+            violin_box: self.violin_box,
+            bandwidth_adjust: self.bandwidth_adjust,
+            points: self.points,
+            seed: self.seed,
+            mean: self.mean,
+            notch: self.notch,
+            bootstrap: self.bootstrap(),
+            quantile_method: self.quantile_method,
+            correlation: self.correlation,
+            heatmap: self.heatmap,
+            outlier_labels: self.outlier_labels,
+            quartile_labels: self.quartile_labels.is_some(),
+            significance: self.significance,
+            split: self.split,
+            grid: self.grid,
+            metrics: self.metrics(),
+            log: self.log.clone(),
+            style,
+            raw_ticks: self.raw_ticks,
+            deterministic: self.deterministic,
+        })
+    }
+}
+
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s {
+        "tab" | "\\t" | "\t" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err(format!("expected a single ASCII character, got {s:?}")),
+    }
+}
+
+fn parse_alias(s: &str) -> Result<(String, String), String> {
+    let (header, column) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected HEADER=COLUMN such as \"Scaffold N50=N50\", got {s:?}"))?;
+    Ok((header.to_string(), column.to_string()))
+}
+
+fn parse_caption(s: &str) -> Result<(Metric, String), String> {
+    let (metric, caption) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected METRIC=TEXT such as \"n50=Contig N50\", got {s:?}"))?;
+    Ok((Metric::from(metric.to_string()), caption.to_string()))
+}
+
+fn parse_span(s: &str) -> Result<(Metric, (usize, usize)), String> {
+    let (metric, span) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected METRIC=ROWSxCOLS such as \"n50=1x2\", got {s:?}"))?;
+    Ok((Metric::from(metric.to_string()), parse_grid(span)?))
+}
+
+fn parse_count(s: &str) -> Result<(Metric, usize), String> {
+    let (metric, count) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected METRIC=N such as \"gc_percentage=4\", got {s:?}"))?;
+    let count = count.trim().parse().map_err(|_| format!("expected a whole number after =, got {count:?}"))?;
+    Ok((Metric::from(metric.to_string()), count))
+}
+
+fn parse_notation(s: &str) -> Result<(Metric, Notation), String> {
+    let (metric, notation) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected METRIC=NOTATION such as \"n50=scientific\", got {s:?}"))?;
+    Ok((Metric::from(metric.to_string()), Notation::from_str(notation, true)?))
+}
+
+/// Today's date in UTC, as 2024-06-12, or that of `SOURCE_DATE_EPOCH` when
+/// it is set, as for reproducible builds.
+fn today() -> String {
+    let seconds = match source_date_epoch() {
+        Some(seconds) => seconds,
+        None => SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()),
+    };
+    // Days since 1970-01-01 to a civil date, after Howard Hinnant
+    let z = (seconds / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Seconds since the epoch in `SOURCE_DATE_EPOCH`, when set to a number.
+fn source_date_epoch() -> Option<u64> {
+    std::env::var("SOURCE_DATE_EPOCH").ok().and_then(|seconds| seconds.trim().parse().ok())
+}
+
+fn parse_background(s: &str) -> Result<Background, String> {
+    if s.eq_ignore_ascii_case("transparent") {
+        return Ok(Background::Transparent);
+    }
+    HexColor::try_from(s.to_string()).map(Background::Color)
+}
+
+fn parse_scale(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(scale) if scale > 0.0 && scale.is_finite() => Ok(scale),
+        _ => Err(format!("expected a positive factor such as 2 or 0.5, got {s:?}")),
+    }
+}
+
+fn parse_seconds(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(seconds),
+        _ => Err(format!("expected a positive number of seconds such as 5 or 0.5, got {s:?}")),
+    }
+}
+
+fn parse_level(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(level) if level > 0.0 && level < 1.0 => Ok(level),
+        _ => Err(format!("expected a level between 0 and 1 such as 0.95, got {s:?}")),
+    }
+}
+
+fn parse_grid(s: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("expected ROWSxCOLS such as 2x1, got {s:?}");
+    let (rows, cols) = s.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let rows: usize = rows.trim().parse().map_err(|_| invalid())?;
+    let cols: usize = cols.trim().parse().map_err(|_| invalid())?;
+    if rows == 0 || cols == 0 {
+        return Err(invalid());
+    }
+    Ok((rows, cols))
+}
@@ -0,0 +1,123 @@
+//! Average linkage (UPGMA) clustering of samples by the Euclidean distance
+//! of their standardized metrics.
+
+use crate::{pca, GenomeStats, Metric};
+
+/// Joining of two clusters, numbered like the samples for single ones and
+/// from the number of samples up in the order of the merges for the others.
+#[derive(Debug, Clone, Copy)]
+pub struct Merge {
+    pub left: usize,
+    pub right: usize,
+    /// Mean distance between the samples of the two clusters.
+    pub height: f64,
+}
+
+/// Samples clustered by their standardized metrics.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    /// Indices in the data of the samples that have every metric.
+    pub rows: Vec<usize>,
+    /// Z-scores of the metrics of each of those samples.
+    pub scores: Vec<Vec<f64>>,
+    pub merges: Vec<Merge>,
+    /// Row of each of those samples counting from the bottom, so that the
+    /// first leaf is at the top.
+    pub places: Vec<usize>,
+}
+
+/// Clusters the samples of `data` that have every one of `metrics`.
+pub fn tree(data: &[GenomeStats], metrics: &[Metric]) -> Result<Tree, String> {
+    let (rows, scores) = pca::standardize(data, metrics);
+    if rows.len() < 2 {
+        return Err(format!("a dendrogram needs at least two samples with every metric, got {}", rows.len()));
+    }
+    let merges = cluster(&scores);
+    let n = rows.len();
+    let mut places = vec![0; n];
+    for (place, leaf) in leaf_order(&merges, n).into_iter().enumerate() {
+        places[leaf] = n - 1 - place;
+    }
+    Ok(Tree { rows, scores, merges, places })
+}
+
+/// Merges that join `points` into a single cluster, closest first.
+pub fn cluster(points: &[Vec<f64>]) -> Vec<Merge> {
+    let n = points.len();
+    let distance = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt();
+    // Live clusters as their number, size and distances to every other one
+    let mut ids: Vec<usize> = (0..n).collect();
+    let mut sizes = vec![1.0; n];
+    let mut distances: Vec<Vec<f64>> = points.iter().map(|a| points.iter().map(|b| distance(a, b)).collect()).collect();
+    let mut live: Vec<usize> = (0..n).collect();
+
+    let mut merges = Vec::with_capacity(n.saturating_sub(1));
+    while live.len() > 1 {
+        let (mut a, mut b, mut closest) = (0, 1, f64::INFINITY);
+        for (i, &x) in live.iter().enumerate() {
+            for &y in &live[i + 1..] {
+                if distances[x][y] < closest {
+                    (a, b, closest) = (x, y, distances[x][y]);
+                }
+            }
+        }
+        merges.push(Merge { left: ids[a], right: ids[b], height: closest });
+        // The merged cluster takes the place of a
+        for &x in &live {
+            let merged = (distances[a][x] * sizes[a] + distances[b][x] * sizes[b]) / (sizes[a] + sizes[b]);
+            distances[a][x] = merged;
+            distances[x][a] = merged;
+        }
+        sizes[a] += sizes[b];
+        ids[a] = n + merges.len() - 1;
+        live.retain(|&x| x != b);
+    }
+    merges
+}
+
+/// Samples in the order of the leaves of the dendrogram of `merges`, which
+/// keeps every cluster contiguous.
+pub fn leaf_order(merges: &[Merge], samples: usize) -> Vec<usize> {
+    let mut order = Vec::with_capacity(samples);
+    if samples == 0 {
+        return order;
+    }
+    let mut stack = vec![samples + merges.len() - 1];
+    while let Some(node) = stack.pop() {
+        if node < samples {
+            order.push(node);
+        } else {
+            let merge = merges[node - samples];
+            stack.push(merge.right);
+            stack.push(merge.left);
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgma_of_four_samples() {
+        // Two pairs, 1 and 3 apart, whose samples are 9 to 13 apart: 11 on
+        // average
+        let points = vec![vec![10.0], vec![0.0], vec![13.0], vec![1.0]];
+        let merges = cluster(&points);
+        let joined: Vec<(usize, usize, f64)> = merges.iter().map(|m| (m.left, m.right, m.height)).collect();
+        assert_eq!(joined, [(1, 3, 1.0), (0, 2, 3.0), (5, 4, 11.0)]);
+        assert_eq!(leaf_order(&merges, 4), [0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn averages_over_cluster_sizes() {
+        // The pair at 0 and 2 is 4 and 6 from the third
+        let merges = cluster(&[vec![0.0], vec![2.0], vec![6.0]]);
+        let joined: Vec<(usize, usize, f64)> = merges.iter().map(|m| (m.left, m.right, m.height)).collect();
+        assert_eq!(joined, [(0, 1, 2.0), (3, 2, 5.0)]);
+        assert_eq!(leaf_order(&merges, 3), [0, 1, 2]);
+        assert!(leaf_order(&[], 0).is_empty());
+        assert_eq!(leaf_order(&[], 1), [0]);
+    }
+}
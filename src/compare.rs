@@ -0,0 +1,341 @@
+//! Rank tests of whether the groups of a metric differ: Mann-Whitney U for
+//! two groups and Kruskal-Wallis for more, with pairwise Mann-Whitney tests
+//! adjusted by Holm's method.
+
+use crate::correlation::ranks;
+use crate::{GenomeStats, Locale, Metric};
+use serde::Serialize;
+use std::error::Error;
+use std::path::Path;
+
+/// Test run on the groups of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Test {
+    MannWhitney,
+    KruskalWallis,
+}
+
+impl Test {
+    /// Name as printed on the panels.
+    pub fn name(self) -> &'static str {
+        match self {
+            Test::MannWhitney => "Mann-Whitney U",
+            Test::KruskalWallis => "Kruskal-Wallis",
+        }
+    }
+}
+
+/// Mann-Whitney test of one pair of groups among more.
+#[derive(Debug, Serialize)]
+pub struct Pairwise<'a> {
+    pub a: &'a str,
+    pub b: &'a str,
+    /// U of the first group.
+    pub u: f64,
+    pub p_value: f64,
+    /// p-value adjusted by Holm's method over every pair of the metric.
+    pub p_adjusted: f64,
+}
+
+/// Result of the test of the groups of one metric.
+#[derive(Debug, Serialize)]
+pub struct Comparison<'a> {
+    pub metric: &'a Metric,
+    pub test: Test,
+    /// Groups with at least one value, and their number of values.
+    pub groups: Vec<&'a str>,
+    pub n: Vec<usize>,
+    /// U of the first group, or the tie-corrected H.
+    pub statistic: f64,
+    pub p_value: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pairwise: Vec<Pairwise<'a>>,
+}
+
+/// Tests whether `metric` differs between `groups` of `data`, or `None` when
+/// fewer than two groups have a value of it.
+pub fn compare<'a>(data: &[GenomeStats], metric: &'a Metric, groups: &[&'a str]) -> Option<Comparison<'a>> {
+    let samples: Vec<(&str, Vec<f64>)> = groups
+        .iter()
+        .map(|&group| {
+            let values = data.iter().filter(|x| x.group_name() == group).map(|x| metric.value(x));
+            (group, values.filter(|v| v.is_finite()).collect::<Vec<f64>>())
+        })
+        .filter(|(_, values)| !values.is_empty())
+        .collect();
+    if samples.len() < 2 {
+        return None;
+    }
+    let values: Vec<&[f64]> = samples.iter().map(|(_, values)| &values[..]).collect();
+    let (test, statistic, p_value) = match values.len() {
+        2 => {
+            let (u, p) = mann_whitney(values[0], values[1]);
+            (Test::MannWhitney, u, p)
+        }
+        _ => {
+            let (h, p) = kruskal_wallis(&values);
+            (Test::KruskalWallis, h, p)
+        }
+    };
+
+    let mut pairwise = Vec::new();
+    if test == Test::KruskalWallis {
+        for i in 0..samples.len() {
+            for j in i + 1..samples.len() {
+                let (u, p_value) = mann_whitney(values[i], values[j]);
+                pairwise.push(Pairwise { a: samples[i].0, b: samples[j].0, u, p_value, p_adjusted: p_value });
+            }
+        }
+        holm(&mut pairwise);
+    }
+    Some(Comparison {
+        metric,
+        test,
+        groups: samples.iter().map(|(group, _)| *group).collect(),
+        n: values.iter().map(|values| values.len()).collect(),
+        statistic,
+        p_value,
+        pairwise,
+    })
+}
+
+/// Stars of a p-value, as conventionally printed over brackets.
+pub fn stars(p: f64) -> &'static str {
+    match p {
+        p if p < 0.001 => "***",
+        p if p < 0.01 => "**",
+        p if p < 0.05 => "*",
+        _ => "ns",
+    }
+}
+
+/// A p-value as printed on the panels, such as `p = 0.012 *`, with the
+/// decimal mark of `locale`.
+pub fn describe(p: f64, locale: Locale) -> String {
+    if p.is_nan() {
+        "p = NA".to_string()
+    } else if p < 0.001 {
+        format!("p < {} {}", locale.number("0.001"), stars(p))
+    } else {
+        format!("p = {} {}", locale.number(&format!("{:.3}", p)), stars(p))
+    }
+}
+
+/// Writes the tests of every one of `metrics` between `groups` as JSON and
+/// returns how many metrics could be tested.
+pub fn write_comparisons(
+    path: &Path,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    groups: &[&str],
+) -> Result<usize, Box<dyn Error>> {
+    let comparisons: Vec<Comparison> = metrics.iter().filter_map(|metric| compare(data, metric, groups)).collect();
+    let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    serde_json::to_writer_pretty(out, &comparisons)?;
+    Ok(comparisons.len())
+}
+
+/// U of `a` and the two-sided p-value of the normal approximation, with
+/// continuity and tie corrections.
+fn mann_whitney(a: &[f64], b: &[f64]) -> (f64, f64) {
+    let pooled: Vec<f64> = a.iter().chain(b).copied().collect();
+    let ranks = ranks(&pooled);
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let u = ranks[..a.len()].iter().sum::<f64>() - n1 * (n1 + 1.0) / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties(&pooled) / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return (u, f64::NAN);
+    }
+    let z = ((u - n1 * n2 / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+    (u, erfc(z / std::f64::consts::SQRT_2).min(1.0))
+}
+
+/// Tie-corrected H of `groups` and its p-value from the chi-squared
+/// distribution with one degree of freedom less than there are groups.
+fn kruskal_wallis(groups: &[&[f64]]) -> (f64, f64) {
+    let pooled: Vec<f64> = groups.iter().flat_map(|values| values.iter().copied()).collect();
+    let ranks = ranks(&pooled);
+    let n = pooled.len() as f64;
+    let mut start = 0;
+    let mut sum = 0.0;
+    for values in groups {
+        let rank_sum: f64 = ranks[start..start + values.len()].iter().sum();
+        sum += rank_sum * rank_sum / values.len() as f64;
+        start += values.len();
+    }
+    let correction = 1.0 - ties(&pooled) / (n * n * n - n);
+    if correction <= 0.0 {
+        return (f64::NAN, f64::NAN);
+    }
+    let h = (12.0 / (n * (n + 1.0)) * sum - 3.0 * (n + 1.0)) / correction;
+    let df = (groups.len() - 1) as f64;
+    (h, gamma_q(df / 2.0, h.max(0.0) / 2.0))
+}
+
+/// Sum of t³ - t over the runs of t tied values.
+fn ties(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+        .chunk_by(|a, b| a == b)
+        .map(|run| run.len() as f64)
+        .map(|t| t * t * t - t)
+        .sum()
+}
+
+/// Holm's step-down adjustment of the p-values of `tests`.
+fn holm(tests: &mut [Pairwise]) {
+    let m = tests.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| tests[a].p_value.total_cmp(&tests[b].p_value));
+    let mut running: f64 = 0.0;
+    for (k, &i) in order.iter().enumerate() {
+        running = running.max(((m - k) as f64 * tests[i].p_value).min(1.0));
+        tests[i].p_adjusted = running;
+    }
+}
+
+/// Complementary error function, to about seven significant digits
+/// (Numerical Recipes, 6.2).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Upper regularized incomplete gamma function Q(a, x), by its series below
+/// a + 1 and its continued fraction above (Numerical Recipes, 6.2).
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut ap) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..500 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * prefactor).max(0.0)
+    } else {
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = if d.abs() < tiny { tiny } else { d };
+            c = b + an / c;
+            c = if c.abs() < tiny { tiny } else { c };
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        prefactor * h
+    }
+}
+
+/// Logarithm of the gamma function, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000000000190015;
+    for (j, c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + j as f64);
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "{actual}, not {expected}");
+    }
+
+    #[test]
+    fn mann_whitney_as_scipy() {
+        // scipy.stats.mannwhitneyu(a, b, method="asymptotic")
+        let (u, p) = mann_whitney(&[19.0, 22.0, 16.0, 29.0, 24.0], &[20.0, 11.0, 17.0, 12.0]);
+        assert_eq!(u, 17.0);
+        assert_close(p, 0.11134688653314041, 1e-6);
+        // With tied ranks within and across the groups
+        let (u, p) = mann_whitney(&[1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 5.0], &[2.0, 3.0, 4.0, 4.0, 5.0, 6.0, 6.0, 7.0]);
+        assert_eq!(u, 10.0);
+        assert_close(p, 0.03971608699701139, 1e-6);
+    }
+
+    #[test]
+    fn mann_whitney_of_identical_values() {
+        let (u, p) = mann_whitney(&[4.0, 4.0], &[4.0, 4.0, 4.0]);
+        assert_eq!(u, 3.0);
+        assert!(p.is_nan());
+    }
+
+    #[test]
+    fn kruskal_wallis_as_scipy() {
+        // scipy.stats.kruskal(*groups)
+        let (h, p) = kruskal_wallis(&[&[1.0, 3.0, 5.0, 7.0, 9.0], &[2.0, 4.0, 6.0, 8.0, 10.0]]);
+        assert_close(h, 0.2727272727272734, 1e-12);
+        assert_close(p, 0.6015081344405895, 1e-6);
+        let (h, p) = kruskal_wallis(&[&[1.0, 1.0, 1.0], &[2.0, 2.0, 2.0], &[2.0, 2.0]]);
+        assert_close(h, 7.0, 1e-12);
+        assert_close(p, 0.0301973834223185, 1e-9);
+    }
+
+    #[test]
+    fn holm_steps_down() {
+        let mut tests: Vec<Pairwise> = [0.01, 0.04, 0.03, 0.005]
+            .into_iter()
+            .map(|p_value| Pairwise { a: "a", b: "b", u: 0.0, p_value, p_adjusted: f64::NAN })
+            .collect();
+        holm(&mut tests);
+        // p.adjust(c(0.01, 0.04, 0.03, 0.005), "holm") in R
+        let adjusted: Vec<f64> = tests.iter().map(|test| test.p_adjusted).collect();
+        for (actual, expected) in adjusted.into_iter().zip([0.03, 0.06, 0.06, 0.02]) {
+            assert_close(actual, expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn special_functions() {
+        assert_close(erfc(0.0), 1.0, 1e-7);
+        assert_close(erfc(1.0), 0.157299207050285, 1e-7);
+        assert_close(erfc(-1.0), 1.842700792949715, 1e-7);
+        assert_close(erfc(3.0), 2.209049699858544e-5, 1e-9);
+        // Q(1, x) is e^-x, and Q(1/2, x) is erfc(√x)
+        for x in [0.1, 1.0, 2.5, 10.0] {
+            assert_close(gamma_q(1.0, x), (-x).exp(), 1e-10);
+            assert_close(gamma_q(0.5, x), erfc(x.sqrt()), 1e-7);
+        }
+        assert_close(gamma_q(0.5, 1.0), 0.157299207050285, 1e-9);
+        assert_close(gamma_q(0.5, 4.0), 0.004677734981047266, 1e-9);
+        assert_eq!(gamma_q(2.0, 0.0), 1.0);
+    }
+}
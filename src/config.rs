@@ -0,0 +1,257 @@
+//! Options of a figure, independent of how they were given.
+
+use crate::{Bootstrap, Correlation, Metric, QuantileMethod, Style};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+/// Everything that decides what a rendered figure looks like and where it is
+/// written.
+#[derive(Debug, Clone)]
+pub struct PlotConfig {
+    /// Path of the file to write; a `{metric}` or `{group}` in it writes one
+    /// file per metric or per group of samples, named after each.
+    pub output: PathBuf,
+    /// Format of the file; guessed from the extension of `output` when `None`.
+    pub format: Option<OutputFormat>,
+    pub width: u32,
+    pub height: u32,
+    pub plot_type: PlotType,
+    /// Number of bins of histogram panels, at least one.
+    pub bins: u16,
+    /// Draw the boxplot on top of violin panels.
+    pub violin_box: bool,
+    /// Factor applied to the rule-of-thumb bandwidth of the density
+    /// estimates of density and violin panels, below one for more detail.
+    pub bandwidth_adjust: f64,
+    /// Overlay every sample as a jittered point on boxplots and violins.
+    pub points: bool,
+    /// Where the offsets of jittered points start, so that the same seed puts
+    /// every point in the same place.
+    pub seed: u64,
+    /// Mark the mean of each box with a diamond.
+    pub mean: bool,
+    /// Notch each box around the median to show its confidence interval.
+    pub notch: bool,
+    /// Bootstrap the confidence interval of each median, drawn as the notches
+    /// of notched boxes and as an error bar otherwise.
+    pub bootstrap: Option<Bootstrap>,
+    /// How the quartiles of each box are estimated.
+    pub quantile_method: QuantileMethod,
+    /// How the correlation heatmap measures the correlation of two metrics.
+    pub correlation: Correlation,
+    /// Draw the standardized metrics as a heatmap next to the dendrogram.
+    pub heatmap: bool,
+    /// Write the file name of each outlier next to it, when it has one.
+    pub outlier_labels: bool,
+    /// Write the values of the quartiles and median of each box next to it,
+    /// where the style has them.
+    pub quartile_labels: bool,
+    /// Test whether the groups of each boxplot panel differ and print the
+    /// p-value on the panel.
+    pub significance: bool,
+    /// Write one figure per metric, next to `output` or inside it when it is
+    /// a directory, instead of stacking the panels.
+    pub split: bool,
+    /// Rows and columns of panels on each page, the panels that do not fit
+    /// flowing onto further PDF pages or image files; by default one column
+    /// of every panel, or one panel per PDF page.
+    pub grid: Option<(usize, usize)>,
+    /// Metrics to plot, in panel order.
+    pub metrics: Vec<Metric>,
+    /// Metrics drawn on a log10 axis, besides those marked `log` in the style.
+    pub log: Vec<Metric>,
+    /// Captions, colors, margins and fonts.
+    pub style: Style,
+    /// Print tick labels as plain numbers instead of `15 Mb` or `2.5k`.
+    pub raw_ticks: bool,
+    /// Draw text with the bundled font, whatever the system has installed,
+    /// so that the same inputs draw the same bytes on any machine.
+    pub deterministic: bool,
+}
+
+impl Default for PlotConfig {
+    fn default() -> Self {
+        PlotConfig {
+            output: PathBuf::from("count-fasta.png"),
+            format: None,
+            width: 800,
+            height: 1120,
+            plot_type: PlotType::Boxplot,
+            bins: 30,
+            violin_box: false,
+            bandwidth_adjust: 1.0,
+            points: false,
+            seed: 0,
+            mean: false,
+            notch: false,
+            bootstrap: None,
+            quantile_method: QuantileMethod::default(),
+            correlation: Correlation::default(),
+            heatmap: false,
+            outlier_labels: true,
+            quartile_labels: false,
+            significance: false,
+            split: false,
+            grid: None,
+            metrics: vec![
+                Metric::AssemblyLength,
+                Metric::NumberOfSequences,
+                Metric::N50,
+                Metric::GcPercentage,
+                Metric::NPercentage,
+            ],
+            log: Vec::new(),
+            style: Style::default(),
+            raw_ticks: false,
+            deterministic: false,
+        }
+    }
+}
+
+impl PlotConfig {
+    /// Whether the panel of `metric` has a log10 value axis.
+    pub fn is_log(&self, metric: &Metric) -> bool {
+        self.log.contains(metric) || self.style.metrics.get(metric).is_some_and(|m| m.log)
+    }
+}
+
+/// How the distribution of each metric is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PlotType {
+    /// Quartiles, whiskers and outliers
+    Boxplot,
+    /// Counts of samples in equally wide bins, see --bins
+    Histogram,
+    /// Kernel density estimate mirrored around the axis, see --violin-box
+    Violin,
+    /// Kernel density estimate of each group, overlaid, see --bandwidth-adjust
+    Density,
+    /// Kernel density estimate of each group on a row of its own, the rows
+    /// overlapping, which stays legible for many groups
+    Ridgeline,
+    /// Value of every sample as a bar, sorted from the highest, to rank
+    /// assemblies by a metric
+    Ranked,
+    /// Cleveland dot plot of the value of every sample, sorted from the
+    /// highest, which stays legible for hundreds of samples
+    Dots,
+    /// Empirical cumulative distribution, one curve per group, to read off
+    /// the share of samples up to any value
+    Ecdf,
+    /// Scatter matrix of every pair of metrics instead of one panel each
+    Pairs,
+    /// Nx curve of every FASTA input, overlaid in a single chart
+    Nx,
+    /// Cumulative length against sequence rank of every FASTA input
+    Cumulative,
+    /// Distribution of the sequence lengths of every FASTA input, as the
+    /// share of sequences up to each length on a log axis
+    Lengths,
+    /// GC ratio of the sequences of every FASTA input, one box per input
+    SequenceGc,
+    /// One rectangle per sequence of every FASTA input, sized by its length
+    Treemap,
+    /// Circular summary of every FASTA input after BlobToolKit, with its Nx
+    /// curve, sequence count and GC content
+    Snail,
+    /// Heatmap of the correlation of every pair of metrics across samples,
+    /// see --correlation
+    Correlation,
+    /// Samples on the first two principal components of the standardized
+    /// metrics, with the weight of each metric as an arrow
+    Pca,
+    /// Tree of the samples clustered by their standardized metrics, see
+    /// --heatmap
+    Dendrogram,
+}
+
+impl PlotType {
+    /// Whether the plot fills the figure on its own rather than being drawn
+    /// once per metric panel.
+    pub fn is_whole_figure(self) -> bool {
+        matches!(
+            self,
+            PlotType::Pairs
+                | PlotType::Nx
+                | PlotType::Cumulative
+                | PlotType::Lengths
+                | PlotType::SequenceGc
+                | PlotType::Treemap
+                | PlotType::Snail
+                | PlotType::Correlation
+                | PlotType::Pca
+                | PlotType::Dendrogram
+        )
+    }
+}
+
+/// Default height of one metric panel, a fifth of the default figure.
+const PANEL_HEIGHT: u32 = 224;
+
+/// Default height of one ranked bar or dot chart, with room for the names of
+/// some eighty samples.
+const RANKED_HEIGHT: u32 = 5 * PANEL_HEIGHT;
+
+/// Height of a figure of `panels` rows of metrics that keeps panels the same
+/// size whatever their number, as many rows as `grid` has when given or a
+/// single one when `split`, and 1120 for PDF pages and single-chart plot
+/// types.
+pub fn fitted_height(
+    plot_type: PlotType,
+    format: OutputFormat,
+    split: bool,
+    grid: Option<(usize, usize)>,
+    panels: usize,
+) -> u32 {
+    let panel = match plot_type {
+        PlotType::Ranked | PlotType::Dots | PlotType::Ridgeline => RANKED_HEIGHT,
+        _ => PANEL_HEIGHT,
+    };
+    if format == OutputFormat::Pdf || plot_type.is_whole_figure() {
+        1120
+    } else if split {
+        panel
+    } else if let Some((rows, _)) = grid {
+        panel * rows as u32
+    } else {
+        panel * panels as u32
+    }
+}
+
+/// Backends the figure can be rendered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Raster image through the bitmap backend
+    Png,
+    /// Scalable vector graphics
+    Svg,
+    /// Multi-page PDF, see --grid
+    Pdf,
+    /// Interactive page rendered with plotly.js, with per-sample tooltips
+    Html,
+    /// Vega-Lite specification of the boxplots, with their statistics
+    /// precomputed, to restyle or embed
+    Vega,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Svg => "svg",
+            OutputFormat::Pdf => "pdf",
+            OutputFormat::Html => "html",
+            OutputFormat::Vega => "json",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("svg") => OutputFormat::Svg,
+            Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
+            Some(ext) if ext.eq_ignore_ascii_case("html") => OutputFormat::Html,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => OutputFormat::Vega,
+            _ => OutputFormat::Png,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Correlations between metrics across samples.
+
+use crate::{GenomeStats, Metric};
+use clap::ValueEnum;
+
+/// How the correlation of two metrics is measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Correlation {
+    /// Linear correlation of the values
+    #[default]
+    Pearson,
+    /// Pearson correlation of the ranks, which catches any monotonic relation
+    /// and shrugs off outliers
+    Spearman,
+}
+
+impl Correlation {
+    /// Caption of a heatmap of such correlations.
+    pub fn caption(self) -> &'static str {
+        match self {
+            Correlation::Pearson => "Pearson correlation",
+            Correlation::Spearman => "Spearman correlation",
+        }
+    }
+}
+
+/// Correlation of `x` and `y` over the samples where both are known; NaN when
+/// fewer than three are or either is constant over them.
+pub fn correlation(x: &[f64], y: &[f64], method: Correlation) -> f64 {
+    let (x, y): (Vec<f64>, Vec<f64>) = x
+        .iter()
+        .zip(y)
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .map(|(x, y)| (*x, *y))
+        .unzip();
+    if x.len() < 3 {
+        return f64::NAN;
+    }
+    match method {
+        Correlation::Pearson => pearson(&x, &y),
+        Correlation::Spearman => pearson(&ranks(&x), &ranks(&y)),
+    }
+}
+
+/// Correlation of every pair of `metrics` over `data`, as rows in the order
+/// of `metrics`.
+pub fn matrix(data: &[GenomeStats], metrics: &[Metric], method: Correlation) -> Vec<Vec<f64>> {
+    let values: Vec<Vec<f64>> = metrics
+        .iter()
+        .map(|metric| data.iter().map(|x| metric.value(x)).collect())
+        .collect();
+    values
+        .iter()
+        .map(|x| values.iter().map(|y| correlation(x, y, method)).collect())
+        .collect()
+}
+
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in x.iter().zip(y) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        covariance += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return f64::NAN;
+    }
+    covariance / (var_x * var_y).sqrt()
+}
+
+/// Ranks of `values` from one, tied values sharing the mean of their ranks.
+pub(crate) fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
@@ -0,0 +1,43 @@
+//! Gaussian kernel density estimation.
+
+use statistical::{mean, standard_deviation};
+use std::f64::consts::PI;
+
+/// Bandwidth from Silverman's rule of thumb, which is robust to skewed data
+/// by taking the smaller of the standard deviation and the scaled IQR.
+/// Returns `None` when the data has no spread to estimate a density from.
+pub fn silverman_bandwidth(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let sd = standard_deviation(&sorted, Some(mean(&sorted)));
+    let iqr = sorted[sorted.len() * 3 / 4] - sorted[sorted.len() / 4];
+    let spread = if iqr > 0.0 { sd.min(iqr / 1.34) } else { sd };
+    let bandwidth = 0.9 * spread * (sorted.len() as f64).powf(-0.2);
+    (bandwidth > 0.0).then_some(bandwidth)
+}
+
+/// Density of `values` at each point of `grid`.
+pub fn gaussian_kde(values: &[f64], bandwidth: f64, grid: &[f64]) -> Vec<f64> {
+    let norm = 1.0 / (values.len() as f64 * bandwidth * (2.0 * PI).sqrt());
+    grid.iter()
+        .map(|&x| {
+            let sum: f64 = values
+                .iter()
+                .map(|&v| {
+                    let u = (x - v) / bandwidth;
+                    (-0.5 * u * u).exp()
+                })
+                .sum();
+            sum * norm
+        })
+        .collect()
+}
+
+/// `points` evenly spaced values from `min` to `max`, inclusive.
+pub fn linspace(min: f64, max: f64, points: usize) -> Vec<f64> {
+    let step = (max - min) / (points - 1) as f64;
+    (0..points).map(|i| min + i as f64 * step).collect()
+}
@@ -0,0 +1,101 @@
+//! Options of the figures of programs that embed the library, in the browser
+//! or through the C API, given as a TOML table named as on the command line:
+//!
+//! ```toml
+//! plot_type = "violin"
+//! metrics = ["n50", "gc_percentage"]
+//! group_by = "platform"
+//!
+//! [style]
+//! title = "Assemblies"
+//! ```
+//!
+//! Embedded figures are a single image: the panels that do not fit the grid
+//! are left out.
+
+use crate::{column_grid, fitted_height, pages, LoadOptions, Metric, OutputFormat, PlotConfig, PlotType, Style, Theme};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::error::Error;
+use std::ops::Range;
+
+/// Options of an embedded figure.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Options {
+    plot_type: Option<String>,
+    /// The metrics of the command line when empty.
+    metrics: Vec<Metric>,
+    log: Vec<Metric>,
+    group_by: Option<String>,
+    color_by: Option<String>,
+    grid: Option<(usize, usize)>,
+    points: bool,
+    seed: u64,
+    mean: bool,
+    notch: bool,
+    theme: Option<String>,
+    /// Size of the image, that of the canvas in the browser.
+    width: Option<u32>,
+    height: Option<u32>,
+    /// Whatever a `--config` file holds.
+    style: Option<toml::Table>,
+}
+
+impl Options {
+    /// Options of the TOML table `text`, the defaults when empty.
+    pub(crate) fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// How the table of the figure is read.
+    pub(crate) fn load_options(&self) -> LoadOptions {
+        LoadOptions { group_by: self.group_by.clone(), color_by: self.color_by.clone(), ..LoadOptions::default() }
+    }
+
+    /// Configuration of a figure in `format`, whose height fits its panels
+    /// unless given.
+    pub(crate) fn plot_config(self, format: OutputFormat) -> Result<PlotConfig, Box<dyn Error>> {
+        let theme = match self.theme {
+            Some(theme) => Theme::from_str(&theme, true)?,
+            None => Theme::default(),
+        };
+        let style = match self.style {
+            Some(overrides) => Style::from_toml(&toml::to_string(&overrides)?, theme.style())?,
+            None => theme.style(),
+        };
+        let defaults = PlotConfig::default();
+        let plot_type = match self.plot_type {
+            Some(plot_type) => PlotType::from_str(&plot_type, true)?,
+            None => defaults.plot_type,
+        };
+        let metrics = match self.metrics.is_empty() {
+            true => defaults.metrics.clone(),
+            false => self.metrics,
+        };
+        let panels = metrics.iter().map(|metric| style.span(metric).0).sum();
+        Ok(PlotConfig {
+            format: Some(format),
+            width: self.width.unwrap_or(defaults.width),
+            height: self.height.unwrap_or_else(|| fitted_height(plot_type, format, false, self.grid, panels)),
+            plot_type,
+            metrics,
+            log: self.log,
+            grid: self.grid,
+            points: self.points,
+            seed: self.seed,
+            mean: self.mean,
+            notch: self.notch,
+            style,
+            ..defaults
+        })
+    }
+}
+
+/// The grid of the figure of `config` and the metrics of its first page,
+/// those an embedded figure shows.
+pub(crate) fn first_page(config: &PlotConfig) -> ((usize, usize), Range<usize>) {
+    let grid = config.grid.unwrap_or_else(|| column_grid(&config.metrics, &config.style));
+    (grid, pages(config, grid).into_iter().next().unwrap_or_default())
+}
+
@@ -0,0 +1,228 @@
+//! Interactive table of the samples, for curating large batches: sort by any
+//! metric, choose the metrics of the figure and render it without leaving the
+//! terminal.
+
+use crate::cli::Args;
+use count_fasta_plots::{render_boxplots, Dataset, GenomeStats, Metric, PlotConfig};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::error::Error;
+
+const HELP: &str = "↑↓ move  ←→ column  s sort  1-9 toggle metric  r render  q quit";
+
+/// Column the samples are sorted by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    Group,
+    /// Index into the metrics of the explorer.
+    Metric(usize),
+}
+
+struct Explorer<'a> {
+    args: &'a Args,
+    config: &'a PlotConfig,
+    dataset: &'a Dataset,
+    /// Every metric given on the command line, with whether it is shown.
+    metrics: Vec<(Metric, bool)>,
+    /// Rows of the records, in display order.
+    order: Vec<usize>,
+    grouped: bool,
+    /// Column under the cursor, an index into `columns()`.
+    column: usize,
+    sort: Option<(Column, bool)>,
+    table: TableState,
+    status: String,
+}
+
+/// Loads the inputs and runs the explorer until it is quit.
+pub fn explore(args: &Args, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    let dataset = Dataset::load(&args.inputs, &args.load_options())?;
+    if dataset.is_empty() {
+        return Err("the input contains no records".into());
+    }
+    let mut explorer = Explorer {
+        args,
+        config,
+        dataset: &dataset,
+        metrics: config.metrics.iter().map(|metric| (metric.clone(), true)).collect(),
+        order: (0..dataset.records().len()).collect(),
+        grouped: !dataset.groups().is_empty(),
+        column: 0,
+        sort: None,
+        table: TableState::default().with_selected(0),
+        status: format!("{} samples", dataset.records().len()),
+    };
+    let mut terminal = ratatui::init();
+    let result = explorer.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl Explorer<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn Error>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::PageDown => self.table.scroll_down_by(20),
+                KeyCode::PageUp => self.table.scroll_up_by(20),
+                KeyCode::Home => self.table.select_first(),
+                KeyCode::End => self.table.select_last(),
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.column = (self.column + 1).min(self.columns().len() - 1);
+                }
+                KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+                KeyCode::Char('s') | KeyCode::Enter => self.sort_by(self.columns()[self.column]),
+                KeyCode::Char(c @ '1'..='9') => self.toggle(c as usize - '1' as usize),
+                KeyCode::Char('r') => self.render(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Columns of the table: the name, the group when grouped and the
+    /// metrics shown.
+    fn columns(&self) -> Vec<Column> {
+        let mut columns = vec![Column::Name];
+        if self.grouped {
+            columns.push(Column::Group);
+        }
+        columns.extend((0..self.metrics.len()).filter(|&i| self.metrics[i].1).map(Column::Metric));
+        columns
+    }
+
+    /// Sorts by `column`, in reverse when it is already sorted by it.
+    fn sort_by(&mut self, column: Column) {
+        let descending = self.sort == Some((column, false));
+        let records = self.dataset.records();
+        let name = |row: usize| records[row].name(row);
+        match column {
+            Column::Name => self.order.sort_by_key(|&row| name(row)),
+            Column::Group => self.order.sort_by(|&a, &b| records[a].group_name().cmp(records[b].group_name())),
+            Column::Metric(i) => {
+                let metric = &self.metrics[i].0;
+                // Missing values last
+                let key = |row: usize| {
+                    let value = metric.value(&records[row]);
+                    if value.is_nan() { f64::INFINITY } else { value }
+                };
+                self.order.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+            }
+        }
+        if descending {
+            self.order.reverse();
+        }
+        self.sort = Some((column, descending));
+    }
+
+    fn toggle(&mut self, metric: usize) {
+        if metric >= self.metrics.len() {
+            return;
+        }
+        let shown = self.metrics.iter().filter(|(_, shown)| *shown).count();
+        if self.metrics[metric].1 && shown == 1 {
+            self.status = "at least one metric must be shown".to_string();
+            return;
+        }
+        self.metrics[metric].1 ^= true;
+        self.column = self.column.min(self.columns().len() - 1);
+    }
+
+    /// Writes the figure of the metrics shown.
+    fn render(&mut self) {
+        let metrics: Vec<Metric> =
+            self.metrics.iter().filter(|(_, shown)| *shown).map(|(metric, _)| metric.clone()).collect();
+        let config = PlotConfig {
+            height: self.args.figure_height(metrics.len()),
+            metrics,
+            ..self.config.clone()
+        };
+        self.status = match render_boxplots(self.dataset, &config) {
+            Ok(written) => {
+                let paths: Vec<String> = written.iter().map(|path| path.display().to_string()).collect();
+                format!("Wrote {}", paths.join(", "))
+            }
+            Err(e) => format!("error: {e}"),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [metrics, table, status] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(2)])
+                .areas(frame.area());
+
+        let toggles: Vec<Span> = self
+            .metrics
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (metric, shown))| {
+                let label = format!("{}:{}", i + 1, self.config.style.title(metric));
+                let span = if *shown { Span::raw(label).bold() } else { Span::raw(label).dim() };
+                [span, Span::raw(" ")]
+            })
+            .collect();
+        frame.render_widget(Line::from(toggles), metrics);
+
+        let columns = self.columns();
+        let records = self.dataset.records();
+        let header = columns.iter().enumerate().map(|(i, &column)| {
+            let mut title = match column {
+                Column::Name => "Sample".to_string(),
+                Column::Group => "Group".to_string(),
+                Column::Metric(m) => self.config.style.title(&self.metrics[m].0).into_owned(),
+            };
+            match self.sort {
+                Some((sorted, false)) if sorted == column => title.push_str(" ▲"),
+                Some((sorted, true)) if sorted == column => title.push_str(" ▼"),
+                _ => {}
+            }
+            let style = if i == self.column { Style::new().reversed() } else { Style::new().bold() };
+            Cell::from(title).style(style)
+        });
+        let rows = self.order.iter().map(|&row| {
+            Row::new(columns.iter().map(|&column| cell(&records[row], row, column, &self.metrics)))
+        });
+        let widths = columns.iter().map(|column| match column {
+            Column::Name => Constraint::Min(20),
+            _ => Constraint::Min(10),
+        });
+        let table_widget = Table::new(rows, widths)
+            .header(Row::new(header))
+            .block(Block::new().borders(Borders::TOP | Borders::BOTTOM))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table_widget, table, &mut self.table);
+
+        frame.render_widget(Paragraph::new(vec![Line::from(self.status.as_str()), Line::from(HELP).dim()]), status);
+    }
+}
+
+fn cell<'a>(record: &'a GenomeStats, row: usize, column: Column, metrics: &[(Metric, bool)]) -> Cell<'a> {
+    match column {
+        Column::Name => Cell::from(record.name(row)),
+        Column::Group => Cell::from(record.group_name()),
+        Column::Metric(i) => {
+            let value = metrics[i].0.value(record);
+            let text = if value.is_nan() {
+                String::new()
+            } else if value.fract() == 0.0 {
+                format!("{value:.0}")
+            } else {
+                format!("{value:.2}")
+            };
+            Cell::from(Line::from(text).right_aligned())
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Assembly statistics computed directly from FASTA files.
+
+use crate::{bases, progress, reader, GenomeStats, Invalid, InvalidInput, SendError};
+use memchr::{memchr, memmem};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File extensions recognised as FASTA.
+const FASTA_EXTENSIONS: &[&str] = &["fa", "fasta", "fna", "fas", "ffn", "frn", "mfa"];
+
+/// Sequence lengths and base composition of one FASTA file.
+#[derive(Debug, Default)]
+pub struct Assembly {
+    pub name: String,
+    pub lengths: Vec<u64>,
+    pub gc_count: u64,
+    pub n_count: u64,
+    /// Runs of N, each counted once however long.
+    pub gap_count: u64,
+    /// GC percentage of each sequence, in file order, NaN for those without
+    /// bases.
+    pub sequence_gc: Vec<f64>,
+}
+
+impl Assembly {
+    pub fn total_length(&self) -> u64 {
+        self.lengths.iter().sum()
+    }
+
+    /// Counts the GC, N and gaps of `bases` of a sequence, `in_gap` telling
+    /// whether the bases before them ended in N, and returns their number
+    /// without whitespace.
+    pub fn count_bases(&mut self, bases: &[u8], in_gap: &mut bool) -> u64 {
+        let counts = bases::count(bases, in_gap);
+        self.gc_count += counts.gc;
+        self.n_count += counts.n;
+        self.gap_count += counts.gaps;
+        counts.bases
+    }
+
+    /// Length of the shortest sequence in the set of longest sequences that
+    /// together cover at least half of the assembly.
+    pub fn n50(&self) -> u64 {
+        self.covering(50).0
+    }
+
+    /// Number of the longest sequences that together cover at least
+    /// `percent` of the assembly, 50 for the L50.
+    pub fn lx(&self, percent: u64) -> u64 {
+        self.covering(percent).1
+    }
+
+    fn covering(&self, percent: u64) -> (u64, u64) {
+        covering(&self.sorted_lengths(), percent)
+    }
+
+    /// Ends a sequence of `len` bases, which added `gc` to the GC count.
+    pub fn push_sequence(&mut self, len: u64, gc: u64) {
+        self.lengths.push(len);
+        self.sequence_gc.push(if len > 0 { gc as f64 / len as f64 * 100.0 } else { f64::NAN });
+    }
+
+    /// Area under the Nx curve: the sum of the squared lengths over the total
+    /// length.
+    pub fn aun(&self) -> f64 {
+        let total = self.total_length();
+        if total == 0 {
+            return 0.0;
+        }
+        self.lengths.iter().map(|&len| len as f64 * len as f64).sum::<f64>() / total as f64
+    }
+
+    /// Sequence lengths, longest first.
+    pub fn sorted_lengths(&self) -> Vec<u64> {
+        let mut sorted = self.lengths.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        sorted
+    }
+
+    pub fn stats(&self) -> GenomeStats {
+        let total = self.total_length();
+        // Longest first, in file order among equals, the GC along
+        let mut order: Vec<usize> = (0..self.lengths.len()).collect();
+        order.sort_by(|&a, &b| self.lengths[b].cmp(&self.lengths[a]));
+        let sequence_gc = if self.sequence_gc.len() == self.lengths.len() {
+            order.iter().map(|&i| self.sequence_gc[i]).collect()
+        } else {
+            Vec::new()
+        };
+        let percentage = |count: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f64 / total as f64 * 100.0
+            }
+        };
+        GenomeStats {
+            filename: Some(self.name.clone()),
+            assembly_length: total as f64,
+            number_of_sequences: self.lengths.len() as f64,
+            n50: self.n50() as f64,
+            gc_percentage: percentage(self.gc_count),
+            n_percentage: percentage(self.n_count),
+            aun: Some(self.aun()),
+            l50: Some(self.lx(50) as f64),
+            l90: Some(self.lx(90) as f64),
+            largest_contig: self.lengths.iter().max().map(|&len| len as f64),
+            shortest_contig: self.lengths.iter().min().map(|&len| len as f64),
+            gaps: Some(self.gap_count as f64),
+            gap_length: Some(self.n_count as f64),
+            sequence_gc,
+            group: None,
+            color: None,
+            lengths: order.iter().map(|&i| self.lengths[i]).collect(),
+            extra: HashMap::new(),
+            source: None,
+            busco: None,
+            mean_quality: None,
+        }
+    }
+}
+
+/// Length and number of the longest sequences that together cover at least
+/// `percent` of the assembly with `sorted_lengths`, longest first.
+pub fn covering(sorted_lengths: &[u64], percent: u64) -> (u64, u64) {
+    let target = (sorted_lengths.iter().sum::<u64>() * percent).div_ceil(100);
+    let mut covered = 0;
+    for (i, &len) in sorted_lengths.iter().enumerate() {
+        covered += len;
+        if covered >= target {
+            return (len, i as u64 + 1);
+        }
+    }
+    (0, 0)
+}
+
+/// Steps of the Nx curve of an assembly given its sequence lengths, longest
+/// first: each sequence contributes a flat segment at its length spanning the
+/// percentage of the assembly it covers.
+pub fn nx_curve(sorted_lengths: &[u64]) -> Vec<(f64, f64)> {
+    let total: u64 = sorted_lengths.iter().sum();
+    let mut points = Vec::with_capacity(sorted_lengths.len() * 2);
+    let mut covered = 0;
+    for &len in sorted_lengths {
+        points.push((covered as f64 / total as f64 * 100.0, len as f64));
+        covered += len;
+        points.push((covered as f64 / total as f64 * 100.0, len as f64));
+    }
+    points
+}
+
+/// Cumulative assembly length against the rank of each sequence, longest
+/// first, starting from the origin.
+pub fn cumulative_curve(sorted_lengths: &[u64]) -> Vec<(f64, f64)> {
+    let mut points = Vec::with_capacity(sorted_lengths.len() + 1);
+    points.push((0.0, 0.0));
+    let mut covered = 0;
+    for (rank, &len) in sorted_lengths.iter().enumerate() {
+        covered += len;
+        points.push(((rank + 1) as f64, covered as f64));
+    }
+    points
+}
+
+/// Empirical cumulative distribution of sequence lengths given longest
+/// first: the percentage of sequences at most as long as each length.
+pub fn length_ecdf(sorted_lengths: &[u64]) -> Vec<(f64, f64)> {
+    let n = sorted_lengths.len() as f64;
+    let mut points = Vec::with_capacity(sorted_lengths.len() * 2);
+    for (i, &len) in sorted_lengths.iter().rev().enumerate() {
+        points.push((len as f64, i as f64 / n * 100.0));
+        points.push((len as f64, (i + 1) as f64 / n * 100.0));
+    }
+    points
+}
+
+/// Whether `path` looks like a (possibly compressed) FASTA file judging by its
+/// extension.
+pub fn is_fasta(path: &Path) -> bool {
+    reader::format_extension(path)
+        .is_some_and(|ext| FASTA_EXTENSIONS.iter().any(|f| ext.eq_ignore_ascii_case(f)))
+}
+
+/// Name of the assembly of a FASTA file: its file name.
+pub fn assembly_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// Sequence lengths of a FASTA file from the `.fai` index beside it, as
+/// written by `samtools faidx`, when there is one at least as recent as the
+/// file. The index says nothing of the base composition.
+pub fn read_index(path: &Path) -> Result<Option<Assembly>, SendError> {
+    let mut index = path.as_os_str().to_owned();
+    index.push(".fai");
+    let index = Path::new(&index);
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(index), modified(path)) {
+        (Ok(indexed), Ok(written)) if indexed >= written => {}
+        _ => return Ok(None),
+    }
+    let mut assembly = Assembly { name: assembly_name(path), ..Default::default() };
+    for (i, line) in std::fs::read_to_string(index)?.lines().enumerate() {
+        let length = line.split('\t').nth(1).and_then(|length| length.parse().ok()).ok_or_else(|| {
+            InvalidInput::new(
+                Invalid::UnparseableRow,
+                format!("{}: line {}: no sequence length", index.display(), i + 1),
+            )
+        })?;
+        assembly.lengths.push(length);
+    }
+    Ok(Some(assembly))
+}
+
+/// Files at least this large are mapped into memory and scanned in place
+/// rather than copied line by line into a buffer.
+const MAP_THRESHOLD: u64 = 16 << 20;
+
+/// Bytes scanned between two updates of the progress bar of a mapped file.
+const PROGRESS_STEP: usize = 4 << 20;
+
+pub fn read_fasta(path: &Path) -> Result<Assembly, SendError> {
+    let mut parser = Parser::new(path);
+    if let Some(map) = reader::map(path, MAP_THRESHOLD)? {
+        // Only headers are looked for line by line, so that the sequence
+        // between two is counted in long runs
+        let (mut offset, mut reported) = (0, 0);
+        while offset < map.len() {
+            let rest = &map[offset..];
+            if rest[0] == b'>' {
+                let end = memchr(b'\n', rest).map_or(rest.len(), |i| i + 1);
+                parser.header();
+                offset += end;
+            } else {
+                let end = memmem::find(rest, b"\n>").map_or(rest.len(), |i| i + 1);
+                for block in rest[..end].chunks(PROGRESS_STEP) {
+                    parser.sequence(block)?;
+                }
+                offset += end;
+            }
+            if offset - reported >= PROGRESS_STEP {
+                progress::advance(offset as u64);
+                reported = offset;
+            }
+        }
+    } else {
+        let mut reader = reader::open(path)?;
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            parser.line(&line)?;
+            line.clear();
+        }
+    }
+    Ok(parser.finish())
+}
+
+/// Reading of a FASTA file, fed one line at a time.
+struct Parser<'a> {
+    path: &'a Path,
+    assembly: Assembly,
+    /// Length so far of the sequence being read, if any.
+    current: Option<u64>,
+    in_gap: bool,
+    /// GC count when the current sequence started
+    gc_start: u64,
+}
+
+impl<'a> Parser<'a> {
+    fn new(path: &'a Path) -> Self {
+        Parser { path, assembly: Assembly { name: assembly_name(path), ..Default::default() }, current: None, in_gap: false, gc_start: 0 }
+    }
+
+    fn line(&mut self, line: &[u8]) -> Result<(), InvalidInput> {
+        if line.first() == Some(&b'>') {
+            self.header();
+            Ok(())
+        } else {
+            self.sequence(line)
+        }
+    }
+
+    /// Ends the sequence being read, if any, and starts another.
+    fn header(&mut self) {
+        let assembly = &mut self.assembly;
+        if let Some(len) = self.current.replace(0) {
+            assembly.push_sequence(len, assembly.gc_count - self.gc_start);
+        }
+        self.in_gap = false;
+        self.gc_start = assembly.gc_count;
+    }
+
+    /// Adds `bases`, which may span several lines, to the sequence being
+    /// read.
+    fn sequence(&mut self, bases: &[u8]) -> Result<(), InvalidInput> {
+        let len = self.current.as_mut().ok_or_else(|| {
+            InvalidInput::new(
+                Invalid::UnparseableRow,
+                format!("{}: sequence data before the first header", self.path.display()),
+            )
+        });
+        let bases = self.assembly.count_bases(bases, &mut self.in_gap);
+        if bases > 0 {
+            *len? += bases;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Assembly {
+        if let Some(len) = self.current {
+            self.assembly.push_sequence(len, self.assembly.gc_count - self.gc_start);
+        }
+        self.assembly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Records of 10, 0, 6 and 4 bases, wrapped, with N runs, lowercase and
+    /// CRLF line ends: the first covers exactly half of the 20 bases.
+    const FIXTURE: &str = ">a first\nACGTAC\nGTNN\n>empty\n>b\nnnnnGG\n>c\r\nAT\r\nAT\r\n";
+
+    /// Total, count, N50, L50, L90, auN, shortest length and lengths.
+    type LengthStats = (f64, f64, f64, Option<f64>, Option<f64>, Option<f64>, Option<f64>, Vec<u64>);
+
+    /// The statistics of `stats` that the sequence lengths alone give.
+    fn length_stats(stats: &GenomeStats) -> LengthStats {
+        (
+            stats.assembly_length,
+            stats.number_of_sequences,
+            stats.n50,
+            stats.l50,
+            stats.l90,
+            stats.aun,
+            stats.shortest_contig,
+            stats.lengths.clone(),
+        )
+    }
+
+    fn write(dir: &Path, name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn stats_of_a_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let stats = read_fasta(&write(dir.path(), "fixture.fa", FIXTURE.as_bytes())).unwrap().stats();
+        assert_eq!(stats.filename.as_deref(), Some("fixture.fa"));
+        // Half of 20 is covered by the first sequence alone; 90%, 18 bases,
+        // by all three that have bases
+        assert_eq!(
+            length_stats(&stats),
+            (20.0, 4.0, 10.0, Some(1.0), Some(3.0), Some(7.6), Some(0.0), vec![10, 6, 4, 0])
+        );
+        assert_eq!(stats.largest_contig, Some(10.0));
+        assert_eq!((stats.gc_percentage, stats.n_percentage), (30.0, 30.0));
+        assert_eq!((stats.gaps, stats.gap_length), (Some(2.0), Some(6.0)));
+        assert_eq!(&stats.sequence_gc[..3], [40.0, 2.0 / 6.0 * 100.0, 0.0]);
+        assert!(stats.sequence_gc[3].is_nan());
+    }
+
+    #[test]
+    fn single_contig() {
+        let dir = tempfile::tempdir().unwrap();
+        let stats = read_fasta(&write(dir.path(), "one.fa", b">only\nACGG\n")).unwrap().stats();
+        assert_eq!(length_stats(&stats), (4.0, 1.0, 4.0, Some(1.0), Some(1.0), Some(4.0), Some(4.0), vec![4]));
+        assert_eq!(stats.gc_percentage, 75.0);
+    }
+
+    #[test]
+    fn covering_ties() {
+        assert_eq!(covering(&[5, 5, 5, 5], 50), (5, 2));
+        assert_eq!(covering(&[5, 5, 5, 5], 90), (5, 4));
+        // 12 of 24 is reached exactly by the first two
+        assert_eq!(covering(&[6, 6, 6, 6], 50), (6, 2));
+        assert_eq!(covering(&[7, 3, 3, 1], 50), (7, 1));
+        assert_eq!(covering(&[], 50), (0, 0));
+        assert_eq!(covering(&[0, 0], 50), (0, 1));
+    }
+
+    #[test]
+    fn sequence_data_before_a_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = read_fasta(&write(dir.path(), "headless.fa", b"ACGT\n>a\nACGT\n")).unwrap_err();
+        assert_eq!(error.downcast_ref::<InvalidInput>().map(|e| e.kind), Some(Invalid::UnparseableRow));
+    }
+
+    #[test]
+    fn gzip_and_index_as_plain() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = read_fasta(&write(dir.path(), "plain.fa", FIXTURE.as_bytes())).unwrap().stats();
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(FIXTURE.as_bytes()).unwrap();
+        let gzipped = read_fasta(&write(dir.path(), "plain.fa.gz", &gzip.finish().unwrap())).unwrap().stats();
+        assert_eq!(length_stats(&gzipped), length_stats(&plain));
+        assert_eq!(
+            (gzipped.gc_percentage, gzipped.n_percentage, gzipped.gaps),
+            (plain.gc_percentage, plain.n_percentage, plain.gaps)
+        );
+
+        // As written by samtools faidx, after the FASTA file
+        write(dir.path(), "plain.fa.fai", b"a\t10\t9\t6\t7\nempty\t0\t30\t0\t1\nb\t6\t34\t6\t7\nc\t4\t45\t2\t4\n");
+        let indexed = read_index(&dir.path().join("plain.fa")).unwrap().expect("an index").stats();
+        assert_eq!(length_stats(&indexed), length_stats(&plain));
+    }
+}
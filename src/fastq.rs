@@ -0,0 +1,109 @@
+//! Read sets in FASTQ files, read one record per read to plot the
+//! distributions of read length and quality.
+
+use crate::{reader, GenomeStats, Invalid, InvalidInput, SendError};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+/// File extensions recognised as FASTQ.
+const FASTQ_EXTENSIONS: &[&str] = &["fq", "fastq"];
+
+/// Whether `path` looks like a (possibly compressed) FASTQ file judging by its
+/// extension.
+pub fn is_fastq(path: &Path) -> bool {
+    reader::format_extension(path)
+        .is_some_and(|ext| FASTQ_EXTENSIONS.iter().any(|f| ext.eq_ignore_ascii_case(f)))
+}
+
+/// Name of the read set in `path`, its file name without FASTQ and
+/// compression extensions.
+pub fn set_name(path: &Path) -> String {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut name = name.as_ref();
+    for ext in [".gz", ".bgz", ".fq", ".fastq"] {
+        if name.len() > ext.len() && name.to_ascii_lowercase().ends_with(ext) {
+            name = &name[..name.len() - ext.len()];
+        }
+    }
+    name.to_string()
+}
+
+/// Mean quality of a read with Phred+33 `qualities`, that of its mean error
+/// probability rather than the mean of its scores, which a few good bases
+/// would inflate.
+pub fn mean_quality(qualities: &[u8]) -> f64 {
+    if qualities.is_empty() {
+        return 0.0;
+    }
+    let error: f64 = qualities
+        .iter()
+        .map(|&q| 10f64.powf(-(q.saturating_sub(b'!') as f64) / 10.0))
+        .sum::<f64>()
+        / qualities.len() as f64;
+    -10.0 * error.log10()
+}
+
+/// Reads every four-line record of a FASTQ file as a set of one sequence,
+/// with its mean quality, grouped by the name of the file.
+pub fn read_fastq(path: &Path) -> Result<Vec<GenomeStats>, SendError> {
+    let invalid = |line: u64, message: &str| -> SendError {
+        InvalidInput::new(Invalid::UnparseableRow, format!("{}: line {}: {}", path.display(), line, message)).into()
+    };
+    let name = set_name(path);
+    let mut reader = reader::open(path)?;
+    let mut reads = Vec::new();
+    let (mut header, mut sequence, mut separator, mut qualities) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let mut line = 1;
+    loop {
+        header.clear();
+        if reader.read_until(b'\n', &mut header)? == 0 {
+            break;
+        }
+        if header.first() != Some(&b'@') {
+            return Err(invalid(line, "expected a read header starting with @"));
+        }
+        for buffer in [&mut sequence, &mut separator, &mut qualities] {
+            buffer.clear();
+            reader.read_until(b'\n', buffer)?;
+            while buffer.last().is_some_and(|b| b.is_ascii_whitespace()) {
+                buffer.pop();
+            }
+        }
+        if separator.first() != Some(&b'+') {
+            return Err(invalid(line + 2, "expected a + line after the sequence"));
+        }
+        if qualities.len() != sequence.len() {
+            return Err(invalid(line + 3, "the qualities are not as long as the sequence"));
+        }
+        let length = sequence.len() as f64;
+        let percentage = |count: usize| if sequence.is_empty() { 0.0 } else { count as f64 / length * 100.0 };
+        let gc = sequence.iter().filter(|b| matches!(b, b'G' | b'C' | b'g' | b'c')).count();
+        let n = sequence.iter().filter(|b| matches!(b, b'N' | b'n')).count();
+        reads.push(GenomeStats {
+            filename: None,
+            assembly_length: length,
+            number_of_sequences: 1.0,
+            n50: length,
+            gc_percentage: percentage(gc),
+            n_percentage: percentage(n),
+            aun: None,
+            l50: None,
+            l90: None,
+            largest_contig: None,
+            shortest_contig: None,
+            gaps: None,
+            gap_length: None,
+            group: Some(name.clone()),
+            color: None,
+            lengths: Vec::new(),
+            sequence_gc: Vec::new(),
+            extra: HashMap::new(),
+            source: None,
+            busco: None,
+            mean_quality: Some(mean_quality(&qualities)),
+        });
+        line += 4;
+    }
+    Ok(reads)
+}
@@ -0,0 +1,159 @@
+//! C API, built into the cdylib with the `ffi` feature, for applications that
+//! embed the plotting. `include/count_fasta_plots.h` declares it, generated
+//! with `cbindgen --output include/count_fasta_plots.h`:
+//!
+//! ```c
+//! CfpDataset *dataset = cfp_load("stats.csv", "group_by = \"platform\"");
+//! size_t len = 0;
+//! uint8_t *png = dataset ? cfp_render(dataset, "png", "metrics = [\"n50\"]", &len) : NULL;
+//! if (!png) {
+//!     fprintf(stderr, "%s\n", cfp_last_error());
+//! }
+//! cfp_free_buffer(png, len);
+//! cfp_free_dataset(dataset);
+//! ```
+//!
+//! Options are TOML tables of the options of [`crate::embed`], the defaults
+//! for `NULL`. Functions that fail return `NULL` and leave a message for
+//! [`cfp_last_error`].
+
+use crate::embed::{first_page, Options};
+use crate::{page_file, page_grid, pages, pdf_document, Dataset, OutputFormat, PlotConfig};
+use clap::ValueEnum;
+use std::cell::RefCell;
+use std::error::Error;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+thread_local! {
+    /// Message of the last failure on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records read by [`cfp_load`].
+pub struct CfpDataset(Dataset);
+
+/// The result of `call`, or `None` with its error, or that of a panic, kept
+/// for [`cfp_last_error`].
+fn guard<T>(call: impl FnOnce() -> Result<T, Box<dyn Error>>) -> Option<T> {
+    let message = match panic::catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(value)) => return Some(value),
+        Ok(Err(e)) => e.to_string(),
+        // The message of the panic is on stderr
+        Err(_) => "internal error".to_string(),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message.replace('\0', "")).ok());
+    None
+}
+
+/// The NUL-terminated string at `text`, `default` for `NULL`.
+unsafe fn string(text: *const c_char, default: &str) -> Result<&str, Box<dyn Error>> {
+    match text.is_null() {
+        true => Ok(default),
+        false => Ok(CStr::from_ptr(text).to_str()?),
+    }
+}
+
+/// Contents of the file of the figure of `dataset`: every page of a PDF, the
+/// first of a PNG or SVG.
+fn render(dataset: &Dataset, config: &PlotConfig, format: OutputFormat) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = dataset.records();
+    match format {
+        OutputFormat::Pdf => {
+            let grid = page_grid(config, format);
+            Ok(pdf_document(data, config, &pages(config, grid), 0, grid)?.to_bytes())
+        }
+        OutputFormat::Png | OutputFormat::Svg => {
+            let (grid, panels) = first_page(config);
+            page_file(data, config, format, &config.metrics[panels], 0, grid)
+        }
+        OutputFormat::Html | OutputFormat::Vega => Err("figures are only rendered to PNG, SVG or PDF".into()),
+    }
+}
+
+/// Reads the CSV file, FASTA file, directory of either or glob pattern
+/// `path`, grouped by the `group_by` and `color_by` of `options`. Free the
+/// records with [`cfp_free_dataset`].
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string, and `options` one or `NULL`.
+#[no_mangle]
+pub unsafe extern "C" fn cfp_load(path: *const c_char, options: *const c_char) -> *mut CfpDataset {
+    let load = || {
+        if path.is_null() {
+            return Err("no path to read".into());
+        }
+        let path = PathBuf::from(string(path, "")?);
+        let options = Options::parse(string(options, "")?)?;
+        let dataset = Dataset::load(&[path], &options.load_options())?;
+        if dataset.is_empty() {
+            return Err("the input contains no records".into());
+        }
+        Ok(Box::into_raw(Box::new(CfpDataset(dataset))))
+    };
+    guard(load).unwrap_or(ptr::null_mut())
+}
+
+/// Draws the figure of `dataset` with `options` to `format`, `"png"`,
+/// `"svg"` or `"pdf"`, and returns the contents of its file, `*len` bytes
+/// long. Free them with [`cfp_free_buffer`].
+///
+/// # Safety
+///
+/// `dataset` must come from [`cfp_load`], `format` and `options` must be
+/// NUL-terminated strings or `NULL`, for PNG and the defaults, and `len` must
+/// point to a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn cfp_render(
+    dataset: *const CfpDataset,
+    format: *const c_char,
+    options: *const c_char,
+    len: *mut usize,
+) -> *mut u8 {
+    let draw = || {
+        let dataset = &dataset.as_ref().ok_or("no records to draw")?.0;
+        let len = len.as_mut().ok_or("nowhere to write the length of the file")?;
+        let format = OutputFormat::from_str(string(format, "png")?, true)?;
+        let config = Options::parse(string(options, "")?)?.plot_config(format)?;
+        let file = render(dataset, &config, format)?.into_boxed_slice();
+        *len = file.len();
+        Ok(Box::into_raw(file).cast::<u8>())
+    };
+    guard(draw).unwrap_or(ptr::null_mut())
+}
+
+/// Frees the `len` bytes of a file returned by [`cfp_render`]; nothing for
+/// `NULL`.
+///
+/// # Safety
+///
+/// `buffer` must come from [`cfp_render`] with this `len`, and not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cfp_free_buffer(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer, len)));
+    }
+}
+
+/// Frees records read by [`cfp_load`]; nothing for `NULL`.
+///
+/// # Safety
+///
+/// `dataset` must come from [`cfp_load`] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cfp_free_dataset(dataset: *mut CfpDataset) {
+    if !dataset.is_null() {
+        drop(Box::from_raw(dataset));
+    }
+}
+
+/// Message of the last failure on the calling thread, valid until the next
+/// one, or `NULL` when nothing failed.
+#[no_mangle]
+pub extern "C" fn cfp_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
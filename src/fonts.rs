@@ -0,0 +1,70 @@
+//! Fonts of the figures. Text is drawn from fonts registered with plotters
+//! rather than looked up while drawing, so that a bundled font stands in for
+//! any family the system cannot resolve, as in minimal containers, and the
+//! figures come out the same everywhere.
+
+use crate::Style;
+use font_kit::family_name::FamilyName;
+use font_kit::properties::Properties;
+use font_kit::source::SystemSource;
+use plotters::style::{register_font, FontStyle};
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// DejaVu Sans, drawn when a family is not installed; see
+/// `assets/DejaVuSans.LICENSE`.
+static FALLBACK: &[u8] = include_bytes!("../assets/DejaVuSans.ttf");
+
+/// Families and font files already registered, so that figures drawn one
+/// after the other only look them up once.
+static REGISTERED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Registers the font of `style`, from its font file when it has one, and the
+/// generic families, each from the system or the bundled fallback, or always
+/// the bundled font when `bundled`.
+pub(crate) fn load(style: &Style, bundled: bool) -> Result<(), Box<dyn Error>> {
+    let mut registered = REGISTERED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let registered = registered.get_or_insert_with(HashSet::new);
+    for family in [style.font.as_str(), "sans-serif", "serif", "monospace"] {
+        let file = style.font_file.as_ref().filter(|_| family == style.font);
+        let key = match file {
+            Some(path) => format!("{family}\0{}", path.display()),
+            None if bundled => format!("{family}\0bundled"),
+            None => family.to_string(),
+        };
+        if registered.contains(&key) {
+            continue;
+        }
+        match file {
+            Some(path) => {
+                let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+                register_font(family, FontStyle::Normal, Box::leak(bytes.into_boxed_slice()))
+                    .map_err(|_| format!("{}: not a TrueType or OpenType font", path.display()))?;
+            }
+            None => {
+                let system = if bundled { None } else { system_font(family) }
+                    .filter(|bytes| register_font(family, FontStyle::Normal, bytes).is_ok());
+                if system.is_none() {
+                    register_font(family, FontStyle::Normal, FALLBACK).map_err(|_| "invalid bundled font")?;
+                }
+            }
+        }
+        registered.insert(key);
+    }
+    Ok(())
+}
+
+/// The regular face of `family` as installed, `None` when the system has no
+/// such family.
+fn system_font(family: &str) -> Option<&'static [u8]> {
+    let name = match family {
+        "sans-serif" => FamilyName::SansSerif,
+        "serif" => FamilyName::Serif,
+        "monospace" => FamilyName::Monospace,
+        name => FamilyName::Title(name.to_string()),
+    };
+    let handle = SystemSource::new().select_best_match(&[name], &Properties::new()).ok()?;
+    let data = handle.load().ok()?.copy_font_data()?;
+    Some(Box::leak(data.to_vec().into_boxed_slice()))
+}
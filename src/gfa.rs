@@ -0,0 +1,98 @@
+//! Segment statistics of GFA assembly graphs, such as the unitig and contig
+//! graphs of hifiasm and verkko, before they are linearized.
+
+use crate::fasta::Assembly;
+use crate::{reader, GenomeStats, Invalid, InvalidInput, SendError};
+use std::io::BufRead;
+use std::path::Path;
+
+/// Segments of one graph. Those written as `*` have a length but no bases.
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub assembly: Assembly,
+    /// Bases of the segments whose sequence is given.
+    pub sequenced: u64,
+}
+
+impl Graph {
+    /// Statistics of the segments, with the GC and N contents and the gaps
+    /// of those whose sequence is given; missing when none is.
+    pub fn stats(&self) -> GenomeStats {
+        let percentage = |count: u64| {
+            if self.sequenced == 0 {
+                f64::NAN
+            } else {
+                count as f64 / self.sequenced as f64 * 100.0
+            }
+        };
+        GenomeStats {
+            gc_percentage: percentage(self.assembly.gc_count),
+            n_percentage: percentage(self.assembly.n_count),
+            gaps: (self.sequenced > 0).then_some(self.assembly.gap_count as f64),
+            gap_length: (self.sequenced > 0).then_some(self.assembly.n_count as f64),
+            ..self.assembly.stats()
+        }
+    }
+}
+
+/// Whether `path` is a (possibly compressed) GFA file judging by its
+/// extension.
+pub fn is_gfa(path: &Path) -> bool {
+    reader::format_extension(path).is_some_and(|ext| ext.eq_ignore_ascii_case("gfa"))
+}
+
+/// Reads the `S` lines of a GFA 1 or 2 file. Segments without a sequence take
+/// their length from their `LN` tag in GFA 1.
+pub fn read_gfa(path: &Path) -> Result<Graph, SendError> {
+    let invalid = |line: usize, message: String| {
+        InvalidInput::new(Invalid::UnparseableRow, format!("{}: line {}: {}", path.display(), line, message))
+    };
+    let mut graph = Graph {
+        assembly: Assembly {
+            name: path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+            ..Default::default()
+        },
+        sequenced: 0,
+    };
+    let mut gfa2 = false;
+    for (i, line) in reader::open(path)?.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.trim_end().split('\t').collect();
+        match fields[0] {
+            "H" => gfa2 |= fields[1..].iter().any(|tag| tag.starts_with("VN:Z:2")),
+            "S" if fields.len() >= 3 => {
+                let name = fields[1];
+                // GFA 2 gives the length before the sequence
+                let (sequence, tags) = if gfa2 {
+                    (fields.get(3).copied().unwrap_or("*"), fields.get(4..).unwrap_or_default())
+                } else {
+                    (fields[2], &fields[3..])
+                };
+                let len = if sequence != "*" {
+                    let gc_start = graph.assembly.gc_count;
+                    graph.assembly.count_bases(sequence.as_bytes(), &mut false);
+                    graph.sequenced += sequence.len() as u64;
+                    let gc = (graph.assembly.gc_count - gc_start) as f64;
+                    graph.assembly.sequence_gc.push(gc / sequence.len() as f64 * 100.0);
+                    sequence.len() as u64
+                } else {
+                    graph.assembly.sequence_gc.push(f64::NAN);
+                    let length = if gfa2 {
+                        Some(fields[2])
+                    } else {
+                        tags.iter().find_map(|tag| tag.strip_prefix("LN:i:"))
+                    };
+                    let length = length
+                        .ok_or_else(|| invalid(i + 1, format!("segment {name} has neither a sequence nor an LN tag")))?;
+                    length
+                        .parse()
+                        .map_err(|_| invalid(i + 1, format!("could not parse the length {length:?} of segment {name}")))?
+                };
+                graph.assembly.lengths.push(len);
+            }
+            "S" => return Err(invalid(i + 1, "segment line with fewer than 3 fields".to_string()).into()),
+            _ => {}
+        }
+    }
+    Ok(graph)
+}
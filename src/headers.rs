@@ -0,0 +1,169 @@
+//! Recognising the columns of statistics tables written by other tools, which
+//! spell them `N50`, `scaffold_N50`, `GC(%)` or `sum_len`.
+
+use csv::StringRecord;
+
+/// Columns read into [`GenomeStats`](crate::GenomeStats), each with the
+/// spellings it is recognised by once normalized.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("filename", &["filename", "file", "sample", "name", "assembly"]),
+    (
+        "assembly_length",
+        &["assemblylength", "totallength", "sumlen", "genomesize", "genomelength", "totalsize"],
+    ),
+    (
+        "number_of_sequences",
+        &[
+            "numberofsequences", "numseqs", "sequences", "numberofcontigs", "contigs",
+            "numberofscaffolds", "scaffolds", "scaffoldcount", "contigcount",
+        ],
+    ),
+    ("N50", &["n50", "scaffoldn50", "contign50", "n50bp"]),
+    ("GC_percentage", &["gcpercentage", "gc", "gcpercent", "gccontent", "gcratio"]),
+    ("N_percentage", &["npercentage", "npercent", "nratio", "ncontent"]),
+    ("auN", &["aun", "areaundernx"]),
+    ("L50", &["l50", "scaffoldl50", "contigl50", "n50n"]),
+    ("L90", &["l90", "scaffoldl90", "contigl90", "n90n"]),
+    ("largest_contig", &["largestcontig", "longest", "maxlen", "longestsequence", "largestscaffold"]),
+    ("shortest_contig", &["shortestcontig", "shortest", "minlen", "shortestsequence", "shortestscaffold"]),
+    ("Gaps", &["gaps", "gapcount", "numberofgaps"]),
+    ("gap_length", &["gaplength", "totalgaplength", "ncount", "sumgaplength"]),
+];
+
+/// Columns that are read when present but not required.
+const OPTIONAL: &[&str] = &["auN", "L50", "L90", "largest_contig", "shortest_contig", "Gaps", "gap_length"];
+
+/// Whether `column` is one of the numeric columns every row of
+/// [`GenomeStats`](crate::GenomeStats) must have.
+pub fn is_numeric(column: &str) -> bool {
+    column != "filename" && !OPTIONAL.contains(&column) && ALIASES.iter().any(|(c, _)| *c == column)
+}
+
+/// First numeric column absent from `columns`, normalized by [`canonical`].
+pub fn missing_column(columns: &StringRecord) -> Option<&'static str> {
+    ALIASES
+        .iter()
+        .map(|(column, _)| *column)
+        .find(|&column| is_numeric(column) && !columns.iter().any(|h| h == column))
+}
+
+/// Checks that every alias maps to one of the columns.
+pub fn check_aliases(aliases: &[(String, String)]) -> Result<(), String> {
+    for (header, column) in aliases {
+        if !ALIASES.iter().any(|(c, _)| c == column) {
+            let columns: Vec<&str> = ALIASES.iter().map(|(c, _)| *c).collect();
+            return Err(format!(
+                "cannot read {:?} as {:?}, which is not one of {}",
+                header,
+                column,
+                columns.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lowercase letters and digits of `header`, so that `GC(%)`, `gc %` and `GC`
+/// compare equal.
+pub fn normalize(header: &str) -> String {
+    header
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// `headers` with every recognised spelling replaced by the column it stands
+/// for, together with the renames made. `aliases` map headers, exactly or once
+/// normalized, to columns and take precedence over the built-in spellings,
+/// those of other headers too. A header is left alone when its column is
+/// already present, so of two spellings of a column the first is read.
+pub fn canonical(
+    headers: &StringRecord,
+    aliases: &[(String, String)],
+) -> (StringRecord, Vec<(String, String)>) {
+    let mut present: Vec<String> = headers.iter().map(str::to_owned).collect();
+    let mut renames = Vec::new();
+    for builtin in [false, true] {
+        for i in 0..present.len() {
+            let header = present[i].clone();
+            let key = normalize(&header);
+            let column = match builtin {
+                false => aliases
+                    .iter()
+                    .find(|(from, _)| *from == header || normalize(from) == key)
+                    .map(|(_, to)| to.as_str()),
+                true => ALIASES
+                    .iter()
+                    .find(|(_, spellings)| spellings.contains(&key.as_str()))
+                    .map(|(column, _)| *column),
+            };
+            let Some(column) = column else {
+                continue;
+            };
+            if column != header && !present.iter().any(|h| h == column) {
+                present[i] = column.to_string();
+                renames.push((header, column.to_string()));
+            }
+        }
+    }
+    (StringRecord::from(present), renames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical_of(headers: &[&str], aliases: &[(&str, &str)]) -> (Vec<String>, Vec<(String, String)>) {
+        let aliases: Vec<(String, String)> = aliases.iter().map(|&(a, b)| (a.to_string(), b.to_string())).collect();
+        let (columns, renames) = canonical(&StringRecord::from(headers.to_vec()), &aliases);
+        (columns.iter().map(str::to_owned).collect(), renames)
+    }
+
+    #[test]
+    fn spellings_of_other_tools() {
+        let (columns, renames) = canonical_of(&["Sample", "Total length", "# contigs", "scaffold_N50", "GC (%)", "N_percentage"], &[]);
+        assert_eq!(columns, ["filename", "assembly_length", "number_of_sequences", "N50", "GC_percentage", "N_percentage"]);
+        assert_eq!(renames.len(), 5);
+        assert_eq!(renames[0], ("Sample".to_string(), "filename".to_string()));
+        assert_eq!(missing_column(&StringRecord::from(columns)), None);
+    }
+
+    #[test]
+    fn first_of_two_spellings() {
+        let (columns, renames) = canonical_of(&["contig_N50", "scaffold_N50"], &[]);
+        assert_eq!(columns, ["N50", "scaffold_N50"]);
+        assert_eq!(renames, [("contig_N50".to_string(), "N50".to_string())]);
+        // A column already present keeps its header
+        let (columns, renames) = canonical_of(&["scaffold_N50", "N50"], &[]);
+        assert_eq!(columns, ["scaffold_N50", "N50"]);
+        assert!(renames.is_empty());
+    }
+
+    #[test]
+    fn aliases_take_precedence() {
+        let (columns, _) = canonical_of(&["total_length", "Size"], &[("Size", "assembly_length")]);
+        assert_eq!(columns, ["total_length", "assembly_length"]);
+        // Matched once normalized, and over the built-in spelling of the
+        // header itself
+        let (columns, _) = canonical_of(&["My_GC", "gc"], &[("my gc", "GC_percentage"), ("gc", "N_percentage")]);
+        assert_eq!(columns, ["GC_percentage", "N_percentage"]);
+        // Two aliases of one column: the first header is read
+        let (columns, _) = canonical_of(&["a", "b"], &[("a", "N50"), ("b", "N50")]);
+        assert_eq!(columns, ["N50", "b"]);
+    }
+
+    #[test]
+    fn aliases_to_unknown_columns() {
+        assert!(check_aliases(&[("Size".to_string(), "assembly_length".to_string())]).is_ok());
+        assert!(check_aliases(&[("Size".to_string(), "size".to_string())]).is_err());
+    }
+
+    #[test]
+    fn required_columns() {
+        assert!(is_numeric("N50") && is_numeric("GC_percentage"));
+        assert!(!is_numeric("filename") && !is_numeric("auN") && !is_numeric("species"));
+        let columns = StringRecord::from(vec!["filename", "assembly_length", "N50"]);
+        assert_eq!(missing_column(&columns), Some("number_of_sequences"));
+    }
+}
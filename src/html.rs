@@ -0,0 +1,917 @@
+//! Interactive HTML output drawn client-side with plotly.js.
+
+use crate::{
+    cluster, compare, correlation, curve_styles, fasta, density_curve, fasta_assemblies, gc_assemblies, group_labels, panel_letter, pca, snail, GenomeStats,
+    LegendPosition, Locale, Metric, Notation, PlotConfig, PlotType, PointColors, Unit,
+};
+use plotters::style::RGBColor;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A record with its row number, so that unnamed samples are still
+/// identifiable in the tooltips.
+pub(crate) type Numbered<'a> = (usize, &'a GenomeStats);
+
+const PLOTLY_URL: &str = "https://cdn.plot.ly/plotly-2.35.2.min.js";
+
+/// Writes a standalone HTML page with one boxplot per metric. Hovering an
+/// outlier shows the sample it belongs to and its exact value.
+pub fn write_html(path: &Path, data: &[GenomeStats], args: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    let (traces, mut layout) = match args.plot_type {
+        PlotType::Pairs => pairs_figure(data, args),
+        PlotType::Nx | PlotType::Cumulative | PlotType::Lengths => curves_figure(data, args)?,
+        PlotType::SequenceGc => sequence_gc_figure(data, args)?,
+        PlotType::Treemap => treemap_figure(data, args)?,
+        PlotType::Snail => snail_figure(data, args)?,
+        PlotType::Correlation => correlation_figure(data, args)?,
+        PlotType::Pca => pca_figure(data, args)?,
+        PlotType::Dendrogram => dendrogram_figure(data, args)?,
+        _ => panels_figure(data, args),
+    };
+    let RGBColor(r, g, b) = args.style.background.0;
+    let alpha = if args.style.transparent { 0 } else { 1 };
+    layout["paper_bgcolor"] = json!(format!("rgba({r}, {g}, {b}, {alpha})"));
+    layout["plot_bgcolor"] = json!(format!("rgba({r}, {g}, {b}, {alpha})"));
+    let RGBColor(r, g, b) = args.style.foreground.0;
+    layout["font"] = json!({ "family": args.style.font, "color": format!("rgb({r}, {g}, {b})") });
+    if let Some(title) = &args.style.title {
+        layout["title"] = json!({ "text": title });
+    }
+    let locale = args.style.locale;
+    if locale != Locale::default() {
+        // Decimal mark then thousands separator
+        layout["separators"] = json!(format!("{}{}", locale.decimal, locale.group.unwrap_or(' ')));
+    }
+    match args.style.legend {
+        None | Some(LegendPosition::Outside) => {}
+        Some(LegendPosition::None) => layout["showlegend"] = json!(false),
+        Some(position) => {
+            // Inside the plotting area, in paper fractions from its lower left
+            let (x, xanchor) = match position {
+                LegendPosition::UpperLeft | LegendPosition::MiddleLeft | LegendPosition::LowerLeft => (0.01, "left"),
+                LegendPosition::UpperMiddle | LegendPosition::LowerMiddle => (0.5, "center"),
+                _ => (0.99, "right"),
+            };
+            let (y, yanchor) = match position {
+                LegendPosition::UpperLeft | LegendPosition::UpperMiddle | LegendPosition::UpperRight => (0.99, "top"),
+                LegendPosition::MiddleLeft | LegendPosition::MiddleRight => (0.5, "middle"),
+                _ => (0.01, "bottom"),
+            };
+            layout["legend"] = json!({ "x": x, "y": y, "xanchor": xanchor, "yanchor": yanchor });
+        }
+    }
+    if let Some(footer) = &args.style.footer {
+        layout["margin"] = json!({ "b": 100 });
+        let annotation = json!({
+            "text": footer,
+            "showarrow": false,
+            "xref": "paper",
+            "yref": "paper",
+            "x": 0,
+            "y": 0,
+            "xanchor": "left",
+            "yanchor": "top",
+            "yshift": -60,
+            "font": { "size": 10 },
+        });
+        match layout["annotations"].as_array_mut() {
+            Some(annotations) => annotations.push(annotation),
+            None => layout["annotations"] = json!([annotation]),
+        }
+    }
+
+    let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+    write!(
+        out,
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>count-fasta-plots</title>
+<script src="{PLOTLY_URL}"></script>
+</head>
+<body>
+<div id="plot"></div>
+<script>
+Plotly.newPlot("plot", {}, {});
+</script>
+</body>
+</html>
+"#,
+        script_json(&traces)?,
+        script_json(&layout)?,
+    )?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Traces and layout with one subplot per metric.
+fn panels_figure(data: &[GenomeStats], args: &PlotConfig) -> (Vec<Value>, Value) {
+    let metrics = &args.metrics;
+    let groups = group_labels(data);
+    let mut traces = Vec::new();
+    for (i, metric) in metrics.iter().enumerate() {
+        let panel = panel_letter(i);
+        if groups.is_empty() {
+            let records: Vec<Numbered> = data.iter().enumerate().collect();
+            traces.push(trace(&records, metric, i, &panel, args.style.box_color.0, args));
+        } else {
+            for (g, group) in groups.iter().enumerate() {
+                let records: Vec<Numbered> = data
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, x)| x.group_name() == *group)
+                    .collect();
+                let mut trace = trace(&records, metric, i, group, args.style.group_color(g), args);
+                trace["legendgroup"] = json!(group);
+                trace["showlegend"] = json!(i == 0);
+                traces.push(trace);
+            }
+        }
+    }
+
+    let mut layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "barmode": "overlay",
+        "showlegend": !groups.is_empty(),
+        "grid": { "rows": metrics.len(), "columns": 1, "pattern": "independent" },
+        "annotations": [],
+        "shapes": [],
+    });
+    let RGBColor(r, g, b) = args.style.foreground.0;
+    let foreground = format!("rgb({r}, {g}, {b})");
+    for (i, metric) in metrics.iter().enumerate() {
+        layout[format!("xaxis{}", i + 1)] = json!({ "title": { "text": args.style.xlabel(metric) } });
+        if args.is_log(metric) {
+            layout[format!("xaxis{}", i + 1)]["type"] = json!("log");
+        }
+        if args.plot_type == PlotType::Ecdf {
+            layout[format!("yaxis{}", i + 1)] = json!({ "range": [0, 100], "ticksuffix": "%" });
+        }
+        if args.plot_type == PlotType::Ridgeline {
+            // Categories are listed from the bottom, so that the first group
+            // tops the panel as in the static figures
+            let order: Vec<&str> = groups.iter().rev().copied().collect();
+            layout[format!("yaxis{}", i + 1)] =
+                json!({ "categoryorder": "array", "categoryarray": order, "automargin": true });
+        }
+        if matches!(args.plot_type, PlotType::Ranked | PlotType::Dots) {
+            // Categories are listed from the bottom, so the highest bar tops
+            // the panel whichever group it is in
+            layout[format!("yaxis{}", i + 1)] = json!({ "categoryorder": "total ascending", "automargin": true });
+        }
+        let axis = &mut layout[format!("xaxis{}", i + 1)];
+        let default = if args.raw_ticks { Notation::Plain } else { Notation::Units };
+        let decimals = args.style.decimals(metric);
+        match args.style.notation(metric).unwrap_or(default) {
+            Notation::Plain => {
+                axis["exponentformat"] = json!("none");
+                if let Some(decimals) = decimals {
+                    axis["tickformat"] = json!(format!(".{decimals}f"));
+                }
+            }
+            // Plotly draws the exponent as a superscript
+            Notation::Scientific => {
+                axis["exponentformat"] = json!("power");
+                axis["showexponent"] = json!("all");
+            }
+            Notation::E => {
+                axis["tickformat"] = json!(decimals.map_or("~e".to_string(), |decimals| format!(".{decimals}e")));
+            }
+            Notation::Si => axis["exponentformat"] = json!("SI"),
+            Notation::Units if metric.unit() == Unit::BasePairs => {
+                // Plotly already abbreviates with SI prefixes, as in 15M
+                axis["ticksuffix"] = json!("b");
+            }
+            Notation::Units => {
+                if let Some(decimals) = decimals.filter(|_| metric.unit() == Unit::Percent) {
+                    axis["tickformat"] = json!(format!(".{decimals}f"));
+                }
+            }
+        }
+        if let Some(ticks) = args.style.ticks(metric) {
+            axis["nticks"] = json!(ticks);
+        }
+        if let Some((lo, hi)) = args.style.range(metric) {
+            // Log axis ranges are given in decades
+            let range = if args.is_log(metric) { (lo.log10(), hi.log10()) } else { (lo, hi) };
+            layout[format!("xaxis{}", i + 1)]["range"] = json!(range);
+        }
+        layout["annotations"]
+            .as_array_mut()
+            .expect("annotations is an array")
+            .push(json!({
+                "text": args.style.title(metric),
+                "showarrow": false,
+                "xref": format!("x{} domain", i + 1),
+                "yref": format!("y{} domain", i + 1),
+                "x": 0.5,
+                "y": 1.0,
+                "yanchor": "bottom",
+                "font": { "size": 16 },
+            }));
+        let distribution = matches!(args.plot_type, PlotType::Boxplot | PlotType::Violin);
+        if let Some(comparison) = compare::compare(data, metric, &groups).filter(|_| args.significance && distribution) {
+            layout["annotations"].as_array_mut().expect("annotations is an array").push(json!({
+                "text": format!("{}, {}", comparison.test.name(), compare::describe(comparison.p_value, args.style.locale)),
+                "showarrow": false,
+                "xref": format!("x{} domain", i + 1),
+                "yref": format!("y{} domain", i + 1),
+                "x": 1.0,
+                "y": 0.0,
+                "xanchor": "right",
+                "yanchor": "bottom",
+                "font": { "size": 10 },
+            }));
+        }
+        if let Some((value, label)) = args.style.threshold(metric) {
+            layout["shapes"].as_array_mut().expect("shapes is an array").push(json!({
+                "type": "line",
+                "xref": format!("x{}", i + 1),
+                "yref": format!("y{} domain", i + 1),
+                "x0": value,
+                "x1": value,
+                "y0": 0,
+                "y1": 1,
+                "line": { "color": foreground, "width": 1, "dash": "dash" },
+            }));
+            // Annotations on a log axis are placed in decades, unlike shapes
+            let x = if args.is_log(metric) { value.log10() } else { value };
+            layout["annotations"].as_array_mut().expect("annotations is an array").push(json!({
+                "text": label,
+                "showarrow": false,
+                "xref": format!("x{}", i + 1),
+                "yref": format!("y{} domain", i + 1),
+                "x": x,
+                "y": 1.0,
+                "xanchor": "left",
+                "yanchor": "top",
+                "font": { "size": 10 },
+            }));
+        }
+    }
+
+    if args.plot_type == PlotType::Ridgeline {
+        // Ridges are drawn over each other rather than side by side
+        layout["violinmode"] = json!("overlay");
+    }
+    (traces, layout)
+}
+
+/// A scatter matrix over all metrics, with one trace per group, or per
+/// `--color-by` class, so that the legend can toggle them.
+fn pairs_figure(data: &[GenomeStats], args: &PlotConfig) -> (Vec<Value>, Value) {
+    let points = PointColors::new(data);
+    let records: Vec<Numbered> = data.iter().enumerate().collect();
+    let subsets: Vec<(String, Vec<Numbered>, RGBColor)> = if points.classes.is_empty() {
+        vec![(String::new(), records, args.style.box_color.0)]
+    } else {
+        points
+            .classes
+            .iter()
+            .enumerate()
+            .map(|(c, class)| {
+                let members = records
+                    .iter()
+                    .filter(|(_, x)| (points.class)(x) == *class)
+                    .copied()
+                    .collect();
+                (class.to_string(), members, points.color_of(c, &args.style))
+            })
+            .collect()
+    };
+
+    let traces = subsets
+        .iter()
+        .map(|(name, members, color)| {
+            let RGBColor(r, g, b) = *color;
+            let dimensions: Vec<Value> = args
+                .metrics
+                .iter()
+                .map(|metric| {
+                    let values: Vec<f64> = members.iter().map(|(_, x)| metric.value(x)).collect();
+                    json!({ "label": args.style.title(metric), "values": values })
+                })
+                .collect();
+            let names: Vec<String> = members.iter().map(|(row, x)| x.name(*row)).collect();
+            json!({
+                "type": "splom",
+                "name": name,
+                "dimensions": dimensions,
+                "text": names,
+                "hovertemplate": "%{text}<br>%{xaxis.title.text}: %{x}<br>%{yaxis.title.text}: %{y}<extra></extra>",
+                "marker": { "color": format!("rgba({r}, {g}, {b}, 0.7)"), "size": 5 },
+                "diagonal": { "visible": false },
+            })
+        })
+        .collect();
+    let layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "showlegend": !points.classes.is_empty(),
+        "dragmode": "select",
+    });
+    (traces, layout)
+}
+
+/// Points of a curve of an assembly given its sequence lengths, longest first.
+type Curve = fn(&[u64]) -> Vec<(f64, f64)>;
+
+/// Nx, cumulative length or length distribution curves of every FASTA
+/// input, one trace each.
+fn curves_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    let (title, curve, hover): (&str, Curve, &str) = match args.plot_type {
+        PlotType::Nx => ("Nx curves", fasta::nx_curve, "%{fullData.name}<br>N%{x:.1f}: %{y}<extra></extra>"),
+        PlotType::Cumulative => (
+            "Cumulative length curves",
+            fasta::cumulative_curve,
+            "%{fullData.name}<br>%{x} sequences: %{y} bp<extra></extra>",
+        ),
+        _ => (
+            "Sequence length distributions",
+            fasta::length_ecdf,
+            "%{fullData.name}<br>%{y:.1f}% of sequences up to %{x} bp<extra></extra>",
+        ),
+    };
+    let assemblies = fasta_assemblies(data, title)?;
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, &args.style);
+    let traces: Vec<Value> = assemblies
+        .iter()
+        .zip(styles)
+        .map(|((row, record), (color, label))| {
+            let RGBColor(r, g, b) = color;
+            let (x, y): (Vec<f64>, Vec<f64>) = curve(&record.lengths).into_iter().unzip();
+            let mut trace = json!({
+                "type": "scatter",
+                "mode": "lines",
+                "x": x,
+                "y": y,
+                "name": record.name(*row),
+                "line": { "color": format!("rgb({r}, {g}, {b})") },
+                "hovertemplate": hover,
+                "showlegend": label.is_some() || groups.is_empty(),
+            });
+            if !groups.is_empty() {
+                trace["legendgroup"] = json!(record.group_name());
+                if let Some(label) = label {
+                    trace["name"] = json!(label);
+                }
+            }
+            trace
+        })
+        .collect();
+    let length_suffix = if args.raw_ticks { "" } else { "b" };
+    let layout = match args.plot_type {
+        PlotType::Nx => json!({
+            "width": args.width,
+            "height": args.height,
+            "title": { "text": "Nx curves" },
+            "xaxis": { "title": { "text": "x (%)" }, "range": [0, 100] },
+            "yaxis": { "title": { "text": "Nx (bp.)" }, "type": "log", "ticksuffix": length_suffix },
+            "shapes": [{
+                "type": "line",
+                "x0": 50, "x1": 50, "xref": "x",
+                "y0": 0, "y1": 1, "yref": "paper",
+                "line": { "color": "gray", "dash": "dot" },
+            }],
+        }),
+        PlotType::Cumulative => json!({
+            "width": args.width,
+            "height": args.height,
+            "title": { "text": "Cumulative length" },
+            "xaxis": { "title": { "text": "Sequence rank (longest first)" } },
+            "yaxis": { "title": { "text": "Cumulative length (bp.)" }, "ticksuffix": length_suffix },
+        }),
+        _ => json!({
+            "width": args.width,
+            "height": args.height,
+            "title": { "text": "Sequence lengths" },
+            "xaxis": { "title": { "text": "Sequence length (bp.)" }, "type": "log", "ticksuffix": length_suffix },
+            "yaxis": { "title": { "text": "Sequences (%)" }, "range": [0, 100] },
+        }),
+    };
+    Ok((traces, layout))
+}
+
+/// GC ratios of the sequences of every FASTA input, one box each.
+fn sequence_gc_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    let assemblies = gc_assemblies(data)?;
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, &args.style);
+    let RGBColor(or, og, ob) = args.style.outlier_color.0;
+    let traces: Vec<Value> = assemblies
+        .iter()
+        .zip(styles)
+        .map(|((row, record), (color, _))| {
+            let RGBColor(r, g, b) = color;
+            json!({
+                "type": "box",
+                "x": record.known_sequence_gc(),
+                "name": record.name(*row),
+                "boxpoints": if args.points { "all" } else { "outliers" },
+                "boxmean": args.mean,
+                "notched": args.notch,
+                "jitter": 0.5,
+                "hovertemplate": "%{x:.2f}%<extra>%{fullData.name}</extra>",
+                "fillcolor": format!("rgba({r}, {g}, {b}, 0.3)"),
+                "line": { "color": format!("rgb({r}, {g}, {b})") },
+                "marker": { "color": format!("rgb({or}, {og}, {ob})") },
+                "showlegend": false,
+            })
+        })
+        // First assembly at the top
+        .rev()
+        .collect();
+    let layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "title": { "text": "GC ratio of sequences" },
+        "xaxis": { "title": { "text": "GC ratio (%)" } },
+        "yaxis": { "automargin": true },
+    });
+    Ok((traces, layout))
+}
+
+/// Treemap of the sequences of every FASTA input, one tile each under a tile
+/// of the whole assembly, shaded like the static treemaps.
+fn treemap_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    let assemblies = fasta_assemblies(data, "Treemaps")?;
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, &args.style);
+    let (mut ids, mut labels, mut parents, mut values, mut colors) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for ((row, record), (color, _)) in assemblies.iter().zip(styles) {
+        let RGBColor(r, g, b) = color;
+        let name = record.name(*row);
+        let (l50, l90) = (fasta::covering(&record.lengths, 50).1, fasta::covering(&record.lengths, 90).1);
+        ids.push(name.clone());
+        labels.push(name.clone());
+        parents.push(String::new());
+        values.push(record.lengths.iter().sum::<u64>());
+        colors.push(format!("rgba({r}, {g}, {b}, 0.1)"));
+        for (i, &len) in record.lengths.iter().enumerate() {
+            let shade = match i as u64 {
+                i if i < l50 => 1.0,
+                i if i < l90 => 0.6,
+                _ => 0.3,
+            };
+            ids.push(format!("{}/{}", name, i + 1));
+            labels.push(format!("#{}", i + 1));
+            parents.push(name.clone());
+            values.push(len);
+            colors.push(format!("rgba({r}, {g}, {b}, {shade})"));
+        }
+    }
+    let trace = json!({
+        "type": "treemap",
+        "ids": ids,
+        "labels": labels,
+        "parents": parents,
+        "values": values,
+        "branchvalues": "total",
+        "marker": { "colors": colors },
+        "hovertemplate": "%{label}: %{value} bp.<extra>%{parent}</extra>",
+    });
+    let layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "title": { "text": "Sequence lengths" },
+    });
+    Ok((vec![trace], layout))
+}
+
+/// Snail plot of every FASTA input, each on its own polar axes of a grid,
+/// with the bins of the assembly as bars.
+fn snail_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    let assemblies = fasta_assemblies(data, "Snail plots")?;
+    let cols = (assemblies.len() as f64).sqrt().ceil() as usize;
+    let rows = assemblies.len().div_ceil(cols);
+    let color = |RGBColor(r, g, b): RGBColor| format!("rgb({r}, {g}, {b})");
+    let step = 360.0 / snail::BINS as f64;
+    let theta: Vec<f64> = (0..snail::BINS).map(|k| (k as f64 + 0.5) * step).collect();
+
+    let mut traces = Vec::new();
+    let mut layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "showlegend": false,
+    });
+    let mut annotations = Vec::new();
+    for (i, (row, record)) in assemblies.iter().enumerate() {
+        let polar = if i == 0 { "polar".to_string() } else { format!("polar{}", i + 1) };
+        let bins = snail::bin(&record.lengths, &record.sequence_gc);
+        let longest = record.lengths[0].max(1) as f64;
+        let share = longest / record.lengths.iter().sum::<u64>().max(1) as f64 * 360.0;
+        let colors: Vec<String> = theta
+            .iter()
+            .map(|&angle| match angle {
+                a if a < share => color(snail::LONGEST),
+                a if a < 180.0 => color(snail::N50),
+                a if a < 324.0 => color(snail::N90),
+                _ => color(snail::REST),
+            })
+            .collect();
+        let hover: Vec<String> = theta
+            .iter()
+            .zip(&bins.lengths)
+            .map(|(angle, len)| format!("N{:.1}: {} bp", angle / 3.6, len))
+            .collect();
+        traces.push(json!({
+            "type": "barpolar",
+            "subplot": polar,
+            "theta": theta,
+            "r": bins.lengths.iter().map(|&len| (len as f64 / longest).sqrt()).collect::<Vec<_>>(),
+            "width": step,
+            "marker": { "color": colors, "line": { "width": 0 } },
+            "hovertext": hover,
+            "hoverinfo": "text",
+        }));
+        let gc: Vec<f64> = bins.gc.iter().map(|gc| 0.15 * gc / 100.0).collect();
+        traces.push(json!({
+            "type": "barpolar",
+            "subplot": polar,
+            "theta": theta,
+            "base": 1.05,
+            "r": gc,
+            "width": step,
+            "marker": { "color": color(snail::GC), "line": { "width": 0 } },
+            "hoverinfo": "skip",
+        }));
+        traces.push(json!({
+            "type": "barpolar",
+            "subplot": polar,
+            "theta": theta,
+            "base": gc.iter().map(|gc| 1.05 + gc).collect::<Vec<_>>(),
+            "r": gc.iter().map(|gc| 0.15 - gc).collect::<Vec<_>>(),
+            "width": step,
+            "marker": { "color": color(snail::AT), "line": { "width": 0 } },
+            "hoverinfo": "skip",
+        }));
+        if record.lengths.len() > 1 {
+            let most = (record.lengths.len() as f64).log10();
+            traces.push(json!({
+                "type": "scatterpolar",
+                "subplot": polar,
+                "mode": "lines",
+                "theta": theta,
+                "r": bins.counts.iter().map(|&count| (count as f64).log10() / most).collect::<Vec<_>>(),
+                "line": { "color": "gray", "width": 1 },
+                "hoverinfo": "skip",
+            }));
+        }
+
+        let (x, y) = ((i % cols) as f64 / cols as f64, 1.0 - (i / cols + 1) as f64 / rows as f64);
+        let (w, h) = (1.0 / cols as f64, 1.0 / rows as f64);
+        layout[polar.as_str()] = json!({
+            "domain": { "x": [x + w * 0.1, x + w * 0.9], "y": [y + h * 0.1, y + h * 0.85] },
+            "bargap": 0,
+            "angularaxis": {
+                "rotation": 90,
+                "direction": "clockwise",
+                "tickvals": [0, 90, 180, 270],
+                "ticktext": ["0%", "25%", "50%", "75%"],
+                "showgrid": false,
+            },
+            "radialaxis": { "visible": false, "range": [0, 1.2] },
+        });
+        annotations.push(json!({
+            "text": record.name(*row),
+            "showarrow": false,
+            "xref": "paper",
+            "yref": "paper",
+            "x": x + w / 2.0,
+            "y": y + h,
+            "xanchor": "center",
+            "yanchor": "top",
+        }));
+    }
+    layout["annotations"] = json!(annotations);
+    Ok((traces, layout))
+}
+
+/// Heatmap of the correlation of every pair of metrics, annotated with the
+/// values.
+fn correlation_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    if args.metrics.len() < 2 {
+        return Err("a correlation heatmap needs at least two metrics".into());
+    }
+    let names: Vec<String> = args.metrics.iter().map(|metric| args.style.title(metric).into_owned()).collect();
+    // Null rather than NaN, which is not JSON
+    let matrix: Vec<Vec<Option<f64>>> = correlation::matrix(data, &args.metrics, args.correlation)
+        .into_iter()
+        .map(|row| row.into_iter().map(|r| (!r.is_nan()).then_some(r)).collect())
+        .collect();
+    let trace = json!({
+        "type": "heatmap",
+        "x": names,
+        "y": names,
+        "z": matrix,
+        "zmin": -1,
+        "zmax": 1,
+        "colorscale": "RdBu",
+        "reversescale": true,
+        "texttemplate": "%{z:.2f}",
+        "hovertemplate": "%{y} and %{x}: %{z:.3f}<extra></extra>",
+    });
+    let layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "title": { "text": args.correlation.caption() },
+        "xaxis": { "automargin": true },
+        "yaxis": { "automargin": true, "autorange": "reversed" },
+    });
+    Ok((vec![trace], layout))
+}
+
+/// Samples on the first two principal components, one trace per group or
+/// `--color-by` class, with the loadings of the metrics as arrows.
+fn pca_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    let pca = pca::analyse(data, &args.metrics)?;
+    let points = PointColors::new(data);
+    let subsets: Vec<(String, RGBColor)> = if points.classes.is_empty() {
+        vec![(String::new(), args.style.box_color.0)]
+    } else {
+        let classes = points.classes.iter().enumerate();
+        classes.map(|(c, class)| (class.to_string(), points.color_of(c, &args.style))).collect()
+    };
+    let traces = subsets
+        .iter()
+        .map(|(name, color)| {
+            let RGBColor(r, g, b) = *color;
+            let members: Vec<(usize, (f64, f64))> = pca
+                .rows
+                .iter()
+                .zip(&pca.scores)
+                .filter(|(row, _)| points.classes.is_empty() || (points.class)(&data[**row]) == name)
+                .map(|(row, score)| (*row, *score))
+                .collect();
+            json!({
+                "type": "scatter",
+                "mode": "markers",
+                "name": name,
+                "x": members.iter().map(|(_, (x, _))| x).collect::<Vec<_>>(),
+                "y": members.iter().map(|(_, (_, y))| y).collect::<Vec<_>>(),
+                "text": members.iter().map(|(row, _)| data[*row].name(*row)).collect::<Vec<_>>(),
+                "hovertemplate": "%{text}<br>PC1: %{x:.2f}<br>PC2: %{y:.2f}<extra></extra>",
+                "marker": { "color": format!("rgba({r}, {g}, {b}, 0.8)"), "size": 7 },
+            })
+        })
+        .collect();
+
+    let extent = pca.scores.iter().fold(0.0f64, |m, (x, y)| m.max(x.abs()).max(y.abs())).max(1e-9);
+    let longest = pca.loadings.iter().fold(0.0f64, |m, (x, y)| m.max(x.hypot(*y))).max(1e-9);
+    let reach = extent / longest * 0.8;
+    let arrows: Vec<Value> = args
+        .metrics
+        .iter()
+        .zip(&pca.loadings)
+        .map(|(metric, (x, y))| {
+            json!({
+                "x": x * reach, "y": y * reach, "ax": 0, "ay": 0,
+                "xref": "x", "yref": "y", "axref": "x", "ayref": "y",
+                "text": metric.to_string(),
+                "showarrow": true,
+                "arrowhead": 2,
+                "arrowcolor": "gray",
+            })
+        })
+        .collect();
+    let layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "title": { "text": "Principal components" },
+        "showlegend": !points.classes.is_empty(),
+        "xaxis": { "title": { "text": format!("PC1 ({:.1}%)", pca.explained.0 * 100.0) }, "zeroline": true },
+        "yaxis": { "title": { "text": format!("PC2 ({:.1}%)", pca.explained.1 * 100.0) }, "zeroline": true },
+        "annotations": arrows,
+    });
+    Ok((traces, layout))
+}
+
+/// Dendrogram of the samples clustered by their standardized metrics, as
+/// lines, and with `--heatmap` the z-scores to its left on the same rows.
+fn dendrogram_figure(data: &[GenomeStats], args: &PlotConfig) -> Result<(Vec<Value>, Value), Box<dyn Error>> {
+    let tree = cluster::tree(data, &args.metrics)?;
+    let n = tree.rows.len();
+    let mut leaves = vec![0; n];
+    for (i, &place) in tree.places.iter().enumerate() {
+        leaves[place] = tree.rows[i];
+    }
+    let names: Vec<String> = leaves.iter().map(|&row| data[row].name(row)).collect();
+
+    // One trace of every branch, broken by nulls
+    let mut nodes: Vec<(f64, f64)> = tree.places.iter().map(|&y| (0.0, y as f64)).collect();
+    let (mut x, mut y): (Vec<Option<f64>>, Vec<Option<f64>>) = (Vec::new(), Vec::new());
+    for merge in &tree.merges {
+        let (a, b) = (nodes[merge.left], nodes[merge.right]);
+        for (px, py) in [a, (merge.height, a.1), (merge.height, b.1), b] {
+            x.push(Some(px));
+            y.push(Some(py));
+        }
+        x.push(None);
+        y.push(None);
+        nodes.push((merge.height, (a.1 + b.1) / 2.0));
+    }
+    let RGBColor(r, g, b) = args.style.foreground.0;
+    let mut traces = vec![json!({
+        "type": "scatter",
+        "mode": "lines",
+        "x": x,
+        "y": y,
+        "line": { "color": format!("rgb({r}, {g}, {b})"), "width": 1 },
+        "hoverinfo": "skip",
+        "showlegend": false,
+    })];
+    let places: Vec<usize> = (0..n).collect();
+    let mut layout = json!({
+        "width": args.width,
+        "height": args.height,
+        "title": { "text": "Clustering of samples" },
+        "xaxis": { "title": { "text": "Distance" }, "rangemode": "tozero" },
+        "yaxis": { "tickvals": places, "ticktext": names, "automargin": true, "zeroline": false },
+    });
+    if args.heatmap {
+        let mut z = vec![Vec::new(); n];
+        for (scores, &place) in tree.scores.iter().zip(&tree.places) {
+            z[place].clone_from(scores);
+        }
+        let columns: Vec<String> = args.metrics.iter().map(Metric::to_string).collect();
+        traces.push(json!({
+            "type": "heatmap",
+            "xaxis": "x2",
+            "x": columns,
+            "y": places,
+            "z": z,
+            "text": names.iter().map(|name| vec![name; columns.len()]).collect::<Vec<_>>(),
+            "zmin": -3,
+            "zmax": 3,
+            "colorscale": "RdBu",
+            "reversescale": true,
+            "colorbar": { "title": { "text": "z-score" } },
+            "hovertemplate": "%{text}<br>%{x}: %{z:.2f}<extra></extra>",
+        }));
+        layout["xaxis"]["domain"] = json!([0.6, 1]);
+        layout["xaxis2"] = json!({ "domain": [0, 0.55], "automargin": true });
+        // Sample names left of the heatmap rather than over it
+        layout["yaxis"]["anchor"] = json!("x2");
+    }
+    Ok((traces, layout))
+}
+
+/// A trace over `records` in the style of `--plot-type`.
+fn trace(
+    records: &[Numbered],
+    metric: &Metric,
+    panel: usize,
+    name: &str,
+    color: RGBColor,
+    args: &PlotConfig,
+) -> Value {
+    let RGBColor(r, g, b) = color;
+    let RGBColor(or, og, ob) = args.style.outlier_color.0;
+    let mut values: Vec<f64> = records.iter().map(|(_, x)| metric.value(x)).collect();
+    if args.plot_type == PlotType::Ecdf {
+        values.retain(|v| v.is_finite());
+        values.sort_by(f64::total_cmp);
+    }
+    let mut trace = match args.plot_type {
+        PlotType::Boxplot => {
+            let names: Vec<String> = records.iter().map(|(row, x)| x.name(*row)).collect();
+            json!({
+                "type": "box",
+                "text": names,
+                "boxpoints": if args.points { "all" } else { "outliers" },
+                "boxmean": args.mean,
+                "notched": args.notch,
+                "jitter": 0.5,
+                "hoveron": "points+boxes",
+                "hovertemplate": "%{text}<br>%{x}<extra></extra>",
+                "fillcolor": format!("rgba({r}, {g}, {b}, 0.3)"),
+                "line": { "color": format!("rgb({r}, {g}, {b})") },
+                "marker": { "color": format!("rgb({or}, {og}, {ob})") },
+            })
+        }
+        PlotType::Violin => {
+            let names: Vec<String> = records.iter().map(|(row, x)| x.name(*row)).collect();
+            json!({
+                "type": "violin",
+                "orientation": "h",
+                "text": names,
+                "points": if args.points { "all" } else { "outliers" },
+                "jitter": 0.5,
+                "hoveron": "points+violins",
+                "hovertemplate": "%{text}<br>%{x}<extra></extra>",
+                "box": { "visible": args.violin_box },
+                "meanline": { "visible": args.mean },
+                "fillcolor": format!("rgba({r}, {g}, {b}, 0.3)"),
+                "line": { "color": format!("rgb({r}, {g}, {b})") },
+                "marker": { "color": format!("rgb({or}, {og}, {ob})") },
+            })
+        }
+        PlotType::Ridgeline => {
+            // One-sided violins wider than their rows overlap like ridges
+            let names: Vec<String> = records.iter().map(|(row, x)| x.name(*row)).collect();
+            json!({
+                "type": "violin",
+                "orientation": "h",
+                "side": "positive",
+                "width": 2.5,
+                "points": false,
+                "text": names,
+                "hoveron": "violins",
+                "fillcolor": format!("rgba({r}, {g}, {b}, 0.6)"),
+                "line": { "color": format!("rgb({r}, {g}, {b})") },
+            })
+        }
+        PlotType::Ranked => {
+            let names: Vec<String> = records.iter().map(|(row, x)| x.name(*row)).collect();
+            json!({
+                "type": "bar",
+                "orientation": "h",
+                "y": names,
+                "hovertemplate": "%{y}<br>%{x}<extra></extra>",
+                "marker": { "color": format!("rgb({r}, {g}, {b})") },
+            })
+        }
+        PlotType::Dots => {
+            let names: Vec<String> = records.iter().map(|(row, x)| x.name(*row)).collect();
+            // Error bars reaching down to zero draw the stems of the lollipops
+            let zeros = vec![0.0; values.len()];
+            json!({
+                "type": "scatter",
+                "mode": "markers",
+                "y": names,
+                "error_x": {
+                    "type": "data",
+                    "symmetric": false,
+                    "array": zeros,
+                    "arrayminus": values,
+                    "width": 0,
+                    "color": format!("rgba({r}, {g}, {b}, 0.5)"),
+                },
+                "hovertemplate": "%{y}<br>%{x}<extra></extra>",
+                "marker": { "color": format!("rgb({r}, {g}, {b})"), "size": 7 },
+            })
+        }
+        PlotType::Density => {
+            let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+            let range = args.style.range(metric).unwrap_or((f64::NEG_INFINITY, f64::INFINITY));
+            let (x, y): (Vec<f64>, Vec<f64>) = density_curve(&finite, metric, range, args).unwrap_or_default().into_iter().unzip();
+            json!({
+                "type": "scatter",
+                "mode": "lines",
+                "x": x,
+                "y": y,
+                "fill": "tozeroy",
+                "fillcolor": format!("rgba({r}, {g}, {b}, 0.2)"),
+                "line": { "color": format!("rgb({r}, {g}, {b})") },
+                "hovertemplate": "%{x}<br>density %{y:.3g}<extra></extra>",
+            })
+        }
+        PlotType::Ecdf => {
+            let n = values.len() as f64;
+            let shares: Vec<f64> = (1..=values.len()).map(|i| i as f64 / n * 100.0).collect();
+            json!({
+                "type": "scatter",
+                "mode": "lines",
+                "y": shares,
+                "line": { "shape": "hv", "color": format!("rgb({r}, {g}, {b})") },
+                "hovertemplate": "%{y:.1f}% up to %{x}<extra></extra>",
+            })
+        }
+        PlotType::Pairs
+        | PlotType::Nx
+        | PlotType::Cumulative
+        | PlotType::Lengths
+        | PlotType::SequenceGc
+        | PlotType::Treemap
+        | PlotType::Snail
+        | PlotType::Correlation
+        | PlotType::Pca
+        | PlotType::Dendrogram => unreachable!("drawn as a single chart"),
+        PlotType::Histogram => json!({
+            "type": "histogram",
+            "nbinsx": args.bins,
+            "opacity": 0.6,
+            "hovertemplate": "%{x}<br>%{y} samples<extra></extra>",
+            "marker": { "color": format!("rgb({r}, {g}, {b})") },
+        }),
+    };
+    if trace["x"].is_null() {
+        trace["x"] = json!(values);
+    }
+    trace["name"] = json!(name);
+    trace["xaxis"] = json!(format!("x{}", panel + 1));
+    trace["yaxis"] = json!(format!("y{}", panel + 1));
+    trace
+}
+
+/// Serializes `value` so it can be inlined in a `<script>` element, where a
+/// sample name containing `</script>` would otherwise end the element.
+fn script_json<T: serde::Serialize>(value: &T) -> serde_json::Result<String> {
+    Ok(serde_json::to_string(value)?.replace("</", "<\\/"))
+}
@@ -0,0 +1,126 @@
+//! Statistics as JSON, the way workflow engines write them: an array of
+//! objects, one per assembly, or JSON Lines (`.jsonl`, `.ndjson`) with one
+//! object per line, their fields named as the columns of a CSV. The JSON of
+//! `assembly-stats` is told apart by its fields and read by
+//! [`assembly_stats`](crate::assembly_stats).
+
+use crate::{reader, Invalid, InvalidInput, SendError};
+use csv::{Position, StringRecord};
+use serde_json::{Map, Value};
+use std::io::BufRead;
+use std::path::Path;
+
+/// Extensions of JSON Lines files.
+const LINES_EXTENSIONS: &[&str] = &["jsonl", "ndjson"];
+
+/// Whether `path` is a JSON or JSON Lines file, compressed or not.
+pub fn is_json(path: &Path) -> bool {
+    reader::format_extension(path).is_some_and(|ext| ext.eq_ignore_ascii_case("json")) || is_json_lines(path)
+}
+
+fn is_json_lines(path: &Path) -> bool {
+    reader::format_extension(path).is_some_and(|ext| LINES_EXTENSIONS.iter().any(|l| ext.eq_ignore_ascii_case(l)))
+}
+
+/// Contents of a JSON input.
+pub enum Json {
+    /// Written by `assembly-stats`.
+    AssemblyStats(Value),
+    /// Objects as the rows of a table, the fields of any of them as its
+    /// header. Each row is positioned at the line or the number of its
+    /// object, named by `unit`.
+    Table { headers: StringRecord, rows: Vec<StringRecord>, unit: &'static str },
+}
+
+/// Reads `input` as the JSON or JSON Lines file `path`.
+pub fn read_json(path: &Path, input: Box<dyn BufRead + '_>) -> Result<Json, SendError> {
+    let (objects, unit) = if is_json_lines(path) {
+        let mut objects = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            objects.push((i + 1, parse_line(path, i + 1, &line)?));
+        }
+        (objects, "line")
+    } else {
+        let json: Value = serde_json::from_reader(input)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        if crate::assembly_stats::is_stats(&json) {
+            return Ok(Json::AssemblyStats(json));
+        }
+        match json {
+            Value::Array(items) => ((1..).zip(items).collect(), "object"),
+            object @ Value::Object(_) => (vec![(1, object)], "object"),
+            _ => return Err(format!("{}: expected an array of objects", path.display()).into()),
+        }
+    };
+    let (headers, rows) = table(path, objects, unit)?;
+    Ok(Json::Table { headers, rows, unit })
+}
+
+/// The value on line `n` of the JSON Lines file `path`.
+pub fn parse_line(path: &Path, n: usize, line: &str) -> Result<Value, SendError> {
+    serde_json::from_str(line).map_err(|e| {
+        // Each line is parsed alone, so serde_json counts lines from 1
+        let message = e.to_string();
+        let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+        let message = format!("{}: line {}, column {}: {}", path.display(), n, e.column(), message);
+        InvalidInput::new(Invalid::UnparseableRow, message).into()
+    })
+}
+
+/// The header and rows of `objects` of `path`, each row at the position
+/// given with its object, in a `unit` such as `"line"`.
+pub fn table(path: &Path, objects: Vec<(usize, Value)>, unit: &str) -> Result<(StringRecord, Vec<StringRecord>), SendError> {
+    let objects = objects
+        .into_iter()
+        .map(|(n, value)| match value {
+            Value::Object(object) => Ok((n, object)),
+            _ => Err(InvalidInput::new(
+                Invalid::UnparseableRow,
+                format!("{}: {} {}: expected an object", path.display(), unit, n),
+            )),
+        })
+        .collect::<Result<Vec<(usize, Map<String, Value>)>, _>>()?;
+    let mut headers: Vec<&str> = Vec::new();
+    for (_, object) in &objects {
+        for key in object.keys() {
+            if !headers.contains(&key.as_str()) {
+                headers.push(key);
+            }
+        }
+    }
+    let rows = objects
+        .iter()
+        .map(|(n, object)| {
+            let mut row = headers
+                .iter()
+                .map(|&key| {
+                    field(object.get(key)).ok_or_else(|| {
+                        let message = format!("{}: {} {}: {:?} is not a value", path.display(), unit, n, key);
+                        InvalidInput::new(Invalid::UnparseableRow, message)
+                    })
+                })
+                .collect::<Result<StringRecord, _>>()?;
+            let mut position = Position::new();
+            position.set_line(*n as u64);
+            row.set_position(Some(position));
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>, InvalidInput>>()?;
+    Ok((StringRecord::from(headers), rows))
+}
+
+/// The value of a field as that of a CSV: empty when missing or `null`,
+/// `None` for arrays and objects.
+fn field(value: Option<&Value>) -> Option<String> {
+    match value {
+        None | Some(Value::Null) => Some(String::new()),
+        Some(Value::String(text)) => Some(text.clone()),
+        Some(Value::Bool(flag)) => Some(flag.to_string()),
+        Some(Value::Number(number)) => Some(number.to_string()),
+        Some(Value::Array(_) | Value::Object(_)) => None,
+    }
+}
@@ -0,0 +1,79 @@
+//! Placement of the metric panels of a figure on a grid of rows and columns,
+//! some of them spanning several cells, flowing onto further pages when they
+//! do not all fit on one.
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::ops::Range;
+
+/// Cells of the grid covered by one panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cell {
+    pub page: usize,
+    pub row: usize,
+    pub column: usize,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+/// Cells of panels spanning `spans`, as rows and columns, laid out in order
+/// row by row on pages of `grid` cells: each panel takes the first free cells
+/// after those of the previous one, or the top left of a new page when none
+/// are left. Spans larger than the grid are cut down to it.
+pub(crate) fn place(grid: (usize, usize), spans: &[(usize, usize)]) -> Vec<Cell> {
+    let (rows, columns) = (grid.0.max(1), grid.1.max(1));
+    let mut taken = vec![false; rows * columns];
+    let (mut page, mut next) = (0, 0);
+    let mut cells = Vec::with_capacity(spans.len());
+    for &(span_rows, span_columns) in spans {
+        let (span_rows, span_columns) = (span_rows.clamp(1, rows), span_columns.clamp(1, columns));
+        let fits = |taken: &[bool], start: usize| {
+            let (row, column) = (start / columns, start % columns);
+            row + span_rows <= rows
+                && column + span_columns <= columns
+                && (row..row + span_rows).all(|r| !taken[r * columns + column..r * columns + column + span_columns].contains(&true))
+        };
+        let start = match (next..rows * columns).find(|&start| fits(&taken, start)) {
+            Some(start) => start,
+            None => {
+                page += 1;
+                taken.fill(false);
+                0
+            }
+        };
+        let (row, column) = (start / columns, start % columns);
+        for r in row..row + span_rows {
+            taken[r * columns + column..r * columns + column + span_columns].fill(true);
+        }
+        next = start + span_columns;
+        cells.push(Cell { page, row, column, rows: span_rows, columns: span_columns });
+    }
+    cells
+}
+
+/// Indices of the panels of `cells` on each page, in page order.
+pub(crate) fn pages(cells: &[Cell]) -> Vec<Range<usize>> {
+    let mut pages: Vec<Range<usize>> = Vec::new();
+    for (i, cell) in cells.iter().enumerate() {
+        match pages.last_mut() {
+            Some(page) if cell.page == cells[page.start].page => page.end = i + 1,
+            _ => pages.push(i..i + 1),
+        }
+    }
+    pages
+}
+
+/// Part of `root`, divided evenly into `grid` cells, covered by `cell`.
+pub(crate) fn area<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    grid: (usize, usize),
+    cell: &Cell,
+) -> DrawingArea<DB, Shift> {
+    let (width, height) = root.dim_in_pixel();
+    // Rounded down as in DrawingArea::split_evenly, so cells tile exactly
+    let x = |column: usize| (width as usize * column / grid.1.max(1)) as u32;
+    let y = |row: usize| (height as usize * row / grid.0.max(1)) as u32;
+    let (left, top) = (x(cell.column), y(cell.row));
+    let (right, bottom) = (x(cell.column + cell.columns), y(cell.row + cell.rows));
+    root.clone().shrink((left, top), (right - left, bottom - top))
+}
@@ -0,0 +1,3779 @@
+//! Plots of assembly statistics produced by count-fasta-rs or computed from
+//! FASTA files.
+//!
+//! Load records into a [`Dataset`], describe the figure with a [`PlotConfig`]
+//! and write it with [`render_boxplots`]:
+//!
+//! ```no_run
+//! use count_fasta_plots::{render_boxplots, Dataset, LoadOptions, PlotConfig};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dataset = Dataset::load(&["stats.csv".into()], &LoadOptions::default())?;
+//! let config = PlotConfig { output: "stats.svg".into(), ..PlotConfig::default() };
+//! render_boxplots(&dataset, &config)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod assembly_stats;
+mod bases;
+mod busco;
+mod cache;
+mod check;
+mod cluster;
+mod compare;
+mod config;
+mod correlation;
+mod density;
+#[cfg(any(target_arch = "wasm32", feature = "ffi"))]
+mod embed;
+mod fasta;
+mod fastq;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(not(target_arch = "wasm32"))]
+mod fonts;
+mod gfa;
+mod headers;
+mod html;
+mod json;
+mod layout;
+mod live;
+mod markdown;
+mod ncbi;
+mod outliers;
+mod parquet_file;
+mod pca;
+mod pdf;
+mod progress;
+#[cfg(feature = "python")]
+mod python;
+mod quast;
+mod reader;
+mod report;
+mod seqkit;
+mod sketch;
+mod snail;
+mod sqlite;
+mod stats;
+mod style;
+mod summary;
+mod terminal;
+mod ticks;
+mod treemap;
+mod vega;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+pub use busco::Busco;
+pub use check::{check_dataset, Invalid, InvalidInput};
+pub use compare::{compare, write_comparisons, Comparison, Pairwise, Test};
+pub use config::{fitted_height, OutputFormat, PlotConfig, PlotType};
+pub use correlation::Correlation;
+pub use style::{HexColor, LabelArea, LegendPosition, MetricStyle, QuartileLabels, QuartilePosition, Style, Theme};
+pub use live::LiveInput;
+pub use markdown::write_markdown;
+pub use outliers::{flag_outliers, write_outliers, Flag, OutlierRule};
+pub use report::write_report;
+pub use sketch::Sketches;
+pub use summary::{
+    quantile, write_sketch_summaries, write_sketch_summary_table, write_summaries, write_summary_table, Bootstrap,
+    QuantileMethod, Summary,
+};
+pub use stats::{write_aggregate_stats, write_stats};
+pub use terminal::write_terminal;
+pub use ticks::{Locale, Notation, Unit};
+#[cfg(target_arch = "wasm32")]
+pub use web::draw_table;
+
+use clap::builder::PossibleValue;
+use csv::{DeserializeErrorKind, StringRecord};
+use image::ImageEncoder;
+use clap::ValueEnum;
+use plotters::prelude::*;
+use plotters::coord::ranged1d::{Ranged, SegmentedCoord, ValueFormatter};
+use plotters::coord::types::{RangedCoordf64, RangedCoordusize};
+use plotters::coord::Shift;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::BufRead;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// Statistics of one assembly, as a row of a count-fasta-rs CSV.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenomeStats {
+    #[serde(default, alias = "sample", alias = "name")]
+    pub filename: Option<String>,
+    pub assembly_length: f64,
+    pub number_of_sequences: f64,
+    #[serde(rename = "N50")]
+    pub n50: f64,
+    #[serde(rename = "GC_percentage")]
+    pub gc_percentage: f64,
+    #[serde(rename = "N_percentage")]
+    pub n_percentage: f64,
+    /// Area under the Nx curve, the mean length of the sequence a random
+    /// base lies in; optional in CSVs.
+    #[serde(default, rename = "auN")]
+    pub aun: Option<f64>,
+    /// Number of the longest sequences covering half and 90% of the
+    /// assembly; optional in CSVs.
+    #[serde(default, rename = "L50")]
+    pub l50: Option<f64>,
+    #[serde(default, rename = "L90")]
+    pub l90: Option<f64>,
+    /// Lengths of the longest and shortest sequences; optional in CSVs.
+    #[serde(default)]
+    pub largest_contig: Option<f64>,
+    #[serde(default)]
+    pub shortest_contig: Option<f64>,
+    /// Number of runs of N and their total length, from the `Gaps` and
+    /// `N_count` columns of count-fasta-rs; optional in CSVs.
+    #[serde(default, rename = "Gaps")]
+    pub gaps: Option<f64>,
+    #[serde(default)]
+    pub gap_length: Option<f64>,
+    /// Value of the `--group-by` column, filled in after deserialization.
+    #[serde(skip)]
+    pub group: Option<String>,
+    /// Value of the `--color-by` column, filled in after deserialization.
+    #[serde(skip)]
+    pub color: Option<String>,
+    /// Sequence lengths, longest first, when computed from a FASTA file.
+    #[serde(skip)]
+    pub lengths: Vec<u64>,
+    /// GC percentage of each sequence in the order of [`Self::lengths`], NaN
+    /// for those without bases, when computed from a FASTA file.
+    #[serde(skip)]
+    pub sequence_gc: Vec<f64>,
+    /// Values of the `--extra-column` columns, filled in after
+    /// deserialization.
+    #[serde(skip)]
+    pub extra: HashMap<String, f64>,
+    /// Path of the file the record was read from.
+    #[serde(skip)]
+    pub source: Option<String>,
+    /// Gene completeness, when a BUSCO summary of the assembly was given.
+    #[serde(skip)]
+    pub busco: Option<Busco>,
+    /// Phred quality of a read of a FASTQ file, which is read as a set of
+    /// one sequence.
+    #[serde(skip)]
+    pub mean_quality: Option<f64>,
+}
+
+impl GenomeStats {
+    /// Name used to identify this record, falling back to its row number
+    /// when the input has no file name column.
+    pub fn name(&self, row: usize) -> String {
+        match &self.filename {
+            Some(name) => name.clone(),
+            None => format!("row {}", row + 1),
+        }
+    }
+
+    /// Group of this record, `NA` when it has none.
+    pub fn group_name(&self) -> &str {
+        self.group.as_deref().unwrap_or("NA")
+    }
+
+    /// Class of this record for `--color-by`, `NA` when it has none.
+    pub fn color_name(&self) -> &str {
+        self.color.as_deref().unwrap_or("NA")
+    }
+
+    /// GC percentages of the sequences that have bases.
+    pub fn known_sequence_gc(&self) -> Vec<f64> {
+        self.sequence_gc.iter().copied().filter(|gc| !gc.is_nan()).collect()
+    }
+}
+
+/// Distinct groups in order of first appearance, or nothing when the records
+/// are not grouped.
+fn group_labels(data: &[GenomeStats]) -> Vec<&str> {
+    if data.iter().all(|x| x.group.is_none()) {
+        return Vec::new();
+    }
+    let mut labels: Vec<&str> = Vec::new();
+    for record in data {
+        if !labels.contains(&record.group_name()) {
+            labels.push(record.group_name());
+        }
+    }
+    labels
+}
+
+/// Distinct `--color-by` classes in order of first appearance, or nothing
+/// when the records are not colored by a column.
+fn color_labels(data: &[GenomeStats]) -> Vec<&str> {
+    if data.iter().all(|x| x.color.is_none()) {
+        return Vec::new();
+    }
+    let mut labels: Vec<&str> = Vec::new();
+    for record in data {
+        if !labels.contains(&record.color_name()) {
+            labels.push(record.color_name());
+        }
+    }
+    labels
+}
+
+/// What the points of scatter plots and of `--points` are colored by: their
+/// `--color-by` class when the records have one, in the colors after those
+/// of the groups so that the two do not clash, and their group otherwise.
+struct PointColors<'a> {
+    classes: Vec<&'a str>,
+    class: fn(&GenomeStats) -> &str,
+    /// Whether the classes are the `--color-by` ones rather than the groups.
+    by_column: bool,
+    offset: usize,
+}
+
+impl<'a> PointColors<'a> {
+    fn new(data: &'a [GenomeStats]) -> Self {
+        let (groups, colors) = (group_labels(data), color_labels(data));
+        match colors.is_empty() {
+            true => PointColors { classes: groups, class: GenomeStats::group_name, by_column: false, offset: 0 },
+            false => PointColors { offset: groups.len(), classes: colors, class: GenomeStats::color_name, by_column: true },
+        }
+    }
+
+    /// Color of the `c`th class.
+    fn color_of(&self, c: usize, style: &Style) -> RGBColor {
+        style.group_color(self.offset + c)
+    }
+
+    /// Color of the point of `record`, the box color when there are no
+    /// classes.
+    fn color(&self, record: &GenomeStats, style: &Style) -> RGBColor {
+        match self.classes.iter().position(|c| *c == (self.class)(record)) {
+            Some(c) => self.color_of(c, style),
+            None => style.box_color.0,
+        }
+    }
+}
+
+/// One of the columns of a count-fasta-rs CSV that can be plotted, a BUSCO
+/// result, a statistic of FASTQ reads, or any other numeric column given with
+/// `--extra-column`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Metric {
+    AssemblyLength,
+    NumberOfSequences,
+    N50,
+    GcPercentage,
+    NPercentage,
+    /// Area under the Nx curve, see [`GenomeStats::aun`].
+    Aun,
+    /// See [`GenomeStats::l50`].
+    L50,
+    L90,
+    /// See [`GenomeStats::largest_contig`].
+    LargestContig,
+    ShortestContig,
+    /// See [`GenomeStats::gaps`]; the mean gap size is the total length over
+    /// the count.
+    GapCount,
+    GapLength,
+    MeanGap,
+    /// Percentages of [`Busco`] groups found complete, fragmented or missing.
+    BuscoComplete,
+    BuscoFragmented,
+    BuscoMissing,
+    /// Length and [`GenomeStats::mean_quality`] of the reads of FASTQ files.
+    ReadLength,
+    MeanQuality,
+    /// The CSV column with this header.
+    Column(String),
+}
+
+impl Metric {
+    pub fn title(&self) -> Cow<'_, str> {
+        Cow::Borrowed(match self {
+            Metric::AssemblyLength => "Assembly size (bp.)",
+            Metric::NumberOfSequences => "Scaffold count",
+            Metric::N50 => "N50 (bp.)",
+            Metric::GcPercentage => "GC ratio (%)",
+            Metric::NPercentage => "N's ratio (%)",
+            Metric::Aun => "auN (bp.)",
+            Metric::L50 => "L50",
+            Metric::L90 => "L90",
+            Metric::LargestContig => "Largest contig (bp.)",
+            Metric::ShortestContig => "Shortest contig (bp.)",
+            Metric::GapCount => "Gap count",
+            Metric::GapLength => "Total gap length (bp.)",
+            Metric::MeanGap => "Mean gap size (bp.)",
+            Metric::BuscoComplete => "BUSCO complete (%)",
+            Metric::BuscoFragmented => "BUSCO fragmented (%)",
+            Metric::BuscoMissing => "BUSCO missing (%)",
+            Metric::ReadLength => "Read length (bp.)",
+            Metric::MeanQuality => "Mean read quality",
+            Metric::Column(header) => return column_label(header),
+        })
+    }
+
+    pub fn xlabel(&self) -> Cow<'_, str> {
+        Cow::Borrowed(match self {
+            Metric::AssemblyLength | Metric::N50 | Metric::Aun => "bp.",
+            Metric::LargestContig | Metric::ShortestContig | Metric::ReadLength => "bp.",
+            Metric::GapLength | Metric::MeanGap => "bp.",
+            Metric::NumberOfSequences | Metric::L50 | Metric::L90 | Metric::GapCount => "Count",
+            Metric::MeanQuality => "Phred quality",
+            Metric::GcPercentage => "GC ratio (%)",
+            Metric::NPercentage => "Ratio (%)",
+            Metric::BuscoComplete | Metric::BuscoFragmented | Metric::BuscoMissing => "BUSCO groups (%)",
+            Metric::Column(header) => return column_label(header),
+        })
+    }
+
+    pub fn unit(&self) -> Unit {
+        match self {
+            Metric::AssemblyLength | Metric::N50 | Metric::Aun => Unit::BasePairs,
+            Metric::LargestContig | Metric::ShortestContig | Metric::ReadLength => Unit::BasePairs,
+            Metric::GapLength | Metric::MeanGap => Unit::BasePairs,
+            Metric::GapCount => Unit::Count,
+            Metric::NumberOfSequences | Metric::L50 | Metric::L90 | Metric::MeanQuality => Unit::Count,
+            Metric::GcPercentage | Metric::NPercentage => Unit::Percent,
+            Metric::BuscoComplete | Metric::BuscoFragmented | Metric::BuscoMissing => Unit::Percent,
+            Metric::Column(header) if header.contains('%') => Unit::Percent,
+            Metric::Column(_) => Unit::Count,
+        }
+    }
+
+    /// Value of this metric in `stats`, NaN for a column the record was not
+    /// read with or a BUSCO result it has none of.
+    pub fn value(&self, stats: &GenomeStats) -> f64 {
+        match self {
+            Metric::AssemblyLength => stats.assembly_length,
+            Metric::NumberOfSequences => stats.number_of_sequences,
+            Metric::N50 => stats.n50,
+            Metric::GcPercentage => stats.gc_percentage,
+            Metric::NPercentage => stats.n_percentage,
+            Metric::Aun => stats.aun.unwrap_or(f64::NAN),
+            Metric::L50 => stats.l50.unwrap_or(f64::NAN),
+            Metric::L90 => stats.l90.unwrap_or(f64::NAN),
+            Metric::LargestContig => stats.largest_contig.unwrap_or(f64::NAN),
+            Metric::ShortestContig => stats.shortest_contig.unwrap_or(f64::NAN),
+            Metric::GapCount => stats.gaps.unwrap_or(f64::NAN),
+            Metric::GapLength => stats.gap_length.unwrap_or(f64::NAN),
+            // NaN without gaps, whose mean size is undefined
+            Metric::MeanGap => match (stats.gap_length, stats.gaps) {
+                (Some(length), Some(gaps)) if gaps > 0.0 => length / gaps,
+                _ => f64::NAN,
+            },
+            Metric::BuscoComplete => stats.busco.map_or(f64::NAN, |b| b.complete),
+            Metric::BuscoFragmented => stats.busco.map_or(f64::NAN, |b| b.fragmented),
+            Metric::BuscoMissing => stats.busco.map_or(f64::NAN, |b| b.missing),
+            Metric::ReadLength => stats.assembly_length,
+            Metric::MeanQuality => stats.mean_quality.unwrap_or(f64::NAN),
+            Metric::Column(header) => stats.extra.get(header).copied().unwrap_or(f64::NAN),
+        }
+    }
+}
+
+/// Caption of a column given by its header: `mean_coverage` becomes
+/// `Mean coverage`, while headers with spaces are kept as they are.
+fn column_label(header: &str) -> Cow<'_, str> {
+    if header.contains(' ') {
+        return Cow::Borrowed(header);
+    }
+    let words = header.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => Cow::Owned(first.to_uppercase().chain(chars).collect()),
+        None => Cow::Borrowed(header),
+    }
+}
+
+/// Only the built-in metrics are command line values; columns are given by
+/// their header.
+impl ValueEnum for Metric {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Metric::AssemblyLength,
+            Metric::NumberOfSequences,
+            Metric::N50,
+            Metric::GcPercentage,
+            Metric::NPercentage,
+            Metric::Aun,
+            Metric::L50,
+            Metric::L90,
+            Metric::LargestContig,
+            Metric::ShortestContig,
+            Metric::GapCount,
+            Metric::GapLength,
+            Metric::MeanGap,
+            Metric::BuscoComplete,
+            Metric::BuscoFragmented,
+            Metric::BuscoMissing,
+            Metric::ReadLength,
+            Metric::MeanQuality,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(match self {
+            Metric::AssemblyLength => "assembly_length",
+            Metric::NumberOfSequences => "number_of_sequences",
+            Metric::N50 => "n50",
+            Metric::GcPercentage => "gc_percentage",
+            Metric::NPercentage => "n_percentage",
+            Metric::Aun => "aun",
+            Metric::L50 => "l50",
+            Metric::L90 => "l90",
+            Metric::LargestContig => "largest_contig",
+            Metric::ShortestContig => "shortest_contig",
+            Metric::GapCount => "gap_count",
+            Metric::GapLength => "gap_length",
+            Metric::MeanGap => "mean_gap",
+            Metric::BuscoComplete => "busco_complete",
+            Metric::BuscoFragmented => "busco_fragmented",
+            Metric::BuscoMissing => "busco_missing",
+            Metric::ReadLength => "read_length",
+            Metric::MeanQuality => "mean_quality",
+            Metric::Column(_) => return None,
+        }))
+    }
+}
+
+impl std::fmt::Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => f.write_str(value.get_name()),
+            None => f.write_str(match self {
+                Metric::Column(header) => header,
+                _ => unreachable!("only columns are skipped"),
+            }),
+        }
+    }
+}
+
+/// Built-in metrics by name, anything else as a column header.
+impl From<String> for Metric {
+    fn from(name: String) -> Self {
+        <Metric as ValueEnum>::from_str(&name, false).unwrap_or(Metric::Column(name))
+    }
+}
+
+impl From<Metric> for String {
+    fn from(metric: Metric) -> Self {
+        metric.to_string()
+    }
+}
+
+/// A chart with values along `X`, linear or logarithmic, and rows or counts
+/// along the vertical axis.
+type Chart<'a, DB, X = RangedCoordf64> = ChartContext<'a, DB, Cartesian2d<X, RangedCoordf64>>;
+
+fn create_boxplot<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    plot: &mut Chart<DB, X>,
+    data: &[f64],
+    y_position: f64,
+    color: RGBColor,
+    x_range: (f64, f64),
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let summary = Summary::compute(data, config.quantile_method);
+    let interval = config.bootstrap.map(|bootstrap| bootstrap.median_interval(data));
+    let interval = interval.map(|(low, high)| (clamp(low, x_range), clamp(high, x_range)));
+    draw_box(plot, &clip_summary(&summary, x_range), interval, y_position, color, config)?;
+
+    // Draw outlier points
+    let outliers: Vec<_> = data.iter()
+        .filter(|&&x| summary.is_outlier(x) && within(x, x_range))
+        .collect();
+    
+    plot.draw_series(outliers.iter().map(|&&x| {
+        Circle::new((x, y_position), config.style.px(3), config.style.outlier_color.0.filled())
+    }))?;
+    
+    Ok(())
+}
+
+/// `summary` as drawn on an axis from `lo` to `hi`: its box and whiskers cut
+/// at the ends of the axis, while the values outside it still count towards
+/// the quartiles.
+fn clip_summary(summary: &Summary, x_range: (f64, f64)) -> Summary {
+    Summary {
+        q1: clamp(summary.q1, x_range),
+        median: clamp(summary.median, x_range),
+        q3: clamp(summary.q3, x_range),
+        mean: clamp(summary.mean, x_range),
+        whisker_low: clamp(summary.whisker_low, x_range),
+        whisker_high: clamp(summary.whisker_high, x_range),
+        ..summary.clone()
+    }
+}
+
+fn clamp(value: f64, (lo, hi): (f64, f64)) -> f64 {
+    value.max(lo).min(hi)
+}
+
+fn within(value: f64, (lo, hi): (f64, f64)) -> bool {
+    (lo..=hi).contains(&value)
+}
+
+/// Draws the box, median, mean and whiskers of `summary`, without its
+/// outliers, with `interval` as the bootstrap confidence interval of the
+/// median when there is one.
+fn draw_box<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    plot: &mut Chart<DB, X>,
+    summary: &Summary,
+    interval: Option<(f64, f64)>,
+    y_position: f64,
+    color: RGBColor,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let line = style.px(1);
+    let &Summary { q1, q3, median: med, whisker_low: whisker_min, whisker_high: whisker_max, .. } =
+        summary;
+
+    // Draw box
+    let median_height = if config.notch {
+        // Pinched to half height at the median, with the notches drawn within
+        // the hinges even when the interval reaches past them
+        let (low, high) = interval.unwrap_or_else(|| summary.notch());
+        let (low, high) = (low.max(q1), high.min(q3));
+        let (top, bottom) = (y_position + 0.3, y_position - 0.3);
+        let outline = vec![
+            (q1, bottom), (low, bottom), (med, y_position - 0.15), (high, bottom), (q3, bottom),
+            (q3, top), (high, top), (med, y_position + 0.15), (low, top), (q1, top), (q1, bottom),
+        ];
+        plot.draw_series(std::iter::once(PathElement::new(outline, color.mix(0.3).stroke_width(line))))?;
+        0.15
+    } else {
+        plot.draw_series(std::iter::once(Rectangle::new(
+            [(q1, y_position - 0.3), (q3, y_position + 0.3)],
+            color.mix(0.3).stroke_width(line),
+        )))?;
+        0.3
+    };
+    
+    // Draw median line, as a line rather than a box so that it keeps its
+    // width on log axes
+    plot.draw_series(std::iter::once(PathElement::new(
+        vec![(med, y_position - median_height), (med, y_position + median_height)],
+        style.median_color.0.stroke_width(style.px(2)),
+    )))?;
+
+    // Without notches, the bootstrap interval of the median is an error bar
+    if let Some((low, high)) = interval.filter(|_| !config.notch) {
+        let cap = 0.1;
+        plot.draw_series([
+            PathElement::new(vec![(low, y_position), (high, y_position)], style.median_color.0.stroke_width(line)),
+            PathElement::new(vec![(low, y_position - cap), (low, y_position + cap)], style.median_color.0.stroke_width(line)),
+            PathElement::new(vec![(high, y_position - cap), (high, y_position + cap)], style.median_color.0.stroke_width(line)),
+        ])?;
+    }
+
+    if config.mean {
+        let r = style.px(5) as i32;
+        let diamond = vec![(0, -r), (r, 0), (0, r), (-r, 0)];
+        plot.draw_series(std::iter::once(
+            EmptyElement::at((summary.mean, y_position))
+                + Polygon::new(diamond.clone(), style.background.0.filled())
+                + PathElement::new(
+                    [diamond, vec![(0, -r)]].concat(),
+                    style.median_color.0.stroke_width(line),
+                ),
+        ))?;
+    }
+    
+    // Draw whiskers
+    plot.draw_series(std::iter::once(PathElement::new(
+        vec![(whisker_min, y_position), (q1, y_position)],
+        style.whisker_color.0.stroke_width(line),
+    )))?;
+    plot.draw_series(std::iter::once(PathElement::new(
+        vec![(q3, y_position), (whisker_max, y_position)],
+        style.whisker_color.0.stroke_width(line),
+    )))?;
+
+    Ok(())
+}
+
+/// Writes the file name of every outlier among `records` next to its circle,
+/// alternately above and below so that neighbouring names overlap less.
+fn label_outliers<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    plot: &mut Chart<DB, X>,
+    records: &[&GenomeStats],
+    metric: &Metric,
+    y_position: f64,
+    x_range: (f64, f64),
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let values: Vec<f64> = records.iter().map(|x| metric.value(x)).collect();
+    let summary = Summary::compute(&values, config.quantile_method);
+    let mut outliers: Vec<(f64, &str)> = records
+        .iter()
+        .zip(&values)
+        .filter(|(_, &x)| summary.is_outlier(x) && within(x, x_range))
+        .filter_map(|(record, &x)| Some((x, record.filename.as_deref()?)))
+        .collect();
+    outliers.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let font = style.text(style.label_size.saturating_sub(2).max(1));
+    let gap = style.px(5) as i32;
+    plot.draw_series(outliers.iter().enumerate().map(|(i, &(x, name))| {
+        let (offset, anchor) = if i % 2 == 0 { (-gap, VPos::Bottom) } else { (gap, VPos::Top) };
+        EmptyElement::at((x, y_position))
+            + Text::new(name.to_string(), (0, offset), font.pos(Pos::new(HPos::Center, anchor)))
+    }))?;
+    Ok(())
+}
+
+/// Writes the values of the quartiles and median of `summary` that are on the
+/// axis along its box at `y_position`, on the side and in the size of the style, as the ticks of
+/// the `metric` axis are written.
+fn label_quartiles<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    plot: &mut Chart<DB, X>,
+    summary: &Summary,
+    metric: &Metric,
+    y_position: f64,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let formatter = value_formatter(config, metric);
+    let label = |value: f64| match &formatter {
+        Some(formatter) => formatter(&value),
+        None => RangedCoordf64::format(&value),
+    };
+    let size = style.quartile_labels.size.unwrap_or(style.label_size.saturating_sub(2).max(1));
+    let (y, offset, anchor) = match style.quartile_labels.position {
+        QuartilePosition::Above => (y_position + 0.3, -(style.px(2) as i32), VPos::Bottom),
+        QuartilePosition::Below => (y_position - 0.3, style.px(2) as i32, VPos::Top),
+    };
+    // The quartiles outwards from the hinges, so that narrow boxes keep
+    // their labels apart
+    let labels = [(summary.q1, HPos::Right), (summary.median, HPos::Center), (summary.q3, HPos::Left)];
+    let x_range = plot.x_range();
+    let visible = labels.into_iter().filter(|&(x, _)| within(x, (x_range.start, x_range.end)));
+    plot.draw_series(visible.map(|(x, align)| {
+        EmptyElement::at((x, y)) + Text::new(label(x), (0, offset), style.text(size).pos(Pos::new(align, anchor)))
+    }))?;
+    Ok(())
+}
+
+/// Draws every value of `data` within `x_range` as a translucent point in the
+/// `color` of its index, spread vertically around `y_position`, as the seed
+/// of `config` has it, so that equal values stay visible.
+fn draw_points<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    plot: &mut Chart<DB, X>,
+    data: &[f64],
+    y_position: f64,
+    color: impl Fn(usize) -> RGBColor,
+    x_range: (f64, f64),
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let radius = config.style.px(2);
+    let visible = data.iter().enumerate().filter(|&(_, &x)| within(x, x_range));
+    plot.draw_series(visible.map(|(i, &x)| {
+        Circle::new((x, y_position + jitter(i, config.seed, 0.25)), radius, color(i).mix(0.6).filled())
+    }))?;
+    Ok(())
+}
+
+/// Offset of the `i`th point within `spread` either way. The offsets follow
+/// the golden ratio sequence rather than a random one, so that they cover the
+/// band evenly and the figure is the same on every run; the `seed` is where
+/// the sequence starts, and another seed moves every point.
+fn jitter(i: usize, seed: u64, spread: f64) -> f64 {
+    // Fractions of 2^64, so that any seed is exact
+    let x = (i as u64).wrapping_add(seed).wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 11;
+    (x as f64 / (1u64 << 53) as f64 * 2.0 - 1.0) * spread
+}
+
+/// Curves beyond this many are drawn without legend entries.
+const MAX_LEGEND_ENTRIES: usize = 20;
+
+/// Kernel density estimate of the `metric` values `data` as points of a
+/// curve clipped to the visible `x_range`, or `None` when they have no spread.
+/// On a log axis the density is estimated over the logarithms of the values,
+/// so that its shape matches the axis, and is per decade.
+fn density_curve(data: &[f64], metric: &Metric, x_range: (f64, f64), config: &PlotConfig) -> Option<Vec<(f64, f64)>> {
+    let log = config.is_log(metric);
+    let logs: Vec<f64>;
+    let (data, x_range) = if log {
+        logs = data.iter().filter(|v| **v > 0.0).map(|v| v.log10()).collect();
+        (&logs[..], (x_range.0.log10(), x_range.1.log10()))
+    } else {
+        (data, x_range)
+    };
+    let bandwidth = match config.style.bandwidth(metric) {
+        Some(bandwidth) => bandwidth,
+        None => density::silverman_bandwidth(data)? * config.bandwidth_adjust,
+    };
+    if data.is_empty() || bandwidth <= 0.0 {
+        return None;
+    }
+    let min = data.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+    let max = data.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    // Let the tails run out past the extreme values, as far as the axis allows
+    let grid = density::linspace(
+        (min - 2.0 * bandwidth).max(x_range.0),
+        (max + 2.0 * bandwidth).min(x_range.1),
+        200,
+    );
+    let densities = density::gaussian_kde(data, bandwidth, &grid);
+    let grid: Vec<f64> = if log { grid.iter().map(|x| 10f64.powf(*x)).collect() } else { grid };
+    Some(grid.into_iter().zip(densities).collect())
+}
+
+/// Draws a kernel density estimate of `data` mirrored around `y_position`,
+/// clipped to the visible `x_range`.
+fn create_violin<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    plot: &mut Chart<DB, X>,
+    data: &[f64],
+    y_position: f64,
+    color: RGBColor,
+    x_range: (f64, f64),
+    metric: &Metric,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let Some(curve) = density_curve(data, metric, x_range, config) else {
+        return Ok(());
+    };
+    let peak = curve.iter().fold(0.0, |a: f64, &(_, d)| a.max(d));
+    // Widest point of the violin spans 0.8 of the row
+    let scale = 0.4 / peak;
+
+    let upper = curve.iter().map(|&(x, d)| (x, y_position + d * scale));
+    let lower = curve.iter().rev().map(|&(x, d)| (x, y_position - d * scale));
+    let outline: Vec<(f64, f64)> = upper.chain(lower).collect();
+
+    plot.draw_series(std::iter::once(Polygon::new(outline.clone(), color.mix(0.3).filled())))?;
+    let mut closed = outline;
+    closed.push(closed[0]);
+    plot.draw_series(std::iter::once(PathElement::new(closed, color.stroke_width(config.style.px(1)))))?;
+
+    Ok(())
+}
+
+/// Draws one panel per metric on a `rows x cols` grid, placed by
+/// [`layout::place`] and all fitting on this page. `first_panel` is the index
+/// of the first metric in the whole figure, used for the panel letters.
+fn draw_figure<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    first_panel: usize,
+    grid: (usize, usize),
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    for (area, panel) in draw_around_panels(root, data, metrics, first_panel, grid, config)? {
+        draw_panel(&area, data, &panel, config)?;
+    }
+    Ok(())
+}
+
+/// Draws the background, frame and legend of the figure of [`draw_figure`]
+/// and returns the area of each of its panels, left to draw, or draws the
+/// whole figure and returns none when it is a single chart.
+fn draw_around_panels<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    first_panel: usize,
+    grid: (usize, usize),
+    config: &PlotConfig,
+) -> Result<PanelAreas<DB>, Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    // Browsers resolve font families themselves
+    #[cfg(not(target_arch = "wasm32"))]
+    fonts::load(&config.style, config.deterministic)?;
+    if !config.style.transparent {
+        root.fill(&config.style.background.0)?;
+    }
+    let root = &draw_frame(root, &config.style)?;
+    match config.plot_type {
+        PlotType::Pairs => draw_pairs(root, data, metrics, config)?,
+        PlotType::Nx => draw_nx(root, data, config)?,
+        PlotType::Cumulative => draw_cumulative(root, data, config)?,
+        PlotType::Lengths => draw_lengths(root, data, config)?,
+        PlotType::SequenceGc => draw_sequence_gc(root, data, config)?,
+        PlotType::Treemap => draw_treemaps(root, data, config)?,
+        PlotType::Snail => draw_snails(root, data, config)?,
+        PlotType::Correlation => draw_correlation(root, data, metrics, config)?,
+        PlotType::Pca => draw_pca(root, data, metrics, config)?,
+        PlotType::Dendrogram => draw_dendrogram(root, data, metrics, config)?,
+        _ => {
+            let groups = group_labels(data);
+            let points = PointColors::new(data);
+            // The same entries as the legend of the first panel would have
+            let colored = matches!(config.plot_type, PlotType::Boxplot | PlotType::Violin) && config.points && points.by_column;
+            let outside = config.style.legend == Some(LegendPosition::Outside)
+                && config.plot_type != PlotType::Ridgeline
+                && (colored || !groups.is_empty());
+            let root = match outside {
+                true => {
+                    let room = config.style.px(config.style.label_area.right.unwrap_or(120));
+                    let (panels, legend) = root.split_horizontally(root.dim_in_pixel().0.saturating_sub(room));
+                    draw_figure_legend(&legend, &groups, colored.then_some(&points), &config.style)?;
+                    panels
+                }
+                false => root.clone(),
+            };
+            return Ok(panel_areas(&root, metrics, first_panel, grid, outside, config));
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Areas of the panels of `metrics` within `root`, laid out on `grid`, with
+/// the groups identified on the first unless `legend_outside`.
+fn panel_areas<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    metrics: &[Metric],
+    first_panel: usize,
+    grid: (usize, usize),
+    legend_outside: bool,
+    config: &PlotConfig,
+) -> Vec<(DrawingArea<DB, Shift>, Panel)> {
+    let spans: Vec<(usize, usize)> = metrics.iter().map(|metric| config.style.span(metric)).collect();
+    let cells = layout::place(grid, &spans);
+    cells
+        .iter()
+        .zip(metrics)
+        .enumerate()
+        .map(|(i, (cell, metric))| {
+            let panel = Panel {
+                metric: metric.clone(),
+                letter: panel_letter(first_panel.saturating_add(i)),
+                // Groups are identified once, on the first panel of each page
+                // or beside all of them
+                legend: i == 0 && !legend_outside,
+            };
+            (layout::area(root, grid, cell), panel)
+        })
+        .collect()
+}
+
+/// Draws one panel of a figure of `config.plot_type` drawn panel by panel.
+fn draw_panel<DB: DrawingBackend>(
+    plot_area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let groups = &group_labels(data);
+    match config.plot_type {
+        PlotType::Boxplot | PlotType::Violin => {
+            draw_distribution_panel(plot_area, data, panel, groups, config)?
+        }
+        PlotType::Histogram => {
+            draw_histogram_panel(plot_area, data, panel, groups, config)?
+        }
+        PlotType::Ranked | PlotType::Dots => draw_ranked_panel(plot_area, data, panel, groups, config)?,
+        PlotType::Ecdf => draw_ecdf_panel(plot_area, data, panel, groups, config)?,
+        PlotType::Density => draw_density_panel(plot_area, data, panel, groups, config)?,
+        PlotType::Ridgeline => draw_ridgeline_panel(plot_area, data, panel, groups, config)?,
+        PlotType::Pairs
+        | PlotType::Nx
+        | PlotType::Cumulative
+        | PlotType::Lengths
+        | PlotType::SequenceGc
+        | PlotType::Treemap
+        | PlotType::Snail
+        | PlotType::Correlation
+        | PlotType::Pca
+        | PlotType::Dendrogram => unreachable!("drawn as a single chart"),
+    }
+    debug!("Drew {} panel", panel.metric);
+    Ok(())
+}
+
+/// Draws the title and footer of the figure, when it has them, and returns
+/// the area left between them for the charts.
+fn draw_frame<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    style: &Style,
+) -> Result<DrawingArea<DB, Shift>, Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let mut area = match &style.title {
+        Some(title) => root.titled(title, style.caption_font(24))?,
+        None => root.clone(),
+    };
+    if let Some(footer) = &style.footer {
+        let font = style.text(style.label_size.saturating_sub(2).max(1));
+        let height = area.dim_in_pixel().1;
+        let (rest, bottom) = area.split_vertically(height.saturating_sub(style.px(style.label_size + 8)));
+        let (_, footer_height) = bottom.dim_in_pixel();
+        bottom.draw(&Text::new(
+            footer.as_str(),
+            (style.px(5) as i32, footer_height as i32 / 2),
+            font.pos(Pos::new(HPos::Left, VPos::Center)),
+        ))?;
+        area = rest;
+    }
+    Ok(area)
+}
+
+/// What a single subplot shows and how it is labelled.
+struct Panel {
+    metric: Metric,
+    letter: String,
+    legend: bool,
+}
+
+/// Letter of the `index`th panel, counting from zero: `A` to `Z`, then `AA`,
+/// `AB` and on as spreadsheet columns are.
+pub(crate) fn panel_letter(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(char::from(b'A' + (index % 26) as u8));
+        match (index / 26).checked_sub(1) {
+            Some(next) => index = next,
+            None => break,
+        }
+    }
+    letters.iter().rev().collect()
+}
+
+/// Areas left for the panels of a figure once the rest of it is drawn.
+type PanelAreas<DB> = Vec<(DrawingArea<DB, Shift>, Panel)>;
+
+/// Records of each group, or all records when ungrouped, paired with the
+/// color they are drawn in.
+fn grouped_records<'a>(
+    data: &'a [GenomeStats],
+    groups: &[&str],
+    style: &Style,
+) -> Vec<(Vec<&'a GenomeStats>, RGBColor)> {
+    if groups.is_empty() {
+        return vec![(data.iter().collect(), style.box_color.0)];
+    }
+    groups
+        .iter()
+        .enumerate()
+        .map(|(g, group)| {
+            let records = data.iter().filter(|x| x.group_name() == *group).collect();
+            (records, style.group_color(g))
+        })
+        .collect()
+}
+
+/// Values of `metric` for each group, or for all records when ungrouped,
+/// paired with the color they are drawn in.
+fn grouped_values(
+    data: &[GenomeStats],
+    metric: &Metric,
+    groups: &[&str],
+    style: &Style,
+) -> Vec<(Vec<f64>, RGBColor)> {
+    grouped_records(data, groups, style)
+        .into_iter()
+        .map(|(records, color)| {
+            let values = records.iter().map(|x| metric.value(x)).filter(|v| v.is_finite());
+            (values.collect(), color)
+        })
+        .collect()
+}
+
+/// Smallest and largest value of `metric`, or the unit interval when no
+/// record has one.
+fn value_range(data: &[GenomeStats], metric: &Metric) -> (f64, f64) {
+    let values = data.iter().map(|x| metric.value(x));
+    let min = values.clone().fold(f64::INFINITY, f64::min);
+    let max = values.fold(f64::NEG_INFINITY, f64::max);
+    if min > max {
+        // Nothing to draw
+        return (0.0, 1.0);
+    }
+    (min, max)
+}
+
+/// Range of the value axis of a `metric` panel: the configured range, or
+/// the values with `padding` of their spread added on either side. On a `log`
+/// axis only positive values count and the padding is a fraction of decades.
+fn axis_range(
+    data: &[GenomeStats],
+    metric: &Metric,
+    config: &PlotConfig,
+    padding: f64,
+) -> Result<(f64, f64), Box<dyn Error>> {
+    let positive = data.iter().map(|x| metric.value(x)).filter(|&v| v > 0.0);
+    extent_range(value_range(data, metric), positive.fold(f64::INFINITY, f64::min), metric, config, padding)
+}
+
+/// [`axis_range`] of values from `min` to `max`, the smallest positive of
+/// them being `min_positive`, infinite when none is.
+fn extent_range(
+    (min, max): (f64, f64),
+    min_positive: f64,
+    metric: &Metric,
+    config: &PlotConfig,
+    padding: f64,
+) -> Result<(f64, f64), Box<dyn Error>> {
+    let log = config.is_log(metric);
+    if let Some((lo, hi)) = config.style.range(metric) {
+        if log && lo <= 0.0 {
+            return Err(format!("the range of {metric} must be positive on a log axis").into());
+        }
+        return Ok((lo, hi));
+    }
+    if !log {
+        let padding = (max - min) * padding;
+        return Ok((min - padding, max + padding));
+    }
+    if min_positive > max {
+        // Nothing to draw; show a single decade
+        return Ok((1.0, 10.0));
+    }
+    let decades = (max.log10() - min_positive.log10()).max(1.0);
+    let factor = 10f64.powf(decades * padding);
+    Ok((min_positive / factor, max * factor))
+}
+
+/// Extends a log axis down to the power of ten below `hi`, since plotters
+/// only places ticks from the first power of ten in range on.
+fn log_axis((lo, hi): (f64, f64)) -> std::ops::Range<f64> {
+    lo.min(10f64.powf(hi.log10().floor()))..hi
+}
+
+type TickFormatter = Box<dyn Fn(&f64) -> String>;
+
+/// Formatter of the value axis of a `metric` panel, or `None` to keep the
+/// plotters default.
+fn value_formatter(config: &PlotConfig, metric: &Metric) -> Option<TickFormatter> {
+    let (unit, locale) = (metric.unit(), config.style.locale);
+    let (notation, decimals) = (config.style.notation(metric), config.style.decimals(metric));
+    if notation.is_some() || decimals.is_some() {
+        let default = if config.raw_ticks { Notation::Plain } else { Notation::Units };
+        let notation = notation.unwrap_or(default);
+        Some(Box::new(move |v| ticks::write(*v, unit, notation, decimals, locale)))
+    } else if !config.raw_ticks {
+        Some(Box::new(move |v| ticks::format(*v, unit, locale)))
+    } else if config.is_log(metric) {
+        Some(Box::new(move |v| ticks::scientific(*v, locale)))
+    } else if locale != Locale::default() {
+        Some(Box::new(move |v| locale.number(&RangedCoordf64::format(v))))
+    } else {
+        None
+    }
+}
+
+/// Draws the reference line configured for `metric` across the chart,
+/// labeled at its top, when it falls within the value axis.
+fn draw_threshold<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    chart: &mut Chart<DB, X>,
+    metric: &Metric,
+    style: &Style,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let Some((value, label)) = style.threshold(metric) else {
+        return Ok(());
+    };
+    let (x_range, y_range) = (chart.x_range(), chart.y_range());
+    if !(x_range.start..=x_range.end).contains(&value) {
+        return Ok(());
+    }
+    chart.draw_series(DashedLineSeries::new(
+        [(value, y_range.start), (value, y_range.end)],
+        style.px(4),
+        style.px(3),
+        style.foreground.0.stroke_width(style.px(1)),
+    ))?;
+    let font = style.text(style.label_size.saturating_sub(2).max(1)).pos(Pos::new(HPos::Left, VPos::Top));
+    chart.draw_series(std::iter::once(
+        EmptyElement::at((value, y_range.end)) + Text::new(label, (style.px(3) as i32, style.px(2) as i32), font),
+    ))?;
+    Ok(())
+}
+
+/// Prints the p-value of `comparison` on a panel of one row per group: next
+/// to a bracket joining the two rows of a Mann-Whitney test, along the left
+/// edge clear of the legend, or in the lower right corner for a
+/// Kruskal-Wallis test.
+fn draw_significance<DB: DrawingBackend, X: Ranged<ValueType = f64>>(
+    chart: &mut Chart<DB, X>,
+    comparison: &Comparison,
+    groups: &[&str],
+    style: &Style,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let (x_range, y_range) = (chart.x_range(), chart.y_range());
+    let rows = groups.len() as f64;
+    let row = |group: &str| rows - groups.iter().position(|g| *g == group).unwrap_or(0) as f64;
+    let font = style.text(style.label_size.saturating_sub(2).max(1));
+    let label = compare::describe(comparison.p_value, style.locale);
+    if comparison.test == Test::MannWhitney {
+        let (top, bottom) = (row(comparison.groups[0]), row(comparison.groups[1]));
+        let height = chart.backend_coord(&(x_range.start, bottom)).1 - chart.backend_coord(&(x_range.start, top)).1;
+        let (spine, arm) = (style.px(6) as i32, style.px(12) as i32);
+        chart.draw_series(std::iter::once(
+            EmptyElement::at((x_range.start, top))
+                + PathElement::new(
+                    vec![(arm, 0), (spine, 0), (spine, height), (arm, height)],
+                    style.foreground.0.stroke_width(style.px(1)),
+                )
+                + Text::new(label, (arm + style.px(3) as i32, height / 2), font.pos(Pos::new(HPos::Left, VPos::Center))),
+        ))?;
+    } else {
+        let label = format!("{}, {}", comparison.test.name(), label);
+        chart.draw_series(std::iter::once(
+            EmptyElement::at((x_range.end, y_range.start))
+                + Text::new(
+                    label,
+                    (-(style.px(5) as i32), -(style.px(3) as i32)),
+                    font.pos(Pos::new(HPos::Right, VPos::Bottom)),
+                ),
+        ))?;
+    }
+    Ok(())
+}
+
+fn draw_group_legend<'a, DB: DrawingBackend + 'a, X: Ranged<ValueType = f64>>(
+    chart: &mut Chart<'a, DB, X>,
+    groups: &[&str],
+    style: &Style,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    label_groups(chart, groups, style)?;
+    style.draw_legend(chart, SeriesLabelPosition::UpperRight)
+}
+
+/// Adds `groups`, as squares of their colors, to the legend of `chart`.
+fn label_groups<'a, DB: DrawingBackend + 'a, X: Ranged<ValueType = f64>>(
+    chart: &mut Chart<'a, DB, X>,
+    groups: &[&str],
+    style: &Style,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let half = style.px(5) as i32;
+    for (g, group) in groups.iter().enumerate() {
+        let color = style.group_color(g);
+        chart
+            .draw_series(std::iter::empty::<Rectangle<(f64, f64)>>())?
+            .label(*group)
+            .legend(move |(x, y)| {
+                Rectangle::new([(x, y - half), (x + 2 * half, y + half)], color.filled())
+            });
+    }
+    Ok(())
+}
+
+/// Legend of every panel of a figure, drawn once in the room kept right of
+/// them by `--legend outside`.
+fn draw_figure_legend<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    groups: &[&str],
+    points: Option<&PointColors>,
+    style: &Style,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    // Level with the top of the first panel, below its caption
+    let mut chart = ChartBuilder::on(area).margin_top(style.px(35)).build_cartesian_2d(0.0..1.0, 0.0..1.0)?;
+    if let Some(points) = points {
+        label_point_classes(&mut chart, points, style)?;
+    }
+    label_groups(&mut chart, groups, style)?;
+    let mut labels = chart.configure_series_labels();
+    labels.position(SeriesLabelPosition::UpperLeft);
+    style.apply_to_legend(&mut labels);
+    labels.draw()?;
+    Ok(())
+}
+
+/// Adds the `--color-by` classes of the points, as dots, to the legend that
+/// [`draw_group_legend`] then draws.
+fn label_point_classes<'a, DB: DrawingBackend + 'a, X: Ranged<ValueType = f64>>(
+    chart: &mut Chart<'a, DB, X>,
+    points: &PointColors,
+    style: &Style,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let radius = style.px(4);
+    for (c, class) in points.classes.iter().enumerate() {
+        let color = points.color_of(c, style);
+        chart
+            .draw_series(std::iter::empty::<Circle<(f64, f64), u32>>())?
+            .label(*class)
+            .legend(move |(x, y)| Circle::new((x + radius as i32, y), radius, color.filled()));
+    }
+    Ok(())
+}
+
+/// Boxplots or violins, one row per group.
+fn draw_distribution_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let (lo, hi) = axis_range(data, &panel.metric, config, 0.1)?;
+    let series: Vec<(Vec<&GenomeStats>, RGBColor)> = grouped_records(data, groups, style)
+        .into_iter()
+        .map(|(records, color)| {
+            // Values past the axis still count towards the box, and are
+            // only left out of the drawing
+            let known = records.into_iter().filter(|x| panel.metric.value(x).is_finite());
+            (known.collect(), color)
+        })
+        .collect();
+    // One row per group, or a single row for ungrouped data
+    let rows = series.len() as f64;
+    let points = PointColors::new(data);
+    let comparison = match config.significance {
+        true => compare::compare(data, &panel.metric, groups),
+        false => None,
+    };
+
+    let mut builder = style.chart(area, 5, (40, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    if config.is_log(&panel.metric) {
+        let chart = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), 0.0..rows + 1.0)?;
+        fill_distribution_panel(chart, &series, panel, (groups, &points), comparison.as_ref(), config, (lo, hi))
+    } else {
+        let chart = builder.build_cartesian_2d(lo..hi, 0.0..rows + 1.0)?;
+        fill_distribution_panel(chart, &series, panel, (groups, &points), comparison.as_ref(), config, (lo, hi))
+    }
+}
+
+fn fill_distribution_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut chart: Chart<'a, DB, X>,
+    series: &[(Vec<&GenomeStats>, RGBColor)],
+    panel: &Panel,
+    (groups, points): (&[&str], &PointColors),
+    comparison: Option<&Comparison>,
+    config: &PlotConfig,
+    x_range: (f64, f64),
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let rows = series.len() as f64;
+
+    let formatter = value_formatter(config, &panel.metric);
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_y_mesh()
+        .y_desc(panel.letter.clone())
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    for (g, (records, color)) in series.iter().enumerate() {
+        // First group at the top
+        let y = rows - g as f64;
+        if records.is_empty() {
+            continue;
+        }
+        let values: Vec<f64> = records.iter().map(|x| panel.metric.value(x)).collect();
+        let values = &values;
+        if config.plot_type == PlotType::Violin {
+            create_violin(&mut chart, values, y, *color, x_range, &panel.metric, config)?;
+        }
+        if config.plot_type == PlotType::Boxplot || config.violin_box {
+            create_boxplot(&mut chart, values, y, *color, x_range, config)?;
+            if config.outlier_labels {
+                label_outliers(&mut chart, records, &panel.metric, y, x_range, config)?;
+            }
+            if config.quartile_labels {
+                let summary = Summary::compute(values, config.quantile_method);
+                label_quartiles(&mut chart, &summary, &panel.metric, y, config)?;
+            }
+        }
+        if config.points {
+            // Points take the color of their --color-by class, if any
+            let point = |i: usize| if points.by_column { points.color(records[i], style) } else { *color };
+            draw_points(&mut chart, values, y, point, x_range, config)?;
+        }
+    }
+    draw_threshold(&mut chart, &panel.metric, style)?;
+    if let Some(comparison) = comparison {
+        draw_significance(&mut chart, comparison, groups, style)?;
+    }
+    let colored = config.points && points.by_column;
+    if panel.legend && colored {
+        label_point_classes(&mut chart, points, style)?;
+    }
+    if panel.legend && (colored || !groups.is_empty()) {
+        draw_group_legend(&mut chart, groups, style)?;
+    }
+    Ok(())
+}
+
+/// Draws the background, frame and legend of box plots of `metrics` from
+/// `sketches`, laid out like those of [`draw_figure`], and returns the area
+/// of each panel, left to draw with [`draw_sketch_panel`].
+fn draw_around_sketches<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sketches: &Sketches,
+    metrics: &[Metric],
+    first_panel: usize,
+    grid: (usize, usize),
+    config: &PlotConfig,
+) -> Result<PanelAreas<DB>, Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    // Browsers resolve font families themselves
+    #[cfg(not(target_arch = "wasm32"))]
+    fonts::load(&config.style, config.deterministic)?;
+    if !config.style.transparent {
+        root.fill(&config.style.background.0)?;
+    }
+    let root = &draw_frame(root, &config.style)?;
+    let groups = sketches.groups();
+    let outside = config.style.legend == Some(LegendPosition::Outside) && !groups.is_empty();
+    let root = match outside {
+        true => {
+            let room = config.style.px(config.style.label_area.right.unwrap_or(120));
+            let (panels, legend) = root.split_horizontally(root.dim_in_pixel().0.saturating_sub(room));
+            draw_figure_legend(&legend, &groups, None, &config.style)?;
+            panels
+        }
+        false => root.clone(),
+    };
+    Ok(panel_areas(&root, metrics, first_panel, grid, outside, config))
+}
+
+/// Box plot of the sketches of one metric, one row per group.
+fn draw_sketch_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sketches: &Sketches,
+    panel: &Panel,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let groups = &sketches.groups();
+    let of_metric = sketches.of(&panel.metric).unwrap_or_default();
+    let series: Vec<(Option<Summary>, RGBColor)> = of_metric
+        .iter()
+        .enumerate()
+        .map(|(g, sketch)| {
+            let color = if groups.is_empty() { style.box_color.0 } else { style.group_color(g) };
+            (sketch.summary(config.quantile_method), color)
+        })
+        .collect();
+    let extents = of_metric.iter().filter_map(|sketch| sketch.extent());
+    let (min, max, min_positive) = extents.fold((f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY), |a, b| {
+        (a.0.min(b.0), a.1.max(b.1), a.2.min(b.2))
+    });
+    let extent = if min > max { (0.0, 1.0) } else { (min, max) };
+    let (lo, hi) = extent_range(extent, min_positive, &panel.metric, config, 0.1)?;
+    let rows = series.len() as f64;
+
+    let mut builder = style.chart(area, 5, (40, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    if config.is_log(&panel.metric) {
+        let chart = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), 0.0..rows + 1.0)?;
+        fill_sketch_panel(chart, &series, panel, groups, config)
+    } else {
+        let chart = builder.build_cartesian_2d(lo..hi, 0.0..rows + 1.0)?;
+        fill_sketch_panel(chart, &series, panel, groups, config)
+    }
+}
+
+fn fill_sketch_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut chart: Chart<'a, DB, X>,
+    series: &[(Option<Summary>, RGBColor)],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let rows = series.len() as f64;
+
+    let formatter = value_formatter(config, &panel.metric);
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_y_mesh()
+        .y_desc(panel.letter.clone())
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    for (g, (summary, color)) in series.iter().enumerate() {
+        if let Some(summary) = summary {
+            // First group at the top
+            draw_box(&mut chart, summary, None, rows - g as f64, *color, config)?;
+            if config.quartile_labels {
+                label_quartiles(&mut chart, summary, &panel.metric, rows - g as f64, config)?;
+            }
+        }
+    }
+    draw_threshold(&mut chart, &panel.metric, style)?;
+    if panel.legend && !groups.is_empty() {
+        draw_group_legend(&mut chart, groups, style)?;
+    }
+    Ok(())
+}
+
+/// Counts of each group in equally wide bins, which are equally wide in
+/// decades on a log axis.
+fn draw_histogram_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let log = config.is_log(&panel.metric);
+    let bins = config.bins as usize;
+    let (min, max) = axis_range(data, &panel.metric, config, 0.0)?;
+    let to_axis = |x: f64| if log { x.log10() } else { x };
+    let from_axis = |x: f64| if log { 10f64.powf(x) } else { x };
+    let start = to_axis(min);
+    let width = if max > min { (to_axis(max) - start) / bins as f64 } else { 1.0 };
+    let edges: Vec<f64> = (0..=bins).map(|b| from_axis(start + b as f64 * width)).collect();
+    let series: Vec<(Vec<usize>, RGBColor)> = grouped_values(data, &panel.metric, groups, style)
+        .into_iter()
+        .map(|(values, color)| {
+            let mut counts = vec![0; bins];
+            for v in values.into_iter().filter(|v| (min..=max).contains(v)) {
+                let bin = ((to_axis(v) - start) / width) as usize;
+                counts[bin.min(bins - 1)] += 1;
+            }
+            (counts, color)
+        })
+        .collect();
+    let max_count = series
+        .iter()
+        .flat_map(|(counts, _)| counts.iter())
+        .copied()
+        .max()
+        .unwrap_or(0);
+
+    let mut builder = style.chart(area, 5, (40, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    let (lo, hi) = (edges[0], edges[bins]);
+    if log {
+        let chart = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), 0.0..max_count as f64 * 1.1)?;
+        fill_histogram_panel(chart, &series, &edges, panel, groups, config)
+    } else {
+        let chart = builder.build_cartesian_2d(lo..hi, 0.0..max_count as f64 * 1.1)?;
+        fill_histogram_panel(chart, &series, &edges, panel, groups, config)
+    }
+}
+
+fn fill_histogram_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut chart: Chart<'a, DB, X>,
+    series: &[(Vec<usize>, RGBColor)],
+    edges: &[f64],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let formatter = value_formatter(config, &panel.metric);
+    let counts = |count: &f64| style.locale.number(&format!("{:.0}", count));
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_x_mesh()
+        .y_label_formatter(&counts)
+        .y_desc(panel.letter.clone())
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    // Groups are overlaid with transparency so overlapping bins stay visible
+    let alpha = if series.len() > 1 { 0.4 } else { 0.6 };
+    for (counts, color) in series {
+        let bars = counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > 0)
+            .map(|(b, &c)| [(edges[b], 0.0), (edges[b + 1], c as f64)]);
+        chart.draw_series(bars.clone().map(|r| Rectangle::new(r, color.mix(alpha).filled())))?;
+        chart.draw_series(bars.map(|r| Rectangle::new(r, color.stroke_width(style.px(1)))))?;
+    }
+    draw_threshold(&mut chart, &panel.metric, style)?;
+    if panel.legend && !groups.is_empty() {
+        draw_group_legend(&mut chart, groups, style)?;
+    }
+    Ok(())
+}
+
+/// Kernel density estimate of each group, overlaid with transparency.
+fn draw_density_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let log = config.is_log(&panel.metric);
+    let (lo, hi) = axis_range(data, &panel.metric, config, 0.1)?;
+    let curves: Vec<(Vec<(f64, f64)>, RGBColor)> = grouped_values(data, &panel.metric, groups, style)
+        .into_iter()
+        .filter_map(|(values, color)| Some((density_curve(&values, &panel.metric, (lo, hi), config)?, color)))
+        .collect();
+    let peak = curves.iter().flat_map(|(curve, _)| curve.iter()).fold(0.0, |a: f64, &(_, d)| a.max(d));
+    let top = if peak > 0.0 { peak * 1.1 } else { 1.0 };
+
+    let mut builder = style.chart(area, 5, (60, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    if log {
+        let chart = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), 0.0..top)?;
+        fill_density_panel(chart, &curves, panel, groups, config)
+    } else {
+        let chart = builder.build_cartesian_2d(lo..hi, 0.0..top)?;
+        fill_density_panel(chart, &curves, panel, groups, config)
+    }
+}
+
+fn fill_density_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut chart: Chart<'a, DB, X>,
+    curves: &[(Vec<(f64, f64)>, RGBColor)],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let formatter = value_formatter(config, &panel.metric);
+    let scientific = |v: &f64| ticks::scientific(*v, style.locale);
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_x_mesh()
+        .y_labels(5)
+        .y_label_formatter(&scientific)
+        .y_desc(panel.letter.clone())
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let alpha = if curves.len() > 1 { 0.2 } else { 0.3 };
+    for (curve, color) in curves {
+        chart.draw_series(AreaSeries::new(curve.iter().copied(), 0.0, color.mix(alpha).filled()))?;
+        chart.draw_series(LineSeries::new(curve.iter().copied(), color.stroke_width(style.px(2))))?;
+    }
+    draw_threshold(&mut chart, &panel.metric, style)?;
+    if panel.legend && !groups.is_empty() {
+        draw_group_legend(&mut chart, groups, style)?;
+    }
+    Ok(())
+}
+
+/// Height of the tallest ridge of a ridgeline panel, in rows.
+const RIDGE_HEIGHT: f64 = 1.4;
+
+/// Name, density curve and color of a group.
+type Ridge<'s> = (&'s str, Option<Vec<(f64, f64)>>, RGBColor);
+
+/// Density of each group on a row of its own, the rows overlapping like the
+/// ridges of a joyplot, which stays legible for many groups.
+fn draw_ridgeline_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let log = config.is_log(&panel.metric);
+    let (lo, hi) = axis_range(data, &panel.metric, config, 0.1)?;
+    let names: Vec<&str> = if groups.is_empty() { vec![""] } else { groups.to_vec() };
+    let ridges: Vec<Ridge> = grouped_values(data, &panel.metric, groups, style)
+        .into_iter()
+        .zip(&names)
+        .map(|((values, color), name)| (*name, density_curve(&values, &panel.metric, (lo, hi), config), color))
+        .collect();
+    let n = ridges.len().max(1);
+
+    let widest = names.iter().map(|name| name.chars().count()).max().unwrap_or(0) as u32;
+    let left = (widest * style.label_size * 3 / 5 + 10).clamp(40, 300);
+    let mut builder = style.chart(area, 5, (left, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    // Axes on one segment per group, plus one above for the tallest ridge to
+    // rise into, and the ridges over them in the same plotting area, where the
+    // middle of segment y is at y
+    let segments = (0..n).into_segmented();
+    if log {
+        let axes = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), segments)?;
+        let plot = axes.plotting_area().strip_coord_spec();
+        let chart = ChartBuilder::on(&plot).build_cartesian_2d(log_axis((lo, hi)).log_scale(), -0.5..n as f64 + 0.5)?;
+        fill_ridgeline_panel(axes, chart, &ridges, panel, config)
+    } else {
+        let axes = builder.build_cartesian_2d(lo..hi, segments)?;
+        let plot = axes.plotting_area().strip_coord_spec();
+        let chart = ChartBuilder::on(&plot).build_cartesian_2d(lo..hi, -0.5..n as f64 + 0.5)?;
+        fill_ridgeline_panel(axes, chart, &ridges, panel, config)
+    }
+}
+
+fn fill_ridgeline_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut axes: ChartContext<'a, DB, Cartesian2d<X, SegmentedCoord<RangedCoordusize>>>,
+    mut chart: Chart<'a, DB, X>,
+    ridges: &[Ridge],
+    panel: &Panel,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let n = ridges.len();
+    // The first group tops the panel
+    let name = |value: &SegmentValue<usize>| match value {
+        SegmentValue::CenterOf(y) if *y < n => ridges[n - 1 - y].0.to_string(),
+        _ => String::new(),
+    };
+    let formatter = value_formatter(config, &panel.metric);
+    let mut mesh = axes.configure_mesh();
+    mesh.disable_y_mesh()
+        .y_labels(n + 1)
+        .y_label_formatter(&name)
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    // Every ridge is scaled alike, so their areas compare, and drawn from the
+    // top down so that each hides the foot of the one behind it
+    let peak = ridges
+        .iter()
+        .flat_map(|(_, curve, _)| curve.iter().flatten())
+        .fold(0.0, |a: f64, &(_, d)| a.max(d));
+    let scale = if peak > 0.0 { RIDGE_HEIGHT / peak } else { 0.0 };
+    let RGBColor(br, bg, bb) = style.background.0;
+    for (i, (_, curve, color)) in ridges.iter().enumerate() {
+        let base = (n - 1 - i) as f64;
+        let Some(curve) = curve else { continue };
+        let points: Vec<(f64, f64)> = curve.iter().map(|&(x, d)| (x, base + d * scale)).collect();
+        // An opaque tint of the color, as a translucent one would let the
+        // ridges behind show through
+        let RGBColor(r, g, b) = *color;
+        let tint = |c: u8, b: u8| ((c as f64 * 0.4) + (b as f64 * 0.6)) as u8;
+        let fill = RGBColor(tint(r, br), tint(g, bg), tint(b, bb));
+        chart.draw_series(AreaSeries::new(points.iter().copied(), base, fill.filled()))?;
+        chart.draw_series(LineSeries::new(points, color.stroke_width(style.px(2))))?;
+        let range = chart.x_range();
+        chart.draw_series(LineSeries::new([(range.start, base), (range.end, base)], color.mix(0.5).stroke_width(style.px(1))))?;
+    }
+    // The rows are already named after the groups, so there is no legend
+    draw_threshold(&mut chart, &panel.metric, style)
+}
+
+/// Share of the samples of each group up to every value, as a step curve.
+fn draw_ecdf_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let log = config.is_log(&panel.metric);
+    let (lo, hi) = axis_range(data, &panel.metric, config, 0.05)?;
+    let series: Vec<(Vec<f64>, RGBColor)> = grouped_values(data, &panel.metric, groups, style)
+        .into_iter()
+        .map(|(mut values, color)| {
+            values.retain(|v| !log || *v > 0.0);
+            values.sort_by(f64::total_cmp);
+            (values, color)
+        })
+        .collect();
+
+    let mut builder = style.chart(area, 5, (50, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    if log {
+        let chart = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), 0.0..100.0)?;
+        fill_ecdf_panel(chart, &series, panel, groups, config)
+    } else {
+        let chart = builder.build_cartesian_2d(lo..hi, 0.0..100.0)?;
+        fill_ecdf_panel(chart, &series, panel, groups, config)
+    }
+}
+
+fn fill_ecdf_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut chart: Chart<'a, DB, X>,
+    series: &[(Vec<f64>, RGBColor)],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let formatter = value_formatter(config, &panel.metric);
+    let mut mesh = chart.configure_mesh();
+    mesh.y_label_formatter(&|share| format!("{:.0}%", share))
+        .y_desc(format!("{} (% up to)", panel.letter))
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let range = chart.x_range();
+    for (values, color) in series {
+        if values.is_empty() {
+            continue;
+        }
+        // A step up of 1 / n at every value, flat in between
+        let n = values.len() as f64;
+        let mut points = vec![(range.start, 0.0)];
+        for (i, &v) in values.iter().enumerate() {
+            points.push((v, i as f64 / n * 100.0));
+            points.push((v, (i + 1) as f64 / n * 100.0));
+        }
+        points.push((range.end, 100.0));
+        chart.draw_series(LineSeries::new(points, color.stroke_width(style.px(2))))?;
+    }
+    draw_threshold(&mut chart, &panel.metric, style)?;
+    if panel.legend && !groups.is_empty() {
+        draw_group_legend(&mut chart, groups, style)?;
+    }
+    Ok(())
+}
+
+/// Value of every sample as a bar, or as a dot on a stem with `dots`, highest
+/// at the top, named after the sample and colored by group.
+fn draw_ranked_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let log = config.is_log(&panel.metric);
+    let mut bars: Vec<(String, f64, RGBColor)> = data
+        .iter()
+        .enumerate()
+        .map(|(row, x)| {
+            let color = match groups.iter().position(|g| *g == x.group_name()) {
+                Some(g) => style.group_color(g),
+                None => style.box_color.0,
+            };
+            (x.name(row), panel.metric.value(x), color)
+        })
+        .filter(|(_, value, _)| value.is_finite() && (!log || *value > 0.0))
+        .collect();
+    // Ties keep the order of the input
+    bars.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let n = bars.len().max(1);
+    let (lo, hi) = match style.range(&panel.metric) {
+        None if !log => {
+            let (min, max) = value_range(data, &panel.metric);
+            (min.min(0.0), max.max(0.0) * 1.05)
+        }
+        _ => axis_range(data, &panel.metric, config, 0.05)?,
+    };
+
+    // Room for the longest name, at about 0.6 em per character
+    let widest = bars.iter().map(|(name, _, _)| name.chars().count()).max().unwrap_or(0) as u32;
+    let left = (widest * style.label_size * 3 / 5 + 10).clamp(40, 300);
+    let mut builder = style.chart(area, 5, (left, 40));
+    builder.caption(style.title(&panel.metric), style.caption_font(20));
+    let segments = (0..n - 1).into_segmented();
+    // Axes on one segment per sample, and the bars over them in the same
+    // plotting area, where the middle of segment y is at y
+    if log {
+        let axes = builder.build_cartesian_2d(log_axis((lo, hi)).log_scale(), segments)?;
+        let plot = axes.plotting_area().strip_coord_spec();
+        let chart = ChartBuilder::on(&plot).build_cartesian_2d(log_axis((lo, hi)).log_scale(), -0.5..n as f64 - 0.5)?;
+        fill_ranked_panel(axes, chart, &bars, panel, groups, config)
+    } else {
+        let axes = builder.build_cartesian_2d(lo..hi, segments)?;
+        let plot = axes.plotting_area().strip_coord_spec();
+        let chart = ChartBuilder::on(&plot).build_cartesian_2d(lo..hi, -0.5..n as f64 - 0.5)?;
+        fill_ranked_panel(axes, chart, &bars, panel, groups, config)
+    }
+}
+
+fn fill_ranked_panel<'a, DB: DrawingBackend + 'a, X>(
+    mut axes: ChartContext<'a, DB, Cartesian2d<X, SegmentedCoord<RangedCoordusize>>>,
+    mut chart: Chart<'a, DB, X>,
+    bars: &[(String, f64, RGBColor)],
+    panel: &Panel,
+    groups: &[&str],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>>
+where
+    <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static,
+    X: Ranged<ValueType = f64> + ValueFormatter<f64>,
+{
+    let style = &config.style;
+    let n = bars.len();
+    let name = |value: &SegmentValue<usize>| match value {
+        SegmentValue::CenterOf(y) if *y < n => bars[n - 1 - y].0.clone(),
+        _ => String::new(),
+    };
+    // Names shrink to fit their rows when there are many samples
+    let row = axes.plotting_area().dim_in_pixel().1 as f64 / n.max(1) as f64 / style.scale;
+    let name_size = style.label_size.min((row * 0.8) as u32).max(6);
+    let formatter = value_formatter(config, &panel.metric);
+    let mut mesh = axes.configure_mesh();
+    mesh.disable_y_mesh()
+        .y_labels(n.max(1))
+        .y_label_formatter(&name)
+        .x_desc(style.xlabel(&panel.metric));
+    if let Some(formatter) = &formatter {
+        mesh.x_label_formatter(formatter.as_ref());
+    }
+    if let Some(ticks) = style.ticks(&panel.metric) {
+        mesh.x_labels(ticks);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.y_label_style(style.text(name_size));
+    mesh.draw()?;
+
+    // Bars and stems start from zero, or from the left edge of a log axis
+    let range = chart.x_range();
+    let base = if config.is_log(&panel.metric) { range.start } else { 0.0f64.clamp(range.start, range.end) };
+    let y = |i: usize| (n - 1 - i) as f64;
+    if config.plot_type == PlotType::Dots {
+        chart.draw_series(bars.iter().enumerate().map(|(i, (_, value, color))| {
+            PathElement::new(vec![(base, y(i)), (*value, y(i))], color.mix(0.5).stroke_width(style.px(1)))
+        }))?;
+        let radius = style.px(4).min((row * style.scale * 0.4) as u32).max(style.px(2));
+        chart.draw_series(
+            bars.iter().enumerate().map(|(i, (_, value, color))| Circle::new((*value, y(i)), radius, color.filled())),
+        )?;
+    } else {
+        chart.draw_series(bars.iter().enumerate().map(|(i, (_, value, color))| {
+            Rectangle::new([(base, y(i) - 0.4), (*value, y(i) + 0.4)], color.mix(0.8).filled())
+        }))?;
+    }
+    draw_threshold(&mut chart, &panel.metric, style)?;
+    if panel.legend && !groups.is_empty() {
+        draw_group_legend(&mut chart, groups, style)?;
+    }
+    Ok(())
+}
+
+/// Scatter matrix of every pair of metrics, with the distribution of each
+/// metric on the diagonal.
+fn draw_pairs<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let n = metrics.len();
+    let cells = root.split_evenly((n, n));
+    let groups = group_labels(data);
+    let points = PointColors::new(data);
+    let colors: Vec<RGBColor> = data.iter().map(|x| points.color(x, style)).collect();
+    let ranges: Vec<(f64, f64)> = metrics
+        .iter()
+        .map(|metric| {
+            style.range(metric).unwrap_or_else(|| {
+                let (min, max) = value_range(data, metric);
+                let padding = if max > min { (max - min) * 0.05 } else { 1.0 };
+                (min - padding, max + padding)
+            })
+        })
+        .collect();
+
+    for (cell, index) in cells.iter().zip(0..) {
+        let (row, col) = (index / n, index % n);
+        let (x_metric, y_metric) = (&metrics[col], &metrics[row]);
+        let x_range = ranges[col].0..ranges[col].1;
+        let x_formatter = value_formatter(config, x_metric);
+        let y_formatter = value_formatter(config, y_metric);
+
+        if row == col {
+            let (min, max) = style.range(x_metric).unwrap_or_else(|| value_range(data, x_metric));
+            let bins = 10;
+            let width = if max > min { (max - min) / bins as f64 } else { 1.0 };
+            let series = grouped_values(data, x_metric, &groups, style);
+            let mut counts = vec![vec![0usize; bins]; series.len()];
+            for (s, (values, _)) in series.iter().enumerate() {
+                for v in values.iter().filter(|v| (min..=max).contains(*v)) {
+                    let bin = ((v - min) / width) as usize;
+                    counts[s][bin.min(bins - 1)] += 1;
+                }
+            }
+            let max_count = counts.iter().flatten().copied().max().unwrap_or(0);
+
+            let mut chart = style
+                .chart(cell, 3, (45, 25))
+                .caption(style.title(x_metric), style.caption_font(12))
+                .build_cartesian_2d(x_range, 0.0..max_count as f64 * 1.1)?;
+            let count_label = |count: &f64| style.locale.number(&format!("{:.0}", count));
+            let mut mesh = chart.configure_mesh();
+            mesh.disable_mesh()
+                .x_labels(style.ticks(x_metric).unwrap_or(3))
+                .y_labels(3)
+                .y_label_formatter(&count_label);
+            if let Some(formatter) = &x_formatter {
+                mesh.x_label_formatter(formatter.as_ref());
+            }
+            style.apply_to_mesh(&mut mesh);
+            mesh.draw()?;
+            for (s, (_, color)) in series.iter().enumerate() {
+                let alpha = if series.len() > 1 { 0.4 } else { 0.6 };
+                chart.draw_series(counts[s].iter().enumerate().filter(|(_, &c)| c > 0).map(
+                    |(b, &c)| {
+                        let lo = min + b as f64 * width;
+                        Rectangle::new([(lo, 0.0), (lo + width, c as f64)], color.mix(alpha).filled())
+                    },
+                ))?;
+            }
+            if row == 0 && points.by_column {
+                label_point_classes(&mut chart, &points, style)?;
+            }
+            if row == 0 && (points.by_column || !groups.is_empty()) {
+                draw_group_legend(&mut chart, &groups, style)?;
+            }
+        } else {
+            let mut chart = style
+                .chart(cell, 3, (45, 25))
+                .build_cartesian_2d(x_range, ranges[row].0..ranges[row].1)?;
+            let mut mesh = chart.configure_mesh();
+            mesh.disable_mesh().x_labels(style.ticks(x_metric).unwrap_or(3)).y_labels(style.ticks(y_metric).unwrap_or(3));
+            if col == 0 {
+                mesh.y_desc(style.xlabel(y_metric));
+            }
+            if row == n - 1 {
+                mesh.x_desc(style.xlabel(x_metric));
+            }
+            if let Some(formatter) = &x_formatter {
+                mesh.x_label_formatter(formatter.as_ref());
+            }
+            if let Some(formatter) = &y_formatter {
+                mesh.y_label_formatter(formatter.as_ref());
+            }
+            style.apply_to_mesh(&mut mesh);
+            mesh.draw()?;
+            let points = data.iter().zip(&colors).map(|(record, color)| {
+                ((x_metric.value(record), y_metric.value(record)), color)
+            });
+            chart.draw_series(
+                points
+                    .filter(|((x, y), _)| x.is_finite() && y.is_finite())
+                    .map(|(point, color)| Circle::new(point, style.px(2), color.mix(0.7).filled())),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Color of a correlation of `r` on a diverging scale, blue for -1, white
+/// for 0 and red for 1.
+fn diverging(r: f64) -> RGBColor {
+    let RGBColor(er, eg, eb) = if r < 0.0 { RGBColor(0x21, 0x66, 0xac) } else { RGBColor(0xb2, 0x18, 0x2b) };
+    let t = r.abs().min(1.0);
+    let mix = |end: u8| (255.0 + (end as f64 - 255.0) * t).round() as u8;
+    RGBColor(mix(er), mix(eg), mix(eb))
+}
+
+/// Heatmap of the correlation of every pair of metrics across the samples
+/// where both are known, each cell annotated with its value.
+fn draw_correlation<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let n = metrics.len();
+    if n < 2 {
+        return Err("a correlation heatmap needs at least two metrics".into());
+    }
+    let matrix = correlation::matrix(data, metrics, config.correlation);
+    let mut chart = style
+        .chart(root, 10, (140, 60))
+        .caption(config.correlation.caption(), style.caption_font(20))
+        .build_cartesian_2d((0..n - 1).into_segmented(), (0..n - 1).into_segmented())?;
+    // The first metric on the top row
+    let name = |value: &SegmentValue<usize>, flip: bool| match value {
+        SegmentValue::CenterOf(i) if *i < n => style.title(&metrics[if flip { n - 1 - i } else { *i }]).into_owned(),
+        _ => String::new(),
+    };
+    let (x_name, y_name) = (|v: &SegmentValue<usize>| name(v, false), |v: &SegmentValue<usize>| name(v, true));
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_mesh()
+        .x_labels(n)
+        .y_labels(n)
+        .x_label_formatter(&x_name)
+        .y_label_formatter(&y_name);
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let font = style.text(style.label_size);
+    for (i, row) in matrix.iter().enumerate() {
+        let y = n - 1 - i;
+        for (x, &r) in row.iter().enumerate() {
+            let corners = [
+                (SegmentValue::Exact(x), SegmentValue::Exact(y)),
+                (SegmentValue::Exact(x + 1), SegmentValue::Exact(y + 1)),
+            ];
+            let (fill, label) = if r.is_nan() {
+                (style.foreground.0.mix(0.1), "NA".to_string())
+            } else {
+                (diverging(r).into(), style.locale.number(&format!("{:.2}", r)))
+            };
+            chart.draw_series([
+                Rectangle::new(corners.clone(), fill.filled()),
+                Rectangle::new(corners, style.background.0.stroke_width(style.px(1))),
+            ])?;
+            let color = if r.abs() > 0.6 { WHITE } else { BLACK };
+            chart.draw_series(std::iter::once(Text::new(
+                label,
+                (SegmentValue::CenterOf(x), SegmentValue::CenterOf(y)),
+                font.color(&color).pos(Pos::new(HPos::Center, VPos::Center)),
+            )))?;
+        }
+    }
+    Ok(())
+}
+
+/// Samples on the first two principal components of the standardized
+/// `metrics`, colored by group, with the loading of each metric drawn as an
+/// arrow from the origin.
+fn draw_pca<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let pca = pca::analyse(data, metrics)?;
+    if pca.rows.len() < data.len() {
+        info!("PCA left out {} samples missing a metric", data.len() - pca.rows.len());
+    }
+    let extent = pca.scores.iter().fold(0.0f64, |m, (x, y)| m.max(x.abs()).max(y.abs())).max(1e-9) * 1.1;
+    let mut chart = style
+        .chart(root, 10, (60, 40))
+        .caption("Principal components", style.caption_font(20))
+        .build_cartesian_2d(-extent..extent, -extent..extent)?;
+    let mut mesh = chart.configure_mesh();
+    let explained = |share: f64| style.locale.number(&format!("{:.1}", share * 100.0));
+    mesh.x_desc(format!("PC1 ({}%)", explained(pca.explained.0)))
+        .y_desc(format!("PC2 ({}%)", explained(pca.explained.1)));
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    // Arrows as long as the scores reach, for the metrics to read against
+    let longest = pca.loadings.iter().fold(0.0f64, |m, (x, y)| m.max(x.hypot(*y))).max(1e-9);
+    let reach = extent / 1.1 / longest * 0.8;
+    let font = style.text(style.label_size.saturating_sub(2).max(1));
+    let arrow = style.foreground.0.mix(0.5).stroke_width(style.px(1));
+    for (metric, (x, y)) in metrics.iter().zip(&pca.loadings) {
+        let tip = (x * reach, y * reach);
+        chart.draw_series(std::iter::once(PathElement::new(vec![(0.0, 0.0), tip], arrow)))?;
+        let anchor = Pos::new(if *x < 0.0 { HPos::Right } else { HPos::Left }, VPos::Center);
+        chart.draw_series(std::iter::once(Text::new(metric.to_string(), tip, font.pos(anchor))))?;
+    }
+
+    let points = PointColors::new(data);
+    chart.draw_series(pca.rows.iter().zip(&pca.scores).map(|(&row, &point)| {
+        Circle::new(point, style.px(3), points.color(&data[row], style).mix(0.8).filled())
+    }))?;
+    if points.by_column {
+        label_point_classes(&mut chart, &points, style)?;
+        draw_group_legend(&mut chart, &[], style)?;
+    } else if !points.classes.is_empty() {
+        draw_group_legend(&mut chart, &points.classes, style)?;
+    }
+    Ok(())
+}
+
+/// Dendrogram of the samples clustered by average linkage on the Euclidean
+/// distance of their standardized `metrics`, first leaf at the top, with a
+/// heatmap of the z-scores, saturating at 3, to its left with `--heatmap`.
+fn draw_dendrogram<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let tree = cluster::tree(data, metrics)?;
+    let n = tree.rows.len();
+    if n < data.len() {
+        info!("The dendrogram leaves out {} samples missing a metric", data.len() - n);
+    }
+    // Sample at each row
+    let mut leaves = vec![0; n];
+    for (i, &place) in tree.places.iter().enumerate() {
+        leaves[place] = tree.rows[i];
+    }
+
+    let root = root.titled("Clustering of samples", style.caption_font(20))?;
+    let (heat_area, tree_area) = if config.heatmap {
+        let (heat, tree) = root.split_horizontally((root.dim_in_pixel().0 as f64 * 0.6) as i32);
+        (Some(heat), tree)
+    } else {
+        (None, root)
+    };
+    let leaf_name = |v: &SegmentValue<usize>| match v {
+        SegmentValue::CenterOf(y) if *y < n => data[leaves[*y]].name(leaves[*y]),
+        _ => String::new(),
+    };
+
+    if let Some(area) = &heat_area {
+        let p = metrics.len();
+        let mut chart = style
+            .chart(area, 10, (140, 140))
+            .build_cartesian_2d((0..p - 1).into_segmented(), (0..n - 1).into_segmented())?;
+        let mut mesh = chart.configure_mesh();
+        mesh.disable_mesh()
+            .x_labels(p)
+            .y_labels(n)
+            .x_label_formatter(&|_| String::new())
+            .y_label_formatter(&leaf_name);
+        style.apply_to_mesh(&mut mesh);
+        mesh.draw()?;
+        // Metric names run down from under their column rather than into
+        // each other
+        let (base_x, base_y) = area.get_base_pixel();
+        let font = style.text(style.label_size).transform(FontTransform::Rotate90);
+        for (j, metric) in metrics.iter().enumerate() {
+            let (x, y) = chart.backend_coord(&(SegmentValue::CenterOf(j), SegmentValue::Exact(0)));
+            let at = (x - base_x + style.px(style.label_size) as i32 / 2, y - base_y + style.px(8) as i32);
+            area.draw(&Text::new(metric.to_string(), at, font.clone()))?;
+        }
+        chart.draw_series(tree.scores.iter().zip(&tree.places).flat_map(|(scores, &y)| {
+            scores.iter().enumerate().map(move |(x, score)| {
+                let corners = [
+                    (SegmentValue::Exact(x), SegmentValue::Exact(y)),
+                    (SegmentValue::Exact(x + 1), SegmentValue::Exact(y + 1)),
+                ];
+                Rectangle::new(corners, diverging(score / 3.0).filled())
+            })
+        }))?;
+    }
+
+    // The axes are drawn on segments, one per sample, and the tree over them
+    // in the same area where the middle of segment y is at y
+    let highest = tree.merges.iter().map(|m| m.height).fold(0.0, f64::max).max(1e-9);
+    let left = if config.heatmap { 0 } else { 140 };
+    let bottom = if config.heatmap { 140 } else { 60 };
+    let mut axes = style
+        .chart(&tree_area, 10, (left, bottom))
+        .build_cartesian_2d(0.0..highest * 1.05, (0..n - 1).into_segmented())?;
+    let mut mesh = axes.configure_mesh();
+    mesh.disable_y_mesh().y_labels(n).x_desc("Distance");
+    if config.heatmap {
+        mesh.disable_y_axis();
+    } else {
+        mesh.y_label_formatter(&leaf_name);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+    let mut chart = style
+        .chart(&tree_area, 10, (left, bottom))
+        .build_cartesian_2d(0.0..highest * 1.05, -0.5..n as f64 - 0.5)?;
+
+    // Position of every node, samples first and then merges
+    let mut nodes: Vec<(f64, f64)> = tree.places.iter().map(|&y| (0.0, y as f64)).collect();
+    let line = style.foreground.0.stroke_width(style.px(1));
+    for merge in &tree.merges {
+        let (a, b) = (nodes[merge.left], nodes[merge.right]);
+        chart.draw_series(std::iter::once(PathElement::new(
+            vec![a, (merge.height, a.1), (merge.height, b.1), b],
+            line,
+        )))?;
+        nodes.push((merge.height, (a.1 + b.1) / 2.0));
+    }
+    let groups = group_labels(data);
+    if !groups.is_empty() {
+        chart.draw_series(tree.rows.iter().zip(&tree.places).map(|(&row, &y)| {
+            let g = groups.iter().position(|g| *g == data[row].group_name()).unwrap_or(0);
+            Circle::new((0.0, y as f64), style.px(3), style.group_color(g).filled())
+        }))?;
+        draw_group_legend(&mut chart, &groups, style)?;
+    }
+    Ok(())
+}
+
+/// Colors and legend entries of per-assembly curves: by group when grouped,
+/// otherwise one color per assembly, named in the legend when there are few.
+fn curve_styles<'a>(
+    assemblies: &[(usize, &'a GenomeStats)],
+    groups: &[&'a str],
+    style: &Style,
+) -> Vec<(RGBColor, Option<String>)> {
+    let mut labelled: Vec<&str> = Vec::new();
+    assemblies
+        .iter()
+        .enumerate()
+        .map(|(i, (row, record))| {
+            if groups.is_empty() {
+                let label = (assemblies.len() <= MAX_LEGEND_ENTRIES).then(|| record.name(*row));
+                (style.group_color(i), label)
+            } else {
+                let group = record.group_name();
+                let g = groups.iter().position(|x| *x == group).unwrap_or(0);
+                // Only the first curve of each group goes in the legend
+                let label = (!labelled.contains(&group)).then(|| {
+                    labelled.push(group);
+                    group.to_string()
+                });
+                (style.group_color(g), label)
+            }
+        })
+        .collect()
+}
+
+/// Records that carry sequence lengths, with their row numbers. `plot` names
+/// what needs them in the error when there are none.
+fn fasta_assemblies<'a>(
+    data: &'a [GenomeStats],
+    plot: &str,
+) -> Result<Vec<(usize, &'a GenomeStats)>, Box<dyn Error>> {
+    let assemblies: Vec<_> = data.iter().enumerate().filter(|(_, x)| !x.lengths.is_empty()).collect();
+    if assemblies.is_empty() {
+        return Err(format!("{plot} need sequence lengths, which are only known for FASTA and GFA inputs").into());
+    }
+    Ok(assemblies)
+}
+
+/// Nx curves of every assembly computed from FASTA, overlaid in one chart.
+fn draw_nx<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let assemblies = fasta_assemblies(data, "Nx curves")?;
+    let longest = assemblies.iter().map(|(_, x)| x.lengths[0]).max().unwrap_or(1);
+    let shortest = assemblies
+        .iter()
+        .filter_map(|(_, x)| x.lengths.last().copied())
+        .min()
+        .unwrap_or(1)
+        .max(1);
+
+    let mut chart = style
+        .chart(root, 10, (60, 40))
+        .caption("Nx curves", style.caption_font(20))
+        .build_cartesian_2d(0.0..100.0, (shortest as f64..longest as f64 * 1.5).log_scale())?;
+    let mut mesh = chart.configure_mesh();
+    let base_pairs = |v: &f64| ticks::format(*v, Unit::BasePairs, style.locale);
+    mesh.x_desc("x (%)").y_desc("Nx (bp.)");
+    if !config.raw_ticks {
+        mesh.y_label_formatter(&base_pairs);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, style);
+    let (line, length) = (style.px(1), style.px(15) as i32);
+    for ((_, record), (color, label)) in assemblies.iter().zip(styles) {
+        let series = chart.draw_series(LineSeries::new(fasta::nx_curve(&record.lengths), color.stroke_width(line)))?;
+        if let Some(label) = label {
+            series
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + length, y)], color.stroke_width(line)));
+        }
+    }
+    // The N50 is where the curves cross this line
+    chart.draw_series(std::iter::once(PathElement::new(
+        vec![(50.0, shortest as f64), (50.0, longest as f64 * 1.5)],
+        style.foreground.0.mix(0.5).stroke_width(line),
+    )))?;
+    style.draw_legend(&mut chart, SeriesLabelPosition::UpperRight)?;
+
+    Ok(())
+}
+
+/// Cumulative length against sequence rank of every assembly computed from
+/// FASTA, overlaid in one chart.
+fn draw_cumulative<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let assemblies = fasta_assemblies(data, "Cumulative length curves")?;
+    let most_sequences = assemblies.iter().map(|(_, x)| x.lengths.len()).max().unwrap_or(1);
+    let largest: u64 = assemblies
+        .iter()
+        .map(|(_, x)| x.lengths.iter().sum())
+        .max()
+        .unwrap_or(1);
+
+    let mut chart = style
+        .chart(root, 10, (80, 40))
+        .caption("Cumulative length", style.caption_font(20))
+        .build_cartesian_2d(0.0..most_sequences as f64, 0.0..largest as f64 * 1.05)?;
+    let mut mesh = chart.configure_mesh();
+    let base_pairs = |v: &f64| ticks::format(*v, Unit::BasePairs, style.locale);
+    mesh.x_desc("Sequence rank (longest first)").y_desc("Cumulative length (bp.)");
+    if !config.raw_ticks {
+        mesh.y_label_formatter(&base_pairs);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, style);
+    let (line, length) = (style.px(1), style.px(15) as i32);
+    for ((_, record), (color, label)) in assemblies.iter().zip(styles) {
+        let series =
+            chart.draw_series(LineSeries::new(fasta::cumulative_curve(&record.lengths), color.stroke_width(line)))?;
+        if let Some(label) = label {
+            series
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + length, y)], color.stroke_width(line)));
+        }
+    }
+    style.draw_legend(&mut chart, SeriesLabelPosition::LowerRight)?;
+
+    Ok(())
+}
+
+/// Distribution of the sequence lengths of every assembly computed from
+/// FASTA, overlaid in one chart.
+fn draw_lengths<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let assemblies = fasta_assemblies(data, "Sequence length distributions")?;
+    let longest = assemblies.iter().map(|(_, x)| x.lengths[0]).max().unwrap_or(1);
+    let shortest = assemblies
+        .iter()
+        .filter_map(|(_, x)| x.lengths.last().copied())
+        .min()
+        .unwrap_or(1)
+        .max(1);
+
+    let mut chart = style
+        .chart(root, 10, (60, 40))
+        .caption("Sequence lengths", style.caption_font(20))
+        .build_cartesian_2d(log_axis((shortest as f64, longest as f64 * 1.5)).log_scale(), 0.0..100.0)?;
+    let mut mesh = chart.configure_mesh();
+    let base_pairs = |v: &f64| ticks::format(*v, Unit::BasePairs, style.locale);
+    let percent = |v: &f64| ticks::format(*v, Unit::Percent, style.locale);
+    mesh.x_desc("Sequence length (bp.)").y_desc("Sequences (%)");
+    if !config.raw_ticks {
+        mesh.x_label_formatter(&base_pairs).y_label_formatter(&percent);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, style);
+    let (line, length) = (style.px(1), style.px(15) as i32);
+    for ((_, record), (color, label)) in assemblies.iter().zip(styles) {
+        let series = chart.draw_series(LineSeries::new(fasta::length_ecdf(&record.lengths), color.stroke_width(line)))?;
+        if let Some(label) = label {
+            series
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + length, y)], color.stroke_width(line)));
+        }
+    }
+    style.draw_legend(&mut chart, SeriesLabelPosition::LowerRight)?;
+
+    Ok(())
+}
+
+/// Records that carry the GC percentage of their sequences, with their row
+/// numbers.
+fn gc_assemblies(data: &[GenomeStats]) -> Result<Vec<(usize, &GenomeStats)>, Box<dyn Error>> {
+    let assemblies: Vec<_> = data.iter().enumerate().filter(|(_, x)| x.sequence_gc.iter().any(|gc| !gc.is_nan())).collect();
+    if assemblies.is_empty() {
+        return Err("GC ratios of sequences are only known for FASTA and GFA inputs with sequences".into());
+    }
+    Ok(assemblies)
+}
+
+/// Boxplots of the GC ratio of the sequences of every assembly computed from
+/// FASTA, one row each, where contaminant sequences stand out as outliers.
+fn draw_sequence_gc<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let assemblies = gc_assemblies(data)?;
+    let values = assemblies.iter().flat_map(|(_, x)| x.sequence_gc.iter().copied());
+    // NaN, for sequences without bases, is ignored by min and max
+    let min = values.clone().fold(f64::INFINITY, f64::min);
+    let max = values.fold(f64::NEG_INFINITY, f64::max);
+    let padding = ((max - min) * 0.05).max(1.0);
+    let (lo, hi) = ((min - padding).max(0.0), (max + padding).min(100.0));
+    let rows = assemblies.len() as f64;
+
+    let mut chart = style
+        .chart(root, 10, (40, 40))
+        .caption("GC ratio of sequences", style.caption_font(20))
+        .build_cartesian_2d(lo..hi, 0.0..rows + 1.0)?;
+    let mut mesh = chart.configure_mesh();
+    let percent = |v: &f64| ticks::format(*v, Unit::Percent, style.locale);
+    mesh.disable_y_mesh().disable_y_axis().x_desc("GC ratio (%)");
+    if !config.raw_ticks {
+        mesh.x_label_formatter(&percent);
+    }
+    style.apply_to_mesh(&mut mesh);
+    mesh.draw()?;
+
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, style);
+    let font = style.text(style.label_size.saturating_sub(2).max(1));
+    for (i, ((row, record), (color, _))) in assemblies.iter().zip(styles).enumerate() {
+        // First assembly at the top, named above its box
+        let y = rows - i as f64;
+        let gc = record.known_sequence_gc();
+        create_boxplot(&mut chart, &gc, y, color, (lo, hi), config)?;
+        if config.quartile_labels && !gc.is_empty() {
+            let summary = Summary::compute(&gc, config.quantile_method);
+            label_quartiles(&mut chart, &summary, &Metric::GcPercentage, y, config)?;
+        }
+        if config.points {
+            draw_points(&mut chart, &gc, y, |_| color, (lo, hi), config)?;
+        }
+        chart.draw_series(std::iter::once(Text::new(
+            record.name(*row),
+            (lo, y + 0.45),
+            font.pos(Pos::new(HPos::Left, VPos::Center)),
+        )))?;
+    }
+    if !groups.is_empty() {
+        draw_group_legend(&mut chart, &groups, style)?;
+    }
+    Ok(())
+}
+
+/// One treemap per assembly computed from FASTA, with a rectangle per
+/// sequence sized by its length: shaded fully for the sequences of the N50,
+/// lighter up to the N90 and lightest beyond.
+fn draw_treemaps<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let assemblies = fasta_assemblies(data, "Treemaps")?;
+    let cols = (assemblies.len() as f64).sqrt().ceil() as usize;
+    let cells = root.split_evenly((assemblies.len().div_ceil(cols), cols));
+
+    let groups = group_labels(data);
+    let styles = curve_styles(&assemblies, &groups, style);
+    let margin = style.px(10);
+    for (cell, ((row, record), (color, _))) in cells.iter().zip(assemblies.iter().zip(styles)) {
+        let (l50, l90) = (fasta::covering(&record.lengths, 50).1, fasta::covering(&record.lengths, 90).1);
+        let caption = format!(
+            "{}: {} of {} sequences hold half of it",
+            record.name(*row),
+            l50,
+            record.lengths.len()
+        );
+        let area = cell.margin(margin, margin, margin, margin).titled(&caption, style.caption_font(14))?;
+        let (width, height) = area.dim_in_pixel();
+        let lengths: Vec<f64> = record.lengths.iter().map(|&len| len as f64).collect();
+        let tiles = treemap::squarify(&lengths, (0.0, 0.0, width as f64, height as f64));
+        for (i, (x, y, w, h)) in tiles.into_iter().enumerate() {
+            let shade = match i as u64 {
+                i if i < l50 => 1.0,
+                i if i < l90 => 0.6,
+                _ => 0.3,
+            };
+            let corners = [(x.round() as i32, y.round() as i32), ((x + w).round() as i32, (y + h).round() as i32)];
+            area.draw(&Rectangle::new(corners, color.mix(shade).filled()))?;
+            // Outlines would hide the slivers of the shortest sequences
+            if w >= 3.0 && h >= 3.0 {
+                area.draw(&Rectangle::new(corners, style.background.0.stroke_width(1)))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Snail plot of each assembly computed from FASTA, after BlobToolKit: the
+/// share of the assembly runs clockwise from the top and the length of the
+/// sequence there outwards on a square root scale, shaded for the longest
+/// sequence, the N50 and the N90, with the sequence count spiralling on a log
+/// scale and the GC content around the outside.
+fn draw_snails<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[GenomeStats],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+    let style = &config.style;
+    let assemblies = fasta_assemblies(data, "Snail plots")?;
+    let cols = (assemblies.len() as f64).sqrt().ceil() as usize;
+    let cells = root.split_evenly((assemblies.len().div_ceil(cols), cols));
+
+    let margin = style.px(10);
+    let font = style.text(style.label_size.saturating_sub(2).max(1));
+    let grid = style.foreground.0.mix(0.2);
+    let step = 100.0 / snail::BINS as f64;
+    for (cell, (row, record)) in cells.iter().zip(&assemblies) {
+        let area = cell.margin(margin, margin, margin, margin).titled(&record.name(*row), style.caption_font(14))?;
+        let (width, height) = area.dim_in_pixel();
+        let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+        // Room outside the circle for the GC ring and the percentages
+        let radius = cx.min(cy) / 1.35;
+        let point = |percent: f64, r: f64| {
+            let angle = percent / 100.0 * std::f64::consts::TAU;
+            ((cx + r * angle.sin()).round() as i32, (cy - r * angle.cos()).round() as i32)
+        };
+        let longest = record.lengths[0].max(1) as f64;
+        let scale = |len: u64| radius * (len as f64 / longest).sqrt();
+        let bins = snail::bin(&record.lengths, &record.sequence_gc);
+        let total: u64 = record.lengths.iter().sum();
+
+        area.draw(&Circle::new(point(0.0, 0.0), radius.round() as i32, grid.filled()))?;
+        let (n50, n90) = (snail::BINS / 2, snail::BINS * 9 / 10);
+        for (start, end, color) in [(0, n50, snail::N50), (n50, n90, snail::N90), (n90, snail::BINS, snail::REST)] {
+            let mut outline = vec![point(0.0, 0.0)];
+            for k in start..end {
+                let r = scale(bins.lengths[k]);
+                outline.push(point(k as f64 * step, r));
+                outline.push(point((k + 1) as f64 * step, r));
+            }
+            area.draw(&Polygon::new(outline, color.filled()))?;
+        }
+        let share = longest / total.max(1) as f64 * 100.0;
+        let sector = (0..=snail::BINS)
+            .map(|k| k as f64 * step)
+            .take_while(|&percent| percent < share)
+            .chain([share])
+            .map(|percent| point(percent, radius));
+        area.draw(&Polygon::new(std::iter::once(point(0.0, 0.0)).chain(sector).collect::<Vec<_>>(), snail::LONGEST.filled()))?;
+
+        // Lengths at every power of ten
+        let mut length = 10u64;
+        while length as f64 <= longest {
+            let r = scale(length);
+            area.draw(&Circle::new(point(0.0, 0.0), r.round() as i32, grid.stroke_width(1)))?;
+            let label = if config.raw_ticks {
+                style.locale.number(&length.to_string())
+            } else {
+                ticks::format(length as f64, Unit::BasePairs, style.locale)
+            };
+            area.draw(&Text::new(label, point(0.0, r), font.pos(Pos::new(HPos::Left, VPos::Bottom))))?;
+            length *= 10;
+        }
+        if record.lengths.len() > 1 {
+            let most = (record.lengths.len() as f64).log10();
+            let spiral = bins
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(k, &count)| point((k + 1) as f64 * step, radius * (count as f64).log10() / most));
+            area.draw(&PathElement::new(spiral.collect::<Vec<_>>(), style.foreground.0.mix(0.4).stroke_width(style.px(1))))?;
+        }
+
+        let (inner, outer) = (radius * 1.05, radius * 1.2);
+        for (k, &gc) in bins.gc.iter().enumerate() {
+            if gc.is_nan() {
+                continue;
+            }
+            let (a, b) = (k as f64 * step, (k + 1) as f64 * step);
+            let middle = inner + (outer - inner) * gc / 100.0;
+            for (from, to, color) in [(inner, middle, snail::GC), (middle, outer, snail::AT)] {
+                let band = vec![point(a, from), point(b, from), point(b, to), point(a, to)];
+                area.draw(&Polygon::new(band, color.filled()))?;
+            }
+        }
+        for percent in [0.0, 25.0, 50.0, 75.0] {
+            let anchor = match percent as u32 {
+                0 => Pos::new(HPos::Center, VPos::Bottom),
+                25 => Pos::new(HPos::Left, VPos::Center),
+                50 => Pos::new(HPos::Center, VPos::Top),
+                _ => Pos::new(HPos::Right, VPos::Center),
+            };
+            let label = format!("{}%", percent);
+            area.draw(&Text::new(label, point(percent, outer + style.px(4) as f64), font.pos(anchor)))?;
+        }
+
+        let bp = |len: f64| ticks::format(len, Unit::BasePairs, style.locale);
+        let mut keys = vec![
+            (None, format!("{} in {} sequences", bp(total as f64), style.locale.number(&record.lengths.len().to_string()))),
+            (Some(snail::LONGEST), format!("Longest {}", bp(longest))),
+            (Some(snail::N50), format!("N50 {}", bp(fasta::covering(&record.lengths, 50).0 as f64))),
+            (Some(snail::N90), format!("N90 {}", bp(fasta::covering(&record.lengths, 90).0 as f64))),
+        ];
+        if bins.gc.iter().any(|gc| !gc.is_nan()) {
+            keys.push((Some(snail::GC), format!("GC {}%", style.locale.number(&format!("{:.1}", record.gc_percentage)))));
+        }
+        let line = style.px(style.label_size + 4) as i32;
+        let size = style.px(style.label_size.saturating_sub(4).max(1)) as i32;
+        for (i, (color, text)) in keys.into_iter().enumerate() {
+            let y = i as i32 * line;
+            if let Some(color) = color {
+                area.draw(&Rectangle::new([(0, y + 2), (size, y + 2 + size)], color.filled()))?;
+            }
+            area.draw(&Text::new(text, (size + style.px(4) as i32, y), font.clone()))?;
+        }
+    }
+    Ok(())
+}
+
+type SendError = Box<dyn Error + Send + Sync>;
+
+/// How input files are read.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Field delimiter of CSV inputs, guessed from the header line of each
+    /// file when `None`.
+    pub delimiter: Option<u8>,
+    /// CSV column whose values group the records.
+    pub group_by: Option<String>,
+    /// Group the records by the input they were read from instead, named
+    /// after the file or directory.
+    pub group_by_input: bool,
+    /// CSV column whose values color the points of point overlays and
+    /// scatter plots.
+    pub color_by: Option<String>,
+    /// Numeric CSV columns read into [`GenomeStats::extra`].
+    pub extra_columns: Vec<String>,
+    /// Headers to read as one of the [`GenomeStats`] columns, such as
+    /// `("Scaffold N50", "N50")`, on top of the spellings recognised anyway.
+    pub aliases: Vec<(String, String)>,
+    /// What to do with rows that leave a numeric column empty.
+    pub missing: Missing,
+    /// Show progress bars while reading, when stderr is a terminal.
+    pub progress: bool,
+    /// BUSCO short summaries, directories of them or glob patterns, read into
+    /// [`GenomeStats::busco`] of the assemblies they name.
+    pub busco: Vec<PathBuf>,
+    /// Read FASTQ files, one record per read grouped by file, instead of
+    /// assemblies.
+    pub reads: bool,
+    /// Only sequence lengths are plotted, so that those of FASTA files can be
+    /// read from their `.fai` index; their GC and N contents are then left
+    /// missing.
+    pub lengths_only: bool,
+    /// Names of the only samples to keep, matched like BUSCO summaries;
+    /// every sample when empty.
+    pub samples: Vec<String>,
+    /// Reuse the statistics of FASTA files from the `.stats.json` file next
+    /// to each, written when missing or out of date.
+    pub cache: bool,
+    /// Table of SQLite inputs the statistics are read from.
+    pub table: Option<String>,
+}
+
+/// How rows with an empty numeric field are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Missing {
+    /// Leave the row out, with a warning
+    #[default]
+    Skip,
+    /// Read the empty fields as 0
+    Zero,
+    /// Stop with an error
+    Fail,
+}
+
+/// Records read from one or more inputs, in the order the inputs were given.
+#[derive(Debug, Clone, Default)]
+pub struct Dataset {
+    records: Vec<GenomeStats>,
+}
+
+impl Dataset {
+    /// Reads CSV files, FASTA files, directories of either and glob patterns
+    /// such as `results/*/stats.csv` on the global thread pool.
+    pub fn load(inputs: &[PathBuf], options: &LoadOptions) -> Result<Self, Box<dyn Error>> {
+        let batches = fold_inputs(inputs, options, Vec::new, Vec::push)?;
+        Dataset::select(batches.into_iter().flatten().collect(), options)
+    }
+
+    /// Reads a CSV, TSV or JSON table held in memory, such as a file uploaded
+    /// to a web page, possibly gzipped; `name` stands for its path, in
+    /// messages, as the source of its records and, by its extension, for its
+    /// format.
+    pub fn from_table(name: &str, table: &[u8], options: &LoadOptions) -> Result<Self, Box<dyn Error>> {
+        let path = Path::new(name);
+        let label = &input_labels(&[path.to_path_buf()])[0];
+        let mut records = Vec::new();
+        let mut each = |mut record: GenomeStats| {
+            label_record(&mut record, path, label, options);
+            records.push(record);
+        };
+        let read = match json::is_json(path) {
+            true => read_json(path, reader::from_bytes(table), options, &mut each),
+            false => read_table(path, reader::from_bytes(table), options, &mut each).map(|_| ()),
+        };
+        read.map_err(|e| e as Box<dyn Error>)?;
+        Dataset::select(records, options)
+    }
+
+    /// The `records` read of the samples of `options`, with their BUSCO
+    /// summaries.
+    fn select(mut records: Vec<GenomeStats>, options: &LoadOptions) -> Result<Self, Box<dyn Error>> {
+        if !options.samples.is_empty() {
+            records = select_samples(records, &options.samples)?;
+        }
+        if !options.busco.is_empty() {
+            attach_busco(&mut records, options)?;
+        }
+        Ok(Dataset { records })
+    }
+
+    pub fn from_records(records: Vec<GenomeStats>) -> Self {
+        Dataset { records }
+    }
+
+    pub fn records(&self) -> &[GenomeStats] {
+        &self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Distinct groups in order of first appearance, or nothing when the
+    /// records are not grouped.
+    pub fn groups(&self) -> Vec<&str> {
+        group_labels(&self.records)
+    }
+}
+
+/// Reads CSV files, FASTA files, directories of either and glob patterns on
+/// the global thread pool, folding the records of each file into a `T` with
+/// `fold` as they are read, and returns those of every file in input order.
+fn fold_inputs<T: Send>(
+    inputs: &[PathBuf],
+    options: &LoadOptions,
+    init: impl Fn() -> T + Sync,
+    fold: impl Fn(&mut T, GenomeStats) + Sync,
+) -> Result<Vec<T>, Box<dyn Error>> {
+    headers::check_aliases(&options.aliases)?;
+    let inputs = &expand_globs(inputs)?;
+    let files = expand_inputs(inputs, options.reads)?;
+    let labels = input_labels(inputs);
+    let progress = progress::Progress::new(files.len(), options.progress);
+    let batches = files
+        .par_iter()
+        .map(|(path, input)| {
+            let mut batch = init();
+            let mut each = |mut record: GenomeStats| {
+                label_record(&mut record, path, &labels[*input], options);
+                fold(&mut batch, record);
+            };
+            progress.file(path, || read_input(path, options, &mut each))?;
+            Ok(batch)
+        })
+        .collect::<Result<Vec<_>, SendError>>();
+    progress.finish();
+    batches.map_err(|e| e as Box<dyn Error>)
+}
+
+/// Records `path` as the source of `record`, and groups or colors it by
+/// `label`, the name of its input, or by its source when `options` say so.
+fn label_record(record: &mut GenomeStats, path: &Path, label: &str, options: &LoadOptions) {
+    record.source = Some(path.display().to_string());
+    if options.group_by_input {
+        record.group = Some(label.to_string());
+    } else if options.group_by.as_deref() == Some(SOURCE_COLUMN) && record.group.is_none() {
+        record.group.clone_from(&record.source);
+    }
+    if options.color_by.as_deref() == Some(SOURCE_COLUMN) && record.color.is_none() {
+        record.color.clone_from(&record.source);
+    }
+}
+
+/// The records named by one of `samples`, exactly or without directories,
+/// compression and FASTA extensions, in the order read.
+fn select_samples(records: Vec<GenomeStats>, samples: &[String]) -> Result<Vec<GenomeStats>, Box<dyn Error>> {
+    let matches = |record: &GenomeStats, sample: &str| {
+        record.filename.as_deref().is_some_and(|name| name == sample || busco::key(name) == busco::key(sample))
+    };
+    if let Some(sample) = samples.iter().find(|sample| !records.iter().any(|record| matches(record, sample))) {
+        return Err(format!("no sample named {sample:?}").into());
+    }
+    Ok(records.into_iter().filter(|record| samples.iter().any(|sample| matches(record, sample))).collect())
+}
+
+/// Reads the BUSCO summaries of `options` into the records of the assemblies
+/// they were computed for, matched by name without FASTA extensions.
+fn attach_busco(records: &mut [GenomeStats], options: &LoadOptions) -> Result<(), Box<dyn Error>> {
+    let mut paths = Vec::new();
+    for path in expand_globs(&options.busco)? {
+        if path.is_dir() {
+            paths.extend(busco::summaries_in(&path).map_err(|e| format!("{}: {}", path.display(), e))?);
+        } else {
+            paths.push(path);
+        }
+    }
+    for path in paths {
+        let (assembly, summary) = busco::read_summary(&path).map_err(|e| e as Box<dyn Error>)?;
+        let key = busco::key(&assembly);
+        let mut matched = 0;
+        for record in records.iter_mut() {
+            if record.filename.as_deref().is_some_and(|name| busco::key(name) == key) {
+                record.busco = Some(summary);
+                matched += 1;
+            }
+        }
+        if matched == 0 {
+            warn!("{}: no assembly named {:?}", path.display(), assembly);
+        } else {
+            info!("Read BUSCO results of {} from {}", assembly, path.display());
+        }
+    }
+    let without = records.iter().filter(|record| record.busco.is_none()).count();
+    if without > 0 {
+        warn!("{} of {} assemblies have no BUSCO summary", without, records.len());
+    }
+    Ok(())
+}
+
+/// Name under which the file each record was read from can be grouped by,
+/// unless a CSV has a column of that name.
+const SOURCE_COLUMN: &str = "source";
+
+/// Replaces every input that does not exist but looks like a glob pattern by
+/// the paths it matches, for shells and workflow engines that pass patterns
+/// through unexpanded.
+fn expand_globs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut expanded = Vec::new();
+    for input in inputs {
+        let pattern = input.to_string_lossy();
+        if input.exists() || !pattern.contains(['*', '?', '[']) {
+            expanded.push(input.clone());
+            continue;
+        }
+        let mut matches = glob::glob(&pattern)?.collect::<Result<Vec<_>, _>>()?;
+        matches.retain(|path| !cache::is_cache(path));
+        if matches.is_empty() {
+            return Err(format!("no files match {pattern}").into());
+        }
+        matches.sort();
+        expanded.extend(matches);
+    }
+    Ok(expanded)
+}
+
+/// FASTA, GFA, CSV, TSV and Parquet files and NCBI assembly reports directly inside
+/// `dir`, or its FASTQ files when reading `reads`, sorted by name.
+fn input_files_in(dir: &Path, reads: bool) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let readable = if reads {
+            fastq::is_fastq(&path)
+        } else {
+            fasta::is_fasta(&path)
+                || gfa::is_gfa(&path)
+                || reader::is_table(&path)
+                || parquet_file::is_parquet(&path)
+                || ncbi::is_report(&path)
+        };
+        if path.is_file() && readable {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Expands the inputs into the files to read, replacing each directory by the
+/// files it contains. Each file comes with the index of its input.
+fn expand_inputs(inputs: &[PathBuf], reads: bool) -> Result<Vec<(PathBuf, usize)>, Box<dyn Error>> {
+    if inputs.iter().filter(|input| reader::is_stdin(input)).count() > 1 {
+        return Err("the standard input (-) can only be read once".into());
+    }
+    let mut files = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        if input.is_dir() {
+            let found = input_files_in(input, reads)?;
+            if found.is_empty() {
+                let kind = if reads { "FASTQ" } else { "FASTA or CSV" };
+                return Err(format!("no {} files found in {}", kind, input.display()).into());
+            }
+            files.extend(found.into_iter().map(|path| (path, i)));
+        } else {
+            files.push((input.clone(), i));
+        }
+    }
+    Ok(files)
+}
+
+/// Legend names of the inputs: their file stems, or the paths as given when
+/// two stems are the same, as with `flye/stats.csv` and `hifiasm/stats.csv`.
+fn input_labels(inputs: &[PathBuf]) -> Vec<String> {
+    let stems: Vec<String> = inputs
+        .iter()
+        .map(|input| {
+            if reader::is_stdin(input) {
+                return "stdin".to_string();
+            }
+            let name = input.file_name().unwrap_or(input.as_os_str());
+            Path::new(name).file_stem().unwrap_or(name).to_string_lossy().into_owned()
+        })
+        .collect();
+    let unique = stems.iter().enumerate().all(|(i, stem)| !stems[..i].contains(stem));
+    if unique {
+        stems
+    } else {
+        inputs.iter().map(|input| input.display().to_string()).collect()
+    }
+}
+
+/// Reads the records of one file, handing each to `each` as it is read.
+fn read_input(path: &Path, options: &LoadOptions, each: &mut dyn FnMut(GenomeStats)) -> Result<(), SendError> {
+    if options.reads != fastq::is_fastq(path) {
+        return Err(match options.reads {
+            true => format!("{}: reads are only read from FASTQ files", path.display()),
+            false => format!("{}: FASTQ files are only read with --reads", path.display()),
+        }
+        .into());
+    }
+    if options.reads {
+        if let Some(column) = &options.group_by {
+            return Err(missing_column(format!("{}: reads are grouped by file, not by {:?}", path.display(), column)));
+        }
+        if let Some(header) = options.extra_columns.first() {
+            return Err(missing_column(format!("{}: FASTQ inputs have no column {:?}", path.display(), header)));
+        }
+        let reads = fastq::read_fastq(path)?;
+        info!("Read {} reads from {}", reads.len(), path.display());
+        reads.into_iter().for_each(each);
+        Ok(())
+    } else if fasta::is_fasta(path) {
+        if let Some(header) = options.extra_columns.first() {
+            return Err(missing_column(format!("{}: FASTA inputs have no column {:?}", path.display(), header)));
+        }
+        let cached = options.cache.then(|| cache::read(path, &fasta::assembly_name(path))).flatten();
+        if let Some(assembly) = cached {
+            info!(
+                "Read {} sequences ({} bp) from the cache of {}",
+                assembly.lengths.len(),
+                assembly.total_length(),
+                path.display()
+            );
+            each(assembly.stats());
+            return Ok(());
+        }
+        if options.lengths_only {
+            if let Some(assembly) = fasta::read_index(path)? {
+                info!(
+                    "Read {} sequence lengths ({} bp) from the index of {}",
+                    assembly.lengths.len(),
+                    assembly.total_length(),
+                    path.display()
+                );
+                each(GenomeStats {
+                    gc_percentage: f64::NAN,
+                    n_percentage: f64::NAN,
+                    gaps: None,
+                    gap_length: None,
+                    ..assembly.stats()
+                });
+                return Ok(());
+            }
+        }
+        let assembly = fasta::read_fasta(path)?;
+        info!(
+            "Read {} sequences ({} bp) from {}",
+            assembly.lengths.len(),
+            assembly.total_length(),
+            path.display()
+        );
+        if options.cache && !reader::is_stdin(path) {
+            // The statistics are there even when the directory is read-only
+            if let Err(e) = cache::write(path, &assembly) {
+                debug!("{}: could not write its cache: {}", path.display(), e);
+            }
+        }
+        each(assembly.stats());
+        Ok(())
+    } else if gfa::is_gfa(path) {
+        if let Some(header) = options.extra_columns.first() {
+            return Err(missing_column(format!("{}: GFA inputs have no column {:?}", path.display(), header)));
+        }
+        let graph = gfa::read_gfa(path)?;
+        info!(
+            "Read {} segments ({} bp) from {}",
+            graph.assembly.lengths.len(),
+            graph.assembly.total_length(),
+            path.display()
+        );
+        each(graph.stats());
+        Ok(())
+    } else if json::is_json(path) {
+        read_json(path, reader::open(path)?, options, each)
+    } else if parquet_file::is_parquet(path) {
+        let (headers, rows) = parquet_file::read_parquet(path)?;
+        let read = read_rows(path, headers, rows, "row", options, each)?;
+        info!("Read {} records from {}", read, path.display());
+        Ok(())
+    } else if ncbi::is_report(path) {
+        let records = ncbi::read_report(path, options)?;
+        info!("Read the NCBI assembly report {}", path.display());
+        records.into_iter().for_each(each);
+        Ok(())
+    } else if sqlite::is_database(path) {
+        let table = options.table.as_deref().ok_or_else(|| {
+            format!("{}: a SQLite database, name the table to read with --table", path.display())
+        })?;
+        let (headers, rows) = sqlite::read_table(path, table)?;
+        let read = read_rows(path, headers, Box::new(rows.into_iter().map(Ok)), "row", options, each)?;
+        info!("Read {} records from table {} of {}", read, table, path.display());
+        Ok(())
+    } else {
+        let read = read_csv(path, options, each)?;
+        info!("Read {} records from {}", read, path.display());
+        Ok(())
+    }
+}
+
+/// Reads the records of the JSON or JSON Lines `input`, `path` naming it in
+/// messages and telling JSON Lines by its extension.
+fn read_json(
+    path: &Path,
+    input: Box<dyn BufRead + '_>,
+    options: &LoadOptions,
+    each: &mut dyn FnMut(GenomeStats),
+) -> Result<(), SendError> {
+    match json::read_json(path, input)? {
+        json::Json::AssemblyStats(json) => {
+            let records = assembly_stats::read_json(path, &json, options)?;
+            info!("Read {} assemblies from {}", records.len(), path.display());
+            records.into_iter().for_each(each);
+        }
+        json::Json::Table { headers, rows, unit } => {
+            let read = read_rows(path, headers, Box::new(rows.into_iter().map(Ok)), unit, options, each)?;
+            info!("Read {} records from {}", read, path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Field of a row computed from its other fields.
+type Derived = Box<dyn Fn(&StringRecord) -> String>;
+
+/// Reads the rows of a CSV one at a time, handing each record to `each`, and
+/// returns how many were read. Only QUAST reports, whose rows are columns,
+/// are held in memory whole.
+fn read_csv(path: &Path, options: &LoadOptions, each: &mut dyn FnMut(GenomeStats)) -> Result<usize, SendError> {
+    read_table(path, reader::open(path)?, options, each)
+}
+
+/// Reads the rows of the CSV `input` like [`read_csv`], `path` naming it in
+/// messages and telling its delimiter by its extension.
+fn read_table(
+    path: &Path,
+    mut input: Box<dyn BufRead + '_>,
+    options: &LoadOptions,
+    each: &mut dyn FnMut(GenomeStats),
+) -> Result<usize, SendError> {
+    let delimiter = match options.delimiter.or_else(|| reader::extension_delimiter(path)) {
+        Some(delimiter) => delimiter,
+        None => {
+            let delimiter = reader::sniff_delimiter(&mut input)?;
+            debug!("{}: fields delimited by {:?}", path.display(), delimiter as char);
+            delimiter
+        }
+    };
+    // Quoted fields may contain the delimiter; spaces around fields, as in
+    // `a, b`, are not part of the values
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(input);
+
+    let mut headers = rdr.headers().map_err(|e| format!("{}: {}", path.display(), e))?.clone();
+    let mut rows: Box<dyn Iterator<Item = Result<StringRecord, csv::Error>>> = Box::new(rdr.into_records());
+    let mut transposed = false;
+    if quast::may_be_report(&headers) {
+        let report = rows.collect::<Result<Vec<_>, _>>().map_err(|e| read_error(path, &e))?;
+        transposed = quast::is_report(&headers, report.iter().filter_map(|row| row.get(0)));
+        if transposed {
+            debug!("{}: reading a QUAST report, one column per assembly", path.display());
+            let (transposed_headers, transposed_rows) = quast::transpose(&headers, &report);
+            headers = transposed_headers;
+            rows = Box::new(transposed_rows.into_iter().map(Ok));
+        } else {
+            rows = Box::new(report.into_iter().map(Ok));
+        }
+    }
+    read_rows(path, headers, rows, if transposed { "column" } else { "line" }, options, each)
+}
+
+/// Reads `rows` under `headers` like [`read_csv`], each positioned at the
+/// `unit` its position counts in messages.
+fn read_rows(
+    path: &Path,
+    mut headers: StringRecord,
+    rows: Box<dyn Iterator<Item = Result<StringRecord, csv::Error>> + '_>,
+    unit: &str,
+    options: &LoadOptions,
+    each: &mut dyn FnMut(GenomeStats),
+) -> Result<usize, SendError> {
+    let mut read = 0;
+    let location = |row: &StringRecord| format!("{} {}", unit, row.position().map_or(0, |p| p.line()));
+    let (mut columns, renames) = headers::canonical(&headers, &options.aliases);
+    for (from, to) in renames {
+        debug!("{}: reading column {:?} as {}", path.display(), from, to);
+    }
+    // QUAST gives the N content per 100 kbp and seqkit as a count of bases
+    let n_content: Option<Derived> =
+        if let Some(i) = quast::n_per_100_kbp(&columns) {
+            debug!("{}: reading N_percentage from {:?}", path.display(), &headers[i]);
+            Some(Box::new(move |row| quast::n_percentage(row.get(i).unwrap_or_default())))
+        } else if let Some((n, length)) = seqkit::n_count(&columns) {
+            debug!(
+                "{}: reading N_percentage from {:?} and {:?}",
+                path.display(),
+                &headers[n],
+                &headers[length]
+            );
+            Some(Box::new(move |row| {
+                seqkit::n_percentage(row.get(n).unwrap_or_default(), row.get(length).unwrap_or_default())
+            }))
+        } else {
+            None
+        };
+    if n_content.is_some() {
+        columns.push_field("N_percentage");
+        headers.push_field("N_percentage");
+    }
+    if let Some(column) = headers::missing_column(&columns) {
+        if column == "N_percentage" && seqkit::is_stats(&headers) {
+            return Err(missing_column(format!(
+                "{}: no sum_n column to read N_percentage from, which recent versions of `seqkit stats -a` write",
+                path.display()
+            )));
+        }
+        return Err(missing_column(format!(
+            "{}: no column for {}; name it with --alias HEADER={} (the header is: {})",
+            path.display(),
+            column,
+            column,
+            headers.iter().collect::<Vec<_>>().join(", ")
+        )));
+    }
+    let group_column = match &options.group_by {
+        Some(column) => match headers.iter().position(|h| h == column) {
+            Some(i) => Some(i),
+            // Filled in once read
+            None if column == SOURCE_COLUMN => None,
+            None => {
+                return Err(missing_column(format!("{}: no column named {:?} to group by", path.display(), column)))
+            }
+        },
+        None => None,
+    };
+    let color_column = match &options.color_by {
+        Some(column) => match headers.iter().position(|h| h == column) {
+            Some(i) => Some(i),
+            None if column == SOURCE_COLUMN => None,
+            None => {
+                return Err(missing_column(format!("{}: no column named {:?} to color by", path.display(), column)))
+            }
+        },
+        None => None,
+    };
+    let extra_columns = options
+        .extra_columns
+        .iter()
+        .map(|column| {
+            let index = headers.iter().position(|h| h == column).ok_or_else(|| {
+                missing_column(format!("{}: no column named {:?} to plot", path.display(), column))
+            })?;
+            Ok((column, index))
+        })
+        .collect::<Result<Vec<_>, SendError>>()?;
+
+    let numeric: Vec<usize> = (0..columns.len())
+        .filter(|&i| headers::is_numeric(&columns[i]))
+        .chain(extra_columns.iter().map(|&(_, index)| index))
+        .collect();
+
+    let mut skipped = 0;
+    for row in rows {
+        let mut row = row.map_err(|e| read_error(path, &e))?;
+        if let Some(n_content) = &n_content {
+            row.push_field(&n_content(&row));
+        }
+        let at = location(&row);
+        let empty: Vec<usize> =
+            numeric.iter().copied().filter(|&i| row.get(i).is_some_and(str::is_empty)).collect();
+        if let Some(&first) = empty.first() {
+            let message = format!("{}: {}: no value in column {:?}", path.display(), at, &headers[first]);
+            match options.missing {
+                Missing::Fail => return Err(InvalidInput::new(Invalid::UnparseableRow, message).into()),
+                Missing::Skip => {
+                    info!("{message}, skipping the row");
+                    skipped += 1;
+                    continue;
+                }
+                Missing::Zero => {
+                    row = row
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| if empty.contains(&i) { "0" } else { field })
+                        .collect();
+                }
+            }
+        }
+        let mut record: GenomeStats = row
+            .deserialize(Some(&columns))
+            .map_err(|e| InvalidInput::new(Invalid::UnparseableRow, parse_error(path, &at, &headers, &row, &e)))?;
+        record.group = group_column
+            .and_then(|i| row.get(i))
+            .filter(|value| !value.is_empty())
+            .map(str::to_owned);
+        record.color = color_column
+            .and_then(|i| row.get(i))
+            .filter(|value| !value.is_empty())
+            .map(str::to_owned);
+        for &(column, index) in &extra_columns {
+            let field = row.get(index).unwrap_or("").trim();
+            let value = field.parse().map_err(|_| {
+                InvalidInput::new(
+                    Invalid::UnparseableRow,
+                    format!("{}: {}: could not parse {:?} as a number in column {}", path.display(), at, field, column),
+                )
+            })?;
+            record.extra.insert(column.clone(), value);
+        }
+        each(record);
+        read += 1;
+    }
+    if skipped > 0 {
+        warn!(
+            "{}: skipped {} of {} rows with missing values",
+            path.display(),
+            skipped,
+            skipped + read
+        );
+    }
+    Ok(read)
+}
+
+/// Explains why the next row of a CSV could not be read: a row that is not
+/// one of the table, unless the input itself could not be read.
+fn read_error(path: &Path, error: &csv::Error) -> SendError {
+    let message = match error.kind() {
+        csv::ErrorKind::UnequalLengths { pos: Some(pos), expected_len, len } => format!(
+            "{}: line {}: found {} fields, but the header has {}",
+            path.display(),
+            pos.line(),
+            len,
+            expected_len
+        ),
+        // Readers of other formats tell rows that are not of the table by
+        // their kind
+        csv::ErrorKind::Io(e) if e.kind() != std::io::ErrorKind::InvalidData => {
+            return format!("{}: {}", path.display(), error).into()
+        }
+        _ => format!("{}: {}", path.display(), error),
+    };
+    InvalidInput::new(Invalid::UnparseableRow, message).into()
+}
+
+/// An error of a column that is not in the header of an input.
+fn missing_column(message: String) -> SendError {
+    InvalidInput::new(Invalid::MissingColumn, message).into()
+}
+
+/// Explains why `row` could not be read, naming the column and the value at
+/// fault when the error points at one.
+fn parse_error(path: &Path, at: &str, headers: &StringRecord, row: &StringRecord, error: &csv::Error) -> String {
+    let detail = match error.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => match err.field().map(|i| i as usize) {
+            Some(i) => {
+                let value = row.get(i).unwrap_or_default();
+                let column = headers.get(i).unwrap_or_default();
+                match err.kind() {
+                    DeserializeErrorKind::ParseFloat(_) | DeserializeErrorKind::ParseInt(_) => {
+                        format!("could not parse {value:?} as a number in column {column}")
+                    }
+                    kind => format!("{value:?} in column {column}: {kind}"),
+                }
+            }
+            None => err.kind().to_string(),
+        },
+        _ => error.to_string(),
+    };
+    format!("{}: {}: {}", path.display(), at, detail)
+}
+
+/// Writes the figure described by `config` to `config.output`, or one figure
+/// per metric with `config.split` or per group of samples with a `{group}` in
+/// `config.output`, and returns the paths written. Despite the name, every
+/// `config.plot_type` is supported; boxplots are the default.
+pub fn render_boxplots(dataset: &Dataset, config: &PlotConfig) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let data = dataset.records();
+    if data.is_empty() {
+        return Err("the input contains no records".into());
+    }
+
+    let format = config.format.unwrap_or_else(|| OutputFormat::from_path(&config.output));
+    let groups = group_labels(data);
+    if !config.output.to_string_lossy().contains("{group}") || groups.is_empty() {
+        return write_figures(config, format, None, &|config, first_panel| write_figure(data, config, format, first_panel));
+    }
+    // One figure per group, each alone in it
+    let mut written = Vec::new();
+    for group in groups {
+        let records: Vec<GenomeStats> = data.iter().filter(|x| x.group_name() == group).cloned().collect();
+        let write = |config: &PlotConfig, first_panel| write_figure(&records, config, format, first_panel);
+        written.extend(write_figures(config, format, Some(group), &write)?);
+    }
+    Ok(written)
+}
+
+/// Writes the box plots of the distributions in `sketches` like
+/// [`render_boxplots`], to PNG, SVG or PDF. Outliers are counted but not
+/// drawn, the values beyond the whiskers not being kept, and neither are
+/// points, labels or tests, which need every record.
+pub fn render_sketches(sketches: &Sketches, config: &PlotConfig) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if sketches.is_empty() {
+        return Err("the input contains no records".into());
+    }
+    if config.plot_type != PlotType::Boxplot {
+        return Err("streamed inputs are only drawn as boxplots".into());
+    }
+    let format = config.format.unwrap_or_else(|| OutputFormat::from_path(&config.output));
+    if matches!(format, OutputFormat::Html | OutputFormat::Vega) {
+        return Err("streamed inputs are only drawn to PNG, SVG or PDF".into());
+    }
+    if let Some(metric) = config.metrics.iter().find(|metric| sketches.of(metric).is_none()) {
+        return Err(format!("{metric} was not read from the inputs").into());
+    }
+    write_figures(config, format, None, &|config, first_panel| write_pages(sketches, config, format, first_panel))
+}
+
+/// Writes the figure of a configuration, its first panel lettered after the
+/// given index, and returns the paths written.
+type WriteFigure<'a> = dyn Fn(&PlotConfig, usize) -> Result<Vec<PathBuf>, Box<dyn Error>> + 'a;
+
+/// Writes a figure with `write`, or one figure per metric with
+/// `config.split` or a `{metric}` in `config.output`, and returns the paths
+/// written. `write` is given the configuration of each figure and the index
+/// of its first panel.
+fn write_figures(
+    config: &PlotConfig,
+    format: OutputFormat,
+    group: Option<&str>,
+    write: &WriteFigure,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let per_metric = config.split || config.output.to_string_lossy().contains("{metric}");
+    if !per_metric || config.plot_type.is_whole_figure() {
+        let config = PlotConfig { output: fill_template(&config.output, None, group), ..config.clone() };
+        return write(&config, 0);
+    }
+    let mut written = Vec::new();
+    for (i, metric) in config.metrics.iter().enumerate() {
+        let output = match config.output.to_string_lossy().contains("{metric}") {
+            true => fill_template(&config.output, Some(metric), group),
+            false => split_path(&fill_template(&config.output, None, group), metric, format),
+        };
+        let config = PlotConfig { output, metrics: vec![metric.clone()], grid: None, ..config.clone() };
+        written.extend(write(&config, i)?);
+    }
+    Ok(written)
+}
+
+/// `output` with its `{metric}` and `{group}` placeholders replaced by the
+/// file names of `metric` and `group`, or by `all` for figures of every
+/// metric or group.
+fn fill_template(output: &Path, metric: Option<&Metric>, group: Option<&str>) -> PathBuf {
+    let template = output.to_string_lossy();
+    if !template.contains('{') {
+        return output.to_path_buf();
+    }
+    let metric = metric.map_or("all".to_string(), |metric| file_name(&metric.to_string()));
+    let group = group.map_or("all".to_string(), file_name);
+    PathBuf::from(template.replace("{metric}", &metric).replace("{group}", &group))
+}
+
+/// `name` as a file name: its words joined by underscores, so that columns
+/// such as "BUSCO completeness (%)" become BUSCO_completeness.
+fn file_name(name: &str) -> String {
+    let words = name.split(|c: char| !c.is_ascii_alphanumeric() && c != '-').filter(|w| !w.is_empty());
+    words.collect::<Vec<_>>().join("_")
+}
+
+/// Path of the figure of `metric` alone: a file named after the metric inside
+/// `output` when it is a directory, otherwise `output` with the metric
+/// appended to its stem, as in `count-fasta_n50.png`.
+fn split_path(output: &Path, metric: &Metric, format: OutputFormat) -> PathBuf {
+    let name = file_name(&metric.to_string());
+    if output.is_dir() {
+        return output.join(name).with_extension(format.extension());
+    }
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = output.extension().map_or(format.extension().into(), |ext| ext.to_string_lossy());
+    output.with_file_name(format!("{stem}_{name}.{extension}"))
+}
+
+/// The panels of `config.metrics` as an SVG document, lettered from the
+/// `first_panel`th letter on.
+fn svg_figure(data: &[GenomeStats], config: &PlotConfig, first_panel: usize) -> Result<String, Box<dyn Error>> {
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, config.style.canvas((config.width, config.height)))
+            .into_drawing_area();
+        draw_figure(&root, data, &config.metrics, first_panel, column_grid(&config.metrics, &config.style), config)?;
+        root.present()?;
+    }
+    Ok(svg)
+}
+
+/// Writes the panels of `config.metrics` to `config.output`, lettered from
+/// the `first_panel`th letter on, and returns the paths written: the pages
+/// of panels that overflow the grid of a PNG or SVG figure are written next
+/// to it, as `count-fasta_page2.png`.
+fn write_figure(
+    data: &[GenomeStats],
+    config: &PlotConfig,
+    format: OutputFormat,
+    first_panel: usize,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    match format {
+        OutputFormat::Png | OutputFormat::Svg | OutputFormat::Pdf => write_pages(data, config, format, first_panel),
+        OutputFormat::Html => {
+            html::write_html(&config.output, data, config)?;
+            Ok(vec![config.output.clone()])
+        }
+        OutputFormat::Vega => {
+            vega::write_vega(&config.output, data, config)?;
+            Ok(vec![config.output.clone()])
+        }
+    }
+}
+
+/// What the panels of a figure are drawn from.
+trait Figure: Sync {
+    /// Draws what surrounds the panels of `metrics`, like
+    /// [`draw_around_panels`], and returns the area of each panel.
+    fn draw_around<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        metrics: &[Metric],
+        first_panel: usize,
+        grid: (usize, usize),
+        config: &PlotConfig,
+    ) -> Result<PanelAreas<DB>, Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static;
+
+    fn draw_panel<DB: DrawingBackend>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+        panel: &Panel,
+        config: &PlotConfig,
+    ) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static;
+
+    /// Draws the whole figure of `metrics`, one panel after the other.
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        metrics: &[Metric],
+        first_panel: usize,
+        grid: (usize, usize),
+        config: &PlotConfig,
+    ) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+        for (area, panel) in self.draw_around(root, metrics, first_panel, grid, config)? {
+            self.draw_panel(&area, &panel, config)?;
+        }
+        Ok(())
+    }
+}
+
+impl Figure for [GenomeStats] {
+    fn draw_around<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        metrics: &[Metric],
+        first_panel: usize,
+        grid: (usize, usize),
+        config: &PlotConfig,
+    ) -> Result<PanelAreas<DB>, Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+        draw_around_panels(root, self, metrics, first_panel, grid, config)
+    }
+
+    fn draw_panel<DB: DrawingBackend>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+        panel: &Panel,
+        config: &PlotConfig,
+    ) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+        draw_panel(area, self, panel, config)
+    }
+}
+
+impl Figure for Sketches {
+    fn draw_around<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        metrics: &[Metric],
+        first_panel: usize,
+        grid: (usize, usize),
+        config: &PlotConfig,
+    ) -> Result<PanelAreas<DB>, Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+        draw_around_sketches(root, self, metrics, first_panel, grid, config)
+    }
+
+    fn draw_panel<DB: DrawingBackend>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+        panel: &Panel,
+        config: &PlotConfig,
+    ) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
+        draw_sketch_panel(area, self, panel, config)?;
+        debug!("Drew {} panel", panel.metric);
+        Ok(())
+    }
+}
+
+/// Writes the panels of `figure` to `config.output` as a PNG, SVG or PDF, as
+/// [`write_figure`] does.
+fn write_pages<F: Figure + ?Sized>(
+    figure: &F,
+    config: &PlotConfig,
+    format: OutputFormat,
+    first_panel: usize,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let grid = page_grid(config, format);
+    let pages = pages(config, grid);
+    if format == OutputFormat::Pdf {
+        pdf_document(figure, config, &pages, first_panel, grid)?.save(&config.output)?;
+        return Ok(vec![config.output.clone()]);
+    }
+    let mut written = Vec::new();
+    for (page, panels) in pages.into_iter().enumerate() {
+        let output = page_path(&config.output, page);
+        let metrics = &config.metrics[panels.clone()];
+        std::fs::write(&output, page_file(figure, config, format, metrics, first_panel + panels.start, grid)?)?;
+        written.push(output);
+    }
+    Ok(written)
+}
+
+/// Rows and columns of panels on each page: those given, or by default one
+/// column of every panel, or one panel per PDF page.
+fn page_grid(config: &PlotConfig, format: OutputFormat) -> (usize, usize) {
+    config.grid.unwrap_or(match format {
+        OutputFormat::Pdf => (1, 1),
+        _ => column_grid(&config.metrics, &config.style),
+    })
+}
+
+/// The PDF document of the `pages` of panels of `figure`.
+fn pdf_document<F: Figure + ?Sized>(
+    figure: &F,
+    config: &PlotConfig,
+    pages: &[Range<usize>],
+    first_panel: usize,
+    grid: (usize, usize),
+) -> Result<pdf::PdfDocument, Box<dyn Error>> {
+    let size = config.style.canvas((config.width, config.height));
+    let mut doc = pdf::PdfDocument::new();
+    for panels in pages {
+        let root = doc.page(size).into_drawing_area();
+        figure.draw(&root, &config.metrics[panels.clone()], first_panel + panels.start, grid, config)?;
+        root.present()?;
+    }
+    Ok(doc)
+}
+
+/// Contents of the PNG or SVG file of a page of the panels of `metrics` of
+/// `figure`, lettered from the `first_panel`th letter on.
+fn page_file<F: Figure + ?Sized>(
+    figure: &F,
+    config: &PlotConfig,
+    format: OutputFormat,
+    metrics: &[Metric],
+    first_panel: usize,
+    grid: (usize, usize),
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let size = config.style.canvas((config.width, config.height));
+    if format == OutputFormat::Svg {
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+            figure.draw(&root, metrics, first_panel, grid, config)?;
+            root.present()?;
+        }
+        return Ok(svg.into_bytes());
+    }
+    let draw = |buffer: &mut [u8]| draw_bitmap(figure, buffer, size, metrics, first_panel, grid, config);
+    let (pixels, color) = if config.style.transparent {
+        (transparent_pixels(size, draw)?, image::ColorType::Rgba8)
+    } else {
+        let mut buffer = vec![0u8; size.0 as usize * size.1 as usize * 3];
+        draw(&mut buffer)?;
+        (buffer, image::ColorType::Rgb8)
+    };
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png).write_image(&pixels, size.0, size.1, color)?;
+    Ok(png)
+}
+
+/// Metrics of each page of a figure of `grid` panels.
+fn pages(config: &PlotConfig, grid: (usize, usize)) -> Vec<Range<usize>> {
+    if config.plot_type.is_whole_figure() {
+        return std::iter::once(0..config.metrics.len()).collect();
+    }
+    let spans: Vec<(usize, usize)> = config.metrics.iter().map(|metric| config.style.span(metric)).collect();
+    layout::pages(&layout::place(grid, &spans))
+}
+
+/// Draws the panels of `metrics` into `buffer`, an RGB image of `size`, each
+/// panel on a thread of its own into a copy of the figure, whose pixels it
+/// changed are copied over in panel order once all are drawn. With a single
+/// thread or panel they are drawn into `buffer` in turn, like other formats.
+fn draw_bitmap<F: Figure + ?Sized>(
+    figure: &F,
+    buffer: &mut [u8],
+    size: (u32, u32),
+    metrics: &[Metric],
+    first_panel: usize,
+    grid: (usize, usize),
+    config: &PlotConfig,
+) -> Result<(), Box<dyn Error>> {
+    let placed: Vec<_> = {
+        let root = BitMapBackend::with_buffer(buffer, size).into_drawing_area();
+        let panels = figure.draw_around(&root, metrics, first_panel, grid, config)?;
+        if panels.len() < 2 || rayon::current_num_threads() < 2 {
+            for (area, panel) in &panels {
+                figure.draw_panel(area, panel, config)?;
+            }
+            root.present()?;
+            return Ok(());
+        }
+        root.present()?;
+        panels.into_iter().map(|(area, panel)| (area.get_pixel_range(), panel)).collect()
+    };
+    let base = buffer.to_vec();
+    let stride = size.0 as usize * 3;
+    // The rows each panel changed, from the first, which may reach past its
+    // area as labels do
+    let layers = placed
+        .par_iter()
+        .map(|((xs, ys), panel)| {
+            let mut copy = base.clone();
+            {
+                let root = BitMapBackend::with_buffer(&mut copy, size).into_drawing_area();
+                let area =
+                    root.clone().shrink((xs.start as u32, ys.start as u32), (xs.len() as u32, ys.len() as u32));
+                figure.draw_panel(&area, panel, config).map_err(|e| e.to_string())?;
+                root.present().map_err(|e| e.to_string())?;
+            }
+            let changed = |y: &usize| copy[y * stride..(y + 1) * stride] != base[y * stride..(y + 1) * stride];
+            let rows = 0..size.1 as usize;
+            Ok(match (rows.clone().find(changed), rows.rev().find(changed)) {
+                (Some(first), Some(last)) => Some((first * stride, copy[first * stride..(last + 1) * stride].to_vec())),
+                _ => None,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    for (start, pixels) in layers.into_iter().flatten() {
+        let end = start + pixels.len();
+        let pixels = pixels.chunks_exact(3).zip(base[start..end].chunks_exact(3));
+        for (target, (pixel, beneath)) in buffer[start..end].chunks_exact_mut(3).zip(pixels) {
+            if pixel != beneath {
+                target.copy_from_slice(pixel);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// RGBA pixels of a figure drawn with `draw` on black and on white, with
+/// the opacity that tells the two apart, which the bitmap backend has no
+/// channel for.
+fn transparent_pixels(
+    size: (u32, u32),
+    draw: impl Fn(&mut [u8]) -> Result<(), Box<dyn Error>>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let pixels = size.0 as usize * size.1 as usize;
+    let (mut black, mut white) = (vec![0u8; pixels * 3], vec![255u8; pixels * 3]);
+    for buffer in [&mut black, &mut white] {
+        draw(buffer)?;
+    }
+    let mut rgba = Vec::with_capacity(pixels * 4);
+    for (on_black, on_white) in black.chunks_exact(3).zip(white.chunks_exact(3)) {
+        // A color c of opacity a is a·c over black and a·c + (1 - a)·255
+        // over white
+        let alpha = (0..3).map(|i| 255 - (on_white[i] as u32).saturating_sub(on_black[i] as u32)).max().unwrap_or(255);
+        for &channel in on_black {
+            rgba.push((channel as u32 * 255 + alpha / 2).checked_div(alpha).unwrap_or(0).min(255) as u8);
+        }
+        rgba.push(alpha as u8);
+    }
+    Ok(rgba)
+}
+
+/// Grid of a single column holding every one of `metrics`, as tall as their
+/// spans add up to.
+fn column_grid(metrics: &[Metric], style: &Style) -> (usize, usize) {
+    (metrics.iter().map(|metric| style.span(metric).0).sum::<usize>().max(1), 1)
+}
+
+/// Path of the `page`th file of a figure, from zero: `output` itself for the
+/// first, then `output` with `_page2`, `_page3`... appended to its stem.
+fn page_path(output: &Path, page: usize) -> PathBuf {
+    if page == 0 {
+        return output.to_path_buf();
+    }
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    match output.extension() {
+        Some(extension) => output.with_file_name(format!("{stem}_page{}.{}", page + 1, extension.to_string_lossy())),
+        None => output.with_file_name(format!("{stem}_page{}", page + 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panel_letters_past_z() {
+        let letters: Vec<String> = [0, 1, 25, 26, 27, 51, 52, 191, 701, 702].into_iter().map(panel_letter).collect();
+        assert_eq!(letters, ["A", "B", "Z", "AA", "AB", "AZ", "BA", "GJ", "ZZ", "AAA"]);
+        assert!(!panel_letter(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn range_clips_box_but_not_quartiles() {
+        let values: Vec<f64> = (1..=10).map(f64::from).chain([1000.0]).collect();
+        let summary = Summary::compute(&values, QuantileMethod::default());
+        let clipped = clip_summary(&summary, (0.0, 20.0));
+        assert_eq!((clipped.q1, clipped.median, clipped.q3), (summary.q1, summary.median, summary.q3));
+        assert_eq!(clipped.median, 6.0);
+        assert_eq!((clipped.whisker_low, clipped.whisker_high), (1.0, 10.0));
+        assert_eq!(clipped.outliers, 1);
+        // Past the box, whiskers and box end at the axis
+        let clipped = clip_summary(&summary, (4.0, 5.0));
+        assert_eq!((clipped.whisker_low, clipped.q1, clipped.q3, clipped.whisker_high), (4.0, 4.0, 5.0, 5.0));
+    }
+}
@@ -0,0 +1,122 @@
+//! Inputs still being written, such as the CSV a batch of assemblies appends
+//! a row to as each one finishes, or JSON Lines piped from a socket: their
+//! records are read on a thread of their own as they arrive, and the figure
+//! drawn from those read so far.
+
+use crate::{input_labels, json, label_record, read_rows, read_table, Dataset, GenomeStats, LoadOptions, SendError};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long to wait for a file to grow before reading it again.
+const POLL: Duration = Duration::from_millis(250);
+
+/// A file read as it grows, like `tail -f`: reads wait for more rather than
+/// end.
+struct Tail(File);
+
+impl Read for Tail {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.0.read(buf)? {
+                0 if !buf.is_empty() => thread::sleep(POLL),
+                n => return Ok(n),
+            }
+        }
+    }
+}
+
+/// Records of a CSV or JSON Lines input, read as they are written to it.
+pub struct LiveInput {
+    records: Arc<Mutex<Vec<GenomeStats>>>,
+    reader: JoinHandle<Result<(), SendError>>,
+    options: LoadOptions,
+}
+
+impl LiveInput {
+    /// Starts reading `path`, followed as it grows, or the standard input
+    /// for `-` until it ends. The input is read as JSON Lines when it starts
+    /// with an object and as a CSV otherwise.
+    pub fn open(path: &Path, options: &LoadOptions) -> Result<Self, Box<dyn Error>> {
+        if options.reads {
+            return Err("only CSV and JSON Lines inputs can be followed, not FASTQ reads".into());
+        }
+        crate::headers::check_aliases(&options.aliases)?;
+        let input: Box<dyn BufRead + Send> = match crate::reader::is_stdin(path) {
+            true => Box::new(BufReader::new(io::stdin())),
+            false => Box::new(BufReader::new(Tail(
+                File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+            ))),
+        };
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let reader = {
+            let (records, options, path) = (records.clone(), options.clone(), path.to_path_buf());
+            thread::spawn(move || read_live(&path, input, &options, &records))
+        };
+        Ok(LiveInput { records, reader, options: options.clone() })
+    }
+
+    /// Number of records read so far.
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the input has ended, or could not be read further.
+    pub fn is_finished(&self) -> bool {
+        self.reader.is_finished()
+    }
+
+    /// The records read so far, of the samples of the options, with their
+    /// BUSCO summaries.
+    pub fn dataset(&self) -> Result<Dataset, Box<dyn Error>> {
+        Dataset::select(self.records.lock().unwrap().clone(), &self.options)
+    }
+
+    /// Waits for the end of the input, and returns why it could not be read
+    /// to the end if it could not.
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        match self.reader.join() {
+            Ok(result) => result.map_err(|e| e as Box<dyn Error>),
+            Err(_) => Err("the input stopped being read".into()),
+        }
+    }
+}
+
+/// Reads the records of `input`, read from `path`, into `records` as they
+/// arrive.
+fn read_live(
+    path: &Path,
+    mut input: Box<dyn BufRead + Send>,
+    options: &LoadOptions,
+    records: &Mutex<Vec<GenomeStats>>,
+) -> Result<(), SendError> {
+    let label = &input_labels(&[path.to_path_buf()])[0];
+    let mut each = |mut record: GenomeStats| {
+        label_record(&mut record, path, label, options);
+        records.lock().unwrap().push(record);
+    };
+    let first = input.fill_buf()?.iter().copied().find(|byte| !byte.is_ascii_whitespace());
+    if first != Some(b'{') {
+        read_table(path, input, options, &mut each)?;
+        return Ok(());
+    }
+    // Each line is read as soon as it is complete, rather than once the
+    // header of every field is known
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (headers, rows) = json::table(path, vec![(i + 1, json::parse_line(path, i + 1, &line)?)], "line")?;
+        read_rows(path, headers, Box::new(rows.into_iter().map(Ok)), "line", options, &mut each)?;
+    }
+    Ok(())
+}
@@ -1,134 +1,298 @@
-use plotters::prelude::*;
-use plotters::coord::types::RangedCoordf64;
-use serde::Deserialize;
+mod cli;
+#[cfg(feature = "tui")]
+mod explore;
+mod serve;
+mod watch;
+
+use clap::Parser;
+use cli::{Args, Cli, Mode};
+use count_fasta_plots::{
+    check_dataset, render_boxplots, render_sketches, write_aggregate_stats, write_comparisons, write_markdown, write_outliers, write_report, write_sketch_summaries,
+    write_sketch_summary_table, write_stats, write_summaries, write_summary_table, write_terminal, Dataset, LiveInput, PlotConfig,
+    Invalid, InvalidInput, Sketches,
+};
 use std::error::Error;
-use statistical::median;
-
-#[derive(Debug, Deserialize)]
-struct GenomeStats {
-    assembly_length: f64,
-    number_of_sequences: f64,
-    #[serde(rename = "N50")]
-    n50: f64,
-    #[serde(rename = "GC_percentage")]
-    gc_percentage: f64,
-    #[serde(rename = "N_percentage")]
-    n_percentage: f64,
+use std::io::IsTerminal;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+fn main() -> ExitCode {
+    let args = Cli::parse().into_args();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            match e.downcast_ref::<InvalidInput>() {
+                Some(invalid) if args.check => exit_code(invalid.kind),
+                _ => ExitCode::FAILURE,
+            }
+        }
+    }
 }
 
-fn create_boxplot<DB: DrawingBackend>(
-    plot: &mut ChartContext<DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
-    data: &[f64],
-    y_position: f64,
-) -> Result<(), Box<dyn Error>> where <DB as plotters::prelude::DrawingBackend>::ErrorType: 'static {
-    // Calculate box plot statistics
-    let mut sorted_data = data.to_vec();
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    
-    let q1_idx = (sorted_data.len() as f64 * 0.25) as usize;
-    let q3_idx = (sorted_data.len() as f64 * 0.75) as usize;
-    
-    let q1 = sorted_data[q1_idx];
-    let q3 = sorted_data[q3_idx];
-    let med = median(&sorted_data);
-    
-    // Calculate IQR and whisker bounds
-    let iqr = q3 - q1;
-    let lower_bound = q1 - 1.5 * iqr;
-    let upper_bound = q3 + 1.5 * iqr;
-    
-    // Find actual whisker ends (last non-outlier points)
-    let whisker_min = sorted_data.iter()
-        .find(|&&x| x >= lower_bound)
-        .copied()
-        .unwrap_or(q1);
-    let whisker_max = sorted_data.iter()
-        .rev()
-        .find(|&&x| x <= upper_bound)
-        .copied()
-        .unwrap_or(q3);
-    
-    // Draw box
-    plot.draw_series(std::iter::once(Rectangle::new(
-        [(q1, y_position - 0.3), (q3, y_position + 0.3)],
-        BLUE.mix(0.3),
-    )))?;
-    
-    // Draw median line
-    plot.draw_series(std::iter::once(Rectangle::new(
-        [(med - 0.1, y_position - 0.3), (med + 0.1, y_position + 0.3)],
-        RED,
-    )))?;
-    
-    // Draw whiskers
-    plot.draw_series(std::iter::once(PathElement::new(
-        vec![(whisker_min, y_position), (q1, y_position)],
-        BLACK,
-    )))?;
-    plot.draw_series(std::iter::once(PathElement::new(
-        vec![(q3, y_position), (whisker_max, y_position)],
-        BLACK,
-    )))?;
-    
-    // Draw outlier points
-    let outliers: Vec<_> = data.iter()
-        .filter(|&&x| x < lower_bound || x > upper_bound)
-        .collect();
-    
-    plot.draw_series(outliers.iter().map(|&&x| {
-        Circle::new((x, y_position), 3, BLACK.filled())
-    }))?;
-    
+/// Exit status of --check for inputs that are not valid; 1 is left for other
+/// errors and 2 for those of the command line.
+fn exit_code(kind: Invalid) -> ExitCode {
+    ExitCode::from(match kind {
+        Invalid::MissingColumn => 3,
+        Invalid::UnparseableRow => 4,
+        Invalid::Empty => 5,
+        Invalid::OutOfRange => 6,
+    })
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    init_logging(args);
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads)
+        .build_global()?;
+
+    if args.summary_table.is_some() && args.summary_table == args.table {
+        return Err("the summary statistics would replace the table they are computed from".into());
+    }
+    let config = args.plot_config()?;
+    #[cfg(feature = "tui")]
+    if args.mode == Mode::Explore {
+        return explore::explore(args, &config);
+    }
+    if args.check {
+        return check(args);
+    }
+    if let Mode::Stats { aggregate } = args.mode {
+        return print_stats(args, &config, aggregate);
+    }
+    if args.follow {
+        return render_live(args, &config);
+    }
+    if let Some(address) = &args.serve {
+        return serve::serve(address, args, &config);
+    }
+    render(args, &config)?;
+    if args.watch {
+        watch::watch(&args.inputs, || render(args, &config))?;
+    }
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut data = Vec::new();
-    let mut rdr = csv::ReaderBuilder::new()
-        .delimiter(b';')
-        .from_path(std::env::args().nth(1).expect("Please provide a CSV file"))?;
-    
-    for result in rdr.deserialize() {
-        let record: GenomeStats = result?;
-        data.push(record);
-    }
-
-    let root = BitMapBackend::new("count-fasta.png", (800, 1120))
-        .into_drawing_area();
-    root.fill(&WHITE)?;
-
-    let plots = root.split_evenly((5, 1));
-    
-    let metrics: Vec<(&str, &str, Box<dyn Fn(&GenomeStats) -> f64>)> = vec![
-        ("Assembly size (bp.)", "bp.", Box::new(|x| x.assembly_length)),
-        ("Scaffold count", "Count", Box::new(|x| x.number_of_sequences)),
-        ("N50 (bp.)", "bp.", Box::new(|x| x.n50)),
-        ("GC ratio (%)", "GC ratio (%)", Box::new(|x| x.gc_percentage)),
-        ("N's ratio (%)", "Ratio (%)", Box::new(|x| x.n_percentage)),
-    ];
-
-    for (i, (plot_area, (title, xlabel, metric))) in plots.iter().zip(metrics.iter()).enumerate() {
-        let values: Vec<f64> = data.iter().map(metric).collect();
-        let min = values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let padding = (max - min) * 0.1;
-
-        let mut chart = ChartBuilder::on(plot_area)
-            .margin(5)
-            .caption(title, ("sans-serif", 20))
-            .set_label_area_size(LabelAreaPosition::Left, 40)
-            .set_label_area_size(LabelAreaPosition::Bottom, 40)
-            .build_cartesian_2d(min - padding..max + padding, 0.0..2.0)?;
-
-        chart
-            .configure_mesh()
-            .disable_y_mesh()
-            .y_desc(format!("{}", (b'A' + i as u8) as char))
-            .x_desc(xlabel.to_string())
-            .draw()?;
-
-        create_boxplot(&mut chart, &values, 1.0)?;
+/// Logs to stderr at the level of -v and --quiet: warnings, such as those of
+/// skipped rows, by default, what is read, skipped and written and how long
+/// it took with -v, and how inputs are read with -vv. --watch, --follow and
+/// --serve log as with -v unless --quiet. `RUST_LOG` overrides the level. Nothing is logged over the screen of the explorer.
+fn init_logging(args: &Args) {
+    #[cfg(feature = "tui")]
+    if args.mode == Mode::Explore {
+        return;
     }
+    let interactive = args.watch || args.follow || args.serve.is_some();
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => "error",
+        (false, 0) if interactive => "info",
+        (false, 0) => "warn",
+        (false, 1) => "info",
+        (false, _) => "debug",
+    };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .without_time()
+        .with_target(false)
+        .init();
+}
+
+/// The records of the inputs, which must not be empty.
+fn load(args: &Args) -> Result<Dataset, Box<dyn Error>> {
+    let started = Instant::now();
+    let dataset = Dataset::load(&args.inputs, &args.load_options())?;
+    if dataset.is_empty() {
+        return Err("the input contains no records".into());
+    }
+    info!(elapsed = ?started.elapsed(), "Loaded {} records", dataset.records().len());
+    Ok(dataset)
+}
+
+/// Reads the inputs and checks their values without drawing them, printing
+/// each value out of range.
+fn check(args: &Args) -> Result<(), Box<dyn Error>> {
+    let started = Instant::now();
+    let dataset = Dataset::load(&args.inputs, &args.load_options())?;
+    match check_dataset(&dataset) {
+        Ok(()) => {
+            info!(elapsed = ?started.elapsed(), "Checked {} records", dataset.records().len());
+            Ok(())
+        }
+        Err(mut problems) if problems[0].kind == Invalid::Empty => Err(problems.remove(0).into()),
+        Err(problems) => {
+            for problem in &problems {
+                eprintln!("error: {problem}");
+            }
+            let noun = if problems.len() == 1 { "value is" } else { "values are" };
+            Err(InvalidInput::new(Invalid::OutOfRange, format!("{} {noun} out of range", problems.len())).into())
+        }
+    }
+}
+
+/// Reads the inputs and prints their statistics, per sample or per group when
+/// `aggregate`.
+fn print_stats(args: &Args, config: &PlotConfig, aggregate: bool) -> Result<(), Box<dyn Error>> {
+    let dataset = load(args)?;
+    let mut out = std::io::stdout().lock();
+    let printed = match aggregate {
+        true => write_aggregate_stats(&mut out, &dataset, config),
+        false => write_stats(&mut out, &dataset),
+    };
+    match printed {
+        // Piped to head and the like
+        Err(e) if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe) => Ok(()),
+        printed => printed,
+    }
+}
 
+/// Reads the inputs and writes the figure, or the report, and the summaries.
+fn render(args: &Args, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    if args.stream {
+        return render_streamed(args, config);
+    }
+    write_outputs(args, config, &load(args)?)
+}
+
+/// Writes the figure, or the report, and the summaries of `dataset`.
+fn write_outputs(args: &Args, config: &PlotConfig, dataset: &Dataset) -> Result<(), Box<dyn Error>> {
+    let started = Instant::now();
+    let written = if args.mode == Mode::Report {
+        write_report(&config.output, dataset, config)?;
+        vec![config.output.clone()]
+    } else if args.terminal {
+        let columns = match args.deterministic {
+            true => 80,
+            false => std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()).unwrap_or(80),
+        };
+        write_terminal(&mut std::io::stdout().lock(), dataset, config, columns)?;
+        Vec::new()
+    } else {
+        render_boxplots(dataset, config)?
+    };
+    let elapsed = started.elapsed();
+    for path in &written {
+        info!(?elapsed, "Wrote {}", path.display());
+    }
+
+    if let Some(path) = &args.stats_out {
+        let started = Instant::now();
+        write_summaries(
+            path,
+            dataset.records(),
+            &config.metrics,
+            &dataset.groups(),
+            args.quantile_method,
+            args.bootstrap(),
+        )?;
+        info!(elapsed = ?started.elapsed(), "Wrote {}", path.display());
+    }
+
+    if let (Some(database), Some(table)) = (&args.sqlite, &args.summary_table) {
+        let started = Instant::now();
+        write_summary_table(
+            database,
+            table,
+            dataset.records(),
+            &config.metrics,
+            &dataset.groups(),
+            args.quantile_method,
+            args.bootstrap(),
+        )?;
+        info!(elapsed = ?started.elapsed(), "Wrote table {} of {}", table, database.display());
+    }
+
+    if let Some(path) = &args.flag_outliers {
+        let started = Instant::now();
+        let flagged = write_outliers(
+            path,
+            dataset.records(),
+            &config.metrics,
+            &dataset.groups(),
+            args.outlier_rule,
+            args.outlier_threshold.unwrap_or(args.outlier_rule.default_threshold()),
+            args.quantile_method,
+        )?;
+        info!(elapsed = ?started.elapsed(), "Wrote {} ({flagged} flagged)", path.display());
+    }
+
+    if let Some(path) = &args.significance_out {
+        let started = Instant::now();
+        let tested = write_comparisons(path, dataset.records(), &config.metrics, &dataset.groups())?;
+        info!(elapsed = ?started.elapsed(), "Wrote {} ({tested} metrics tested)", path.display());
+    }
+
+    if let Some(path) = &args.markdown {
+        let started = Instant::now();
+        write_markdown(path, dataset, config, &written)?;
+        info!(elapsed = ?started.elapsed(), "Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Reads the input as it is written to and writes the figure, and the
+/// summaries, of the records read so far every --interval seconds while
+/// more arrive, until the input ends. Errors of a render are reported
+/// without stopping.
+fn render_live(args: &Args, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    let [path] = args.inputs.as_slice() else {
+        return Err("--follow reads a single input".into());
+    };
+    let input = LiveInput::open(path, &args.load_options())?;
+    info!("Following {}, press Ctrl-C to stop", path.display());
+    let mut drawn = 0;
+    loop {
+        // Checked first, so that the records read before the end are drawn
+        let finished = input.is_finished();
+        let read = input.len();
+        if read > drawn {
+            info!("Drawing {read} records");
+            if let Err(e) = input.dataset().and_then(|dataset| write_outputs(args, config, &dataset)) {
+                error!("{e}");
+            }
+            drawn = read;
+        }
+        if finished {
+            input.finish()?;
+            if drawn == 0 {
+                return Err("the input contains no records".into());
+            }
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs_f64(args.interval));
+    }
+}
+
+/// Reads the inputs in one pass and writes the boxplots, and the summaries,
+/// of their sketches.
+fn render_streamed(args: &Args, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    if args.mode == Mode::Report {
+        return Err("reports need every record, which --stream does not keep".into());
+    }
+    let started = Instant::now();
+    let sketches = Sketches::load(&args.inputs, &args.load_options(), &config.metrics)?;
+    info!(elapsed = ?started.elapsed(), "Read the inputs");
+    let started = Instant::now();
+    let written = render_sketches(&sketches, config)?;
+    let elapsed = started.elapsed();
+    for path in &written {
+        info!(?elapsed, "Wrote {}", path.display());
+    }
+    if let Some(path) = &args.stats_out {
+        let started = Instant::now();
+        write_sketch_summaries(path, &sketches, args.quantile_method)?;
+        info!(elapsed = ?started.elapsed(), "Wrote {}", path.display());
+    }
+    if let (Some(database), Some(table)) = (&args.sqlite, &args.summary_table) {
+        let started = Instant::now();
+        write_sketch_summary_table(database, table, &sketches, args.quantile_method)?;
+        info!(elapsed = ?started.elapsed(), "Wrote table {} of {}", table, database.display());
+    }
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,83 @@
+//! Markdown summary of a run, for wikis and lab notebooks.
+
+use crate::report::number;
+use crate::summary::summary_rows;
+use crate::{group_labels, Dataset, PlotConfig};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Writes a table of the summary statistics of every metric, per group when
+/// grouped, followed by the `figures` written for it: images are embedded and
+/// other files linked, relative to `path` when they lie beside it.
+pub fn write_markdown(
+    path: &Path,
+    dataset: &Dataset,
+    config: &PlotConfig,
+    figures: &[PathBuf],
+) -> Result<(), Box<dyn Error>> {
+    let data = dataset.records();
+    let groups = group_labels(data);
+    let mut markdown = String::new();
+    writeln!(markdown, "# {}\n", config.style.title.as_deref().unwrap_or("Assembly statistics"))?;
+    write!(markdown, "{} samples", data.len())?;
+    if !groups.is_empty() {
+        write!(markdown, " in {} groups", groups.len())?;
+    }
+    writeln!(markdown, ".\n")?;
+
+    let group_column = if groups.is_empty() { "" } else { " Group |" };
+    let group_rule = if groups.is_empty() { "" } else { " --- |" };
+    writeln!(markdown, "| Metric |{group_column} n | Min | Q1 | Median | Q3 | Max | Mean | SD | Outliers |")?;
+    writeln!(markdown, "| --- |{group_rule} ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: |")?;
+    for row in summary_rows(data, &config.metrics, &groups, config.quantile_method, config.bootstrap) {
+        write!(markdown, "| {} |", cell(&config.style.title(row.metric)))?;
+        if let Some(group) = row.group {
+            write!(markdown, " {} |", cell(group))?;
+        }
+        write!(markdown, " {} |", row.count)?;
+        for value in [row.min, row.q1, row.median, row.q3, row.max, row.mean, row.stddev] {
+            write!(markdown, " {} |", number(value))?;
+        }
+        writeln!(markdown, " {} |", row.outliers)?;
+    }
+
+    if !figures.is_empty() {
+        writeln!(markdown, "\n## Figures\n")?;
+    }
+    let base = path.parent().unwrap_or(Path::new(""));
+    for figure in figures {
+        let target = relative_to(figure, base);
+        let target = target.display().to_string().replace(' ', "%20");
+        let name = figure.file_name().unwrap_or_default().to_string_lossy();
+        let is_image = figure
+            .extension()
+            .is_some_and(|ext| ["png", "svg"].iter().any(|image| ext.eq_ignore_ascii_case(image)));
+        if is_image {
+            writeln!(markdown, "![{name}]({target})\n")?;
+        } else {
+            writeln!(markdown, "- [{name}]({target})")?;
+        }
+    }
+
+    std::fs::write(path, markdown)?;
+    Ok(())
+}
+
+/// `path` relative to the directory `base` when it lies inside it, as given
+/// otherwise.
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let absolute = |path: &Path| std::path::absolute(path).ok();
+    match (absolute(path), absolute(if base.as_os_str().is_empty() { Path::new(".") } else { base })) {
+        (Some(path), Some(base)) => match path.strip_prefix(&base) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path,
+        },
+        _ => path.to_path_buf(),
+    }
+}
+
+/// `text` made safe to put in a table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,121 @@
+//! NCBI assembly statistics reports, the `*_assembly_stats.txt` files of
+//! GenBank and RefSeq assembly downloads.
+
+use crate::{reader, GenomeStats, Invalid, InvalidInput, LoadOptions, Missing, SendError};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+const SUFFIX: &str = "_assembly_stats.txt";
+
+/// Whether `path` is named like an NCBI assembly statistics report.
+pub fn is_report(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    name.ends_with(SUFFIX)
+}
+
+/// Reads the statistics of the whole assembly, those of the rows whose unit,
+/// molecule, location and sequence type are all `all`. The record is named
+/// after the file, as in `GCF_000001405.40_GRCh38.p14`, and can be grouped by
+/// a field of the header such as `Organism name`.
+pub fn read_report(path: &Path, options: &LoadOptions) -> Result<Vec<GenomeStats>, SendError> {
+    let mut fields = HashMap::new();
+    let mut stats: HashMap<String, f64> = HashMap::new();
+    for line in reader::open(path)?.lines() {
+        let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+        if let Some(field) = line.strip_prefix("# ") {
+            if let Some((key, value)) = field.split_once(':') {
+                fields.insert(key.trim().to_string(), value.trim().to_string());
+            }
+            continue;
+        }
+        let columns: Vec<&str> = line.split('\t').collect();
+        if let [unit, molecule, location, sequence, statistic, value] = columns[..] {
+            if [unit, molecule, location, sequence].iter().all(|&c| c == "all") {
+                if let Ok(value) = value.trim().parse() {
+                    stats.insert(statistic.to_string(), value);
+                }
+            }
+        }
+    }
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    let name = name.strip_suffix(SUFFIX).unwrap_or(name).to_string();
+    let total = stats.get("total-length").copied();
+    // Older reports count GC bases instead of giving a percentage
+    let gc = stats.get("gc-perc").copied().or_else(|| Some(stats.get("gc-count")? / total? * 100.0));
+    let n = total.and_then(|total| Some(stats.get("total-gap-length")? / total * 100.0));
+
+    let mut missing = None;
+    let mut value = |statistic: &'static str, value: Option<f64>| {
+        value.unwrap_or_else(|| {
+            missing.get_or_insert(statistic);
+            0.0
+        })
+    };
+    let mut record = GenomeStats {
+        filename: Some(name),
+        assembly_length: value("total-length", total),
+        number_of_sequences: value("scaffold-count", stats.get("scaffold-count").copied()),
+        n50: value("scaffold-N50", stats.get("scaffold-N50").copied()),
+        gc_percentage: value("gc-perc", gc),
+        n_percentage: value("total-gap-length", n),
+        aun: None,
+        l50: stats.get("scaffold-L50").copied(),
+        l90: None,
+        largest_contig: None,
+        shortest_contig: None,
+        gaps: None,
+        gap_length: stats.get("total-gap-length").copied(),
+        group: None,
+        color: None,
+        lengths: Vec::new(),
+        sequence_gc: Vec::new(),
+        extra: HashMap::new(),
+        source: None,
+        busco: None,
+        mean_quality: None,
+    };
+    if let Some(statistic) = missing {
+        let message = format!("{}: no {} statistic for the whole assembly", path.display(), statistic);
+        match options.missing {
+            Missing::Fail => return Err(InvalidInput::new(Invalid::MissingColumn, message).into()),
+            Missing::Skip => {
+                tracing::warn!("{message}, skipping it");
+                return Ok(Vec::new());
+            }
+            Missing::Zero => {}
+        }
+    }
+
+    if let Some(column) = &options.group_by {
+        if let Some(value) = fields.get(column.as_str()) {
+            record.group = Some(value.clone());
+        } else if column != crate::SOURCE_COLUMN {
+            let message = format!("{}: no field named {:?} to group by", path.display(), column);
+            return Err(InvalidInput::new(Invalid::MissingColumn, message).into());
+        }
+    }
+    if let Some(column) = &options.color_by {
+        if let Some(value) = fields.get(column.as_str()) {
+            record.color = Some(value.clone());
+        } else if column != crate::SOURCE_COLUMN {
+            let message = format!("{}: no field named {:?} to color by", path.display(), column);
+            return Err(InvalidInput::new(Invalid::MissingColumn, message).into());
+        }
+    }
+    for column in &options.extra_columns {
+        let value = stats
+            .get(column.as_str())
+            .ok_or_else(|| {
+                InvalidInput::new(
+                    Invalid::MissingColumn,
+                    format!("{}: no statistic named {:?} to plot", path.display(), column),
+                )
+            })?;
+        record.extra.insert(column.clone(), *value);
+    }
+    Ok(vec![record])
+}
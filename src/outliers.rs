@@ -0,0 +1,155 @@
+//! Report of the samples whose metrics fall outside the usual range of their
+//! group.
+
+use crate::summary::{quantile, QuantileMethod, Summary};
+use crate::{GenomeStats, Metric};
+use clap::ValueEnum;
+use serde::Serialize;
+use statistical::{mean, median, standard_deviation};
+use std::error::Error;
+use std::path::Path;
+
+/// How a value is judged an outlier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+#[value(rename_all = "lower")]
+pub enum OutlierRule {
+    /// Beyond the Tukey fences, threshold IQR past the quartiles, as the box
+    /// plots draw them
+    #[default]
+    Tukey,
+    /// More than threshold standard deviations from the mean
+    Zscore,
+    /// Modified z-score (Iglewicz and Hoaglin, 1993) beyond threshold: the
+    /// distance to the median in units of the median absolute deviation,
+    /// which a few extreme values do not inflate
+    Mad,
+}
+
+impl OutlierRule {
+    /// Threshold used when none is given: the 1.5 IQR of the box plots, the
+    /// usual three standard deviations and the 3.5 recommended for the
+    /// modified z-score.
+    pub fn default_threshold(self) -> f64 {
+        match self {
+            OutlierRule::Tukey => 1.5,
+            OutlierRule::Zscore => 3.0,
+            OutlierRule::Mad => 3.5,
+        }
+    }
+
+    /// Range of `values`, which must not be empty, outside which a value is
+    /// an outlier.
+    fn bounds(self, values: &[f64], threshold: f64, method: QuantileMethod) -> (f64, f64) {
+        match self {
+            OutlierRule::Tukey => {
+                let summary = Summary::compute(values, method);
+                let iqr = summary.q3 - summary.q1;
+                (summary.q1 - threshold * iqr, summary.q3 + threshold * iqr)
+            }
+            OutlierRule::Zscore => {
+                let m = mean(values);
+                let sd = if values.len() > 1 { standard_deviation(values, Some(m)) } else { 0.0 };
+                (m - threshold * sd, m + threshold * sd)
+            }
+            OutlierRule::Mad => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let center = quantile(&sorted, 0.5, method);
+                let deviations: Vec<f64> = values.iter().map(|x| (x - center).abs()).collect();
+                // 0.6745 is the third quartile of the standard normal, which
+                // makes the MAD of normal data match its standard deviation
+                let spread = threshold * median(&deviations) / 0.6745;
+                (center - spread, center + spread)
+            }
+        }
+    }
+}
+
+/// One flagged value, with the range it fell outside of.
+#[derive(Debug, Serialize)]
+pub struct Flag<'a> {
+    pub sample: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<&'a str>,
+    pub metric: &'a Metric,
+    pub value: f64,
+    pub rule: OutlierRule,
+    pub threshold: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Every value of `metrics` that `rule` flags in `data`, judged within its
+/// group when grouped, by metric and then in the order of the samples.
+pub fn flag_outliers<'a>(
+    data: &[GenomeStats],
+    metrics: &'a [Metric],
+    groups: &[&'a str],
+    rule: OutlierRule,
+    threshold: f64,
+    method: QuantileMethod,
+) -> Vec<Flag<'a>> {
+    let ungrouped = [None];
+    let subsets: Vec<Option<&str>> = match groups.is_empty() {
+        true => ungrouped.to_vec(),
+        false => groups.iter().copied().map(Some).collect(),
+    };
+    let mut flags = Vec::new();
+    for metric in metrics {
+        for &group in &subsets {
+            let samples: Vec<(usize, f64)> = data
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| group.is_none_or(|group| x.group_name() == group))
+                .map(|(i, x)| (i, metric.value(x)))
+                .filter(|(_, v)| v.is_finite())
+                .collect();
+            if samples.is_empty() {
+                continue;
+            }
+            let values: Vec<f64> = samples.iter().map(|(_, v)| *v).collect();
+            let (low, high) = rule.bounds(&values, threshold, method);
+            flags.extend(samples.into_iter().filter(|&(_, v)| v < low || v > high).map(|(i, value)| Flag {
+                sample: data[i].name(i),
+                group,
+                metric,
+                value,
+                rule,
+                threshold,
+                low,
+                high,
+            }));
+        }
+    }
+    flags
+}
+
+/// Writes the values of `metrics` that `rule` flags, as JSON when `path`
+/// ends in `.json` and as CSV (tab-separated for `.tsv`) otherwise, and
+/// returns how many there are.
+pub fn write_outliers(
+    path: &Path,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    groups: &[&str],
+    rule: OutlierRule,
+    threshold: f64,
+    method: QuantileMethod,
+) -> Result<usize, Box<dyn Error>> {
+    let flags = flag_outliers(data, metrics, groups, rule, threshold, method);
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if extension.eq_ignore_ascii_case("json") {
+        let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(out, &flags)?;
+    } else {
+        let delimiter = if extension.eq_ignore_ascii_case("tsv") { b'\t' } else { b',' };
+        let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_path(path)?;
+        for flag in &flags {
+            writer.serialize(flag)?;
+        }
+        writer.flush()?;
+    }
+    Ok(flags.len())
+}
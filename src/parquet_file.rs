@@ -0,0 +1,66 @@
+//! Parquet tables, such as statistics exported from a data lake, read with
+//! the `parquet` feature. Their columns are named and recognised as those of
+//! a CSV, and their rows are read one at a time, as those of a CSV are.
+
+use crate::SendError;
+use csv::StringRecord;
+use std::path::Path;
+
+/// Rows of a table, read as they are needed.
+pub type Rows = Box<dyn Iterator<Item = Result<StringRecord, csv::Error>>>;
+
+/// Whether `path` is a Parquet file, which is never compressed as a whole.
+pub fn is_parquet(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("parquet") || ext.eq_ignore_ascii_case("parq"))
+}
+
+/// The header and rows of the Parquet file `path`, each row positioned at
+/// its number.
+#[cfg(feature = "parquet")]
+pub fn read_parquet(path: &Path) -> Result<(StringRecord, Rows), SendError> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use crate::{Invalid, InvalidInput};
+    use parquet::record::reader::RowIter;
+    use std::io;
+
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let reader = SerializedFileReader::new(file)
+        .map_err(|e| InvalidInput::new(Invalid::UnparseableRow, format!("{}: {}", path.display(), e)))?;
+    let schema = reader.metadata().file_metadata().schema_descr().root_schema();
+    let headers: StringRecord = schema.get_fields().iter().map(|field| field.name()).collect();
+    let rows = RowIter::from_file_into(Box::new(reader)).zip(1..).map(move |(row, n)| {
+        // Read as rows that are not those of the table, as a CSV's are
+        let row = row.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut record = row
+            .get_column_iter()
+            .map(|(name, value)| {
+                field(value).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("row {n}: {name:?} is not a value")))
+            })
+            .collect::<Result<StringRecord, _>>()?;
+        let mut position = csv::Position::new();
+        position.set_line(n);
+        record.set_position(Some(position));
+        Ok(record)
+    });
+    Ok((headers, Box::new(rows)))
+}
+
+#[cfg(not(feature = "parquet"))]
+pub fn read_parquet(path: &Path) -> Result<(StringRecord, Rows), SendError> {
+    Err(format!("{}: Parquet files are only read when built with the parquet feature", path.display()).into())
+}
+
+/// The value of a field as that of a CSV: empty when null, `None` for groups,
+/// lists and maps.
+#[cfg(feature = "parquet")]
+fn field(value: &parquet::record::Field) -> Option<String> {
+    use parquet::record::Field;
+
+    match value {
+        Field::Null => Some(String::new()),
+        Field::Str(text) => Some(text.clone()),
+        Field::Bytes(bytes) => Some(String::from_utf8_lossy(bytes.data()).into_owned()),
+        Field::Group(_) | Field::ListInternal(_) | Field::MapInternal(_) => None,
+        value => Some(value.to_string()),
+    }
+}
@@ -0,0 +1,174 @@
+//! Principal component analysis of standardized metrics.
+
+use crate::{GenomeStats, Metric};
+
+/// First two principal components of the samples that have every metric.
+#[derive(Debug, Clone)]
+pub struct Pca {
+    /// Indices in the data of the samples analysed, in their order.
+    pub rows: Vec<usize>,
+    /// Coordinates of each sample on the first and second components.
+    pub scores: Vec<(f64, f64)>,
+    /// Share of the variance explained by the first and second components.
+    pub explained: (f64, f64),
+    /// Weight of each metric on the first and second components.
+    pub loadings: Vec<(f64, f64)>,
+}
+
+/// Indices of the samples of `data` that have every one of `metrics`, and
+/// their z-scores, so that each metric weighs the same whatever its unit.
+pub fn standardize(data: &[GenomeStats], metrics: &[Metric]) -> (Vec<usize>, Vec<Vec<f64>>) {
+    let rows: Vec<usize> = (0..data.len())
+        .filter(|&i| metrics.iter().all(|metric| metric.value(&data[i]).is_finite()))
+        .collect();
+    let n = rows.len() as f64;
+    let mut z: Vec<Vec<f64>> = rows.iter().map(|&i| metrics.iter().map(|m| m.value(&data[i])).collect()).collect();
+    for j in 0..metrics.len() {
+        let mean = z.iter().map(|row| row[j]).sum::<f64>() / n;
+        let sd = (z.iter().map(|row| (row[j] - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        for row in &mut z {
+            // A constant metric tells the samples apart on no axis
+            row[j] = if sd > 0.0 { (row[j] - mean) / sd } else { 0.0 };
+        }
+    }
+    (rows, z)
+}
+
+/// Standardizes `metrics` over the samples of `data` that have all of them
+/// and projects them on the two axes of largest variance.
+pub fn analyse(data: &[GenomeStats], metrics: &[Metric]) -> Result<Pca, String> {
+    if metrics.len() < 2 {
+        return Err("PCA needs at least two metrics".to_string());
+    }
+    let (rows, z) = standardize(data, metrics);
+    if rows.len() < 3 {
+        return Err(format!("PCA needs at least three samples with every metric, got {}", rows.len()));
+    }
+    let n = rows.len() as f64;
+    let p = metrics.len();
+    let mut covariance = vec![vec![0.0; p]; p];
+    for row in &z {
+        for a in 0..p {
+            for b in 0..p {
+                covariance[a][b] += row[a] * row[b] / (n - 1.0);
+            }
+        }
+    }
+
+    let (values, vectors) = eigen(covariance);
+    let mut order: Vec<usize> = (0..p).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+    let total: f64 = values.iter().filter(|v| **v > 0.0).sum();
+    let component = |k: usize| {
+        let mut vector: Vec<f64> = (0..p).map(|j| vectors[j][order[k]]).collect();
+        // The sign of an eigenvector is arbitrary: point the heaviest weight
+        // up so that runs agree
+        let heaviest = vector.iter().copied().fold(0.0, |a: f64, b| if b.abs() > a.abs() { b } else { a });
+        if heaviest < 0.0 {
+            vector.iter_mut().for_each(|v| *v = -*v);
+        }
+        vector
+    };
+    let (first, second) = (component(0), component(1));
+    let project = |row: &[f64], vector: &[f64]| row.iter().zip(vector).map(|(x, w)| x * w).sum::<f64>();
+    let share = |k: usize| if total > 0.0 { values[order[k]].max(0.0) / total } else { 0.0 };
+    Ok(Pca {
+        scores: z.iter().map(|row| (project(row, &first), project(row, &second))).collect(),
+        explained: (share(0), share(1)),
+        loadings: first.into_iter().zip(second).collect(),
+        rows,
+    })
+}
+
+/// Eigenvalues and eigenvectors, as columns, of the symmetric `matrix` by
+/// cyclic Jacobi rotations.
+fn eigen(mut matrix: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let p = matrix.len();
+    let mut vectors: Vec<Vec<f64>> = (0..p).map(|i| (0..p).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    for _ in 0..100 {
+        let off: f64 = (0..p)
+            .flat_map(|i| (0..p).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| matrix[i][j].powi(2))
+            .sum();
+        if off < 1e-22 {
+            break;
+        }
+        for a in 0..p {
+            for b in a + 1..p {
+                if matrix[a][b].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (matrix[b][b] - matrix[a][a]) / (2.0 * matrix[a][b]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let (c, s) = (1.0 / (t * t + 1.0).sqrt(), t / (t * t + 1.0).sqrt());
+                // Rotates columns a and b of the matrix and of the vectors,
+                // then rows a and b of the matrix
+                for row in matrix.iter_mut().chain(vectors.iter_mut()) {
+                    let (ka, kb) = (row[a], row[b]);
+                    row[a] = c * ka - s * kb;
+                    row[b] = s * ka + c * kb;
+                }
+                let (row_a, row_b) = (matrix[a].clone(), matrix[b].clone());
+                matrix[a] = row_a.iter().zip(&row_b).map(|(ak, bk)| c * ak - s * bk).collect();
+                matrix[b] = row_a.iter().zip(&row_b).map(|(ak, bk)| s * ak + c * bk).collect();
+            }
+        }
+    }
+    ((0..p).map(|i| matrix[i][i]).collect(), vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dataset, LoadOptions};
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual}, not {expected}");
+    }
+
+    #[test]
+    fn eigen_of_a_fixed_matrix() {
+        let (values, vectors) = eigen(vec![vec![4.0, 1.0, 0.0], vec![1.0, 4.0, 0.0], vec![0.0, 0.0, 2.0]]);
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        let expected = [(5.0, [h, h, 0.0]), (3.0, [h, -h, 0.0]), (2.0, [0.0, 0.0, 1.0])];
+        for (value, vector) in expected {
+            let k = values.iter().position(|v| (v - value).abs() < 1e-9).unwrap_or_else(|| panic!("{values:?}"));
+            // Up to its sign
+            let sign = if vectors[0][k] * vector[0] + vectors[1][k] * vector[1] + vectors[2][k] * vector[2] < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            for (j, &weight) in vector.iter().enumerate() {
+                assert_close(sign * vectors[j][k], weight);
+            }
+        }
+    }
+
+    #[test]
+    fn analyse_correlated_metrics() {
+        let table = "filename,assembly_length,number_of_sequences,N50,GC_percentage,N_percentage\n\
+                     a,100,1,50,40,0\nb,200,2,100,41,0\nc,400,3,200,42,0\nd,800,4,400,43,0\n";
+        let dataset = Dataset::from_table("table.csv", table.as_bytes(), &LoadOptions::default()).unwrap();
+        let pca = analyse(dataset.records(), &[Metric::AssemblyLength, Metric::N50]).unwrap();
+        // N50 is half the assembly length, so the first component explains
+        // everything and weighs both the same
+        assert_eq!(pca.rows, [0, 1, 2, 3]);
+        assert_close(pca.explained.0, 1.0);
+        assert_close(pca.explained.1, 0.0);
+        assert_close(pca.loadings[0].0, std::f64::consts::FRAC_1_SQRT_2);
+        assert_close(pca.loadings[1].0, std::f64::consts::FRAC_1_SQRT_2);
+        let (_, z) = standardize(dataset.records(), &[Metric::AssemblyLength]);
+        for (&(first, second), z) in pca.scores.iter().zip(&z) {
+            assert_close(first, z[0] * std::f64::consts::SQRT_2);
+            assert_close(second, 0.0);
+        }
+    }
+
+    #[test]
+    fn analyse_needs_three_samples() {
+        let table = "filename,assembly_length,number_of_sequences,N50,GC_percentage,N_percentage\na,100,1,50,40,0\nb,200,2,100,41,0\n";
+        let dataset = Dataset::from_table("table.csv", table.as_bytes(), &LoadOptions::default()).unwrap();
+        assert!(analyse(dataset.records(), &[Metric::AssemblyLength, Metric::N50]).is_err());
+    }
+}
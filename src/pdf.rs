@@ -0,0 +1,443 @@
+//! A minimal vector PDF backend for plotters.
+//!
+//! Every page is drawn through its own [`PdfBackend`] and collected in a
+//! [`PdfDocument`], which writes a single multi-page file. Text is set in the
+//! standard PDF base fonts, so nothing has to be embedded.
+
+use plotters_backend::text_anchor::{HPos, VPos};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontFamily, FontStyle,
+};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Control point distance for approximating a quarter circle with a cubic Bézier.
+const KAPPA: f64 = 0.552_284_75;
+
+struct Page {
+    size: (u32, u32),
+    content: String,
+}
+
+/// A PDF file under construction.
+#[derive(Default)]
+pub struct PdfDocument {
+    pages: Vec<Page>,
+    fonts: Vec<&'static str>,
+    alphas: Vec<u8>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new page of the given size in points. The page is added to the
+    /// document when the backend is presented.
+    pub fn page(&mut self, size: (u32, u32)) -> PdfBackend<'_> {
+        PdfBackend {
+            doc: self,
+            size,
+            content: String::new(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+        out.write_all(&self.to_bytes())?;
+        out.flush()
+    }
+
+    fn font_resource(&mut self, base_font: &'static str) -> usize {
+        match self.fonts.iter().position(|&f| f == base_font) {
+            Some(i) => i,
+            None => {
+                self.fonts.push(base_font);
+                self.fonts.len() - 1
+            }
+        }
+    }
+
+    fn alpha_resource(&mut self, alpha: f64) -> usize {
+        let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match self.alphas.iter().position(|&a| a == alpha) {
+            Some(i) => i,
+            None => {
+                self.alphas.push(alpha);
+                self.alphas.len() - 1
+            }
+        }
+    }
+
+    /// The document as the contents of a PDF file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Object layout: catalog, page tree, shared resources, fonts, graphic
+        // states, then a page object and content stream per page.
+        let fonts_start = 4;
+        let alphas_start = fonts_start + self.fonts.len();
+        let pages_start = alphas_start + self.alphas.len();
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        let kids: Vec<String> = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", pages_start + 2 * i))
+            .collect();
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+        );
+
+        let mut resources = String::from("<< /Font <<");
+        for i in 0..self.fonts.len() {
+            let _ = write!(resources, " /F{} {} 0 R", i, fonts_start + i);
+        }
+        resources.push_str(" >> /ExtGState <<");
+        for i in 0..self.alphas.len() {
+            let _ = write!(resources, " /GS{} {} 0 R", i, alphas_start + i);
+        }
+        resources.push_str(" >> >>");
+        objects.push(resources.into_bytes());
+
+        for font in &self.fonts {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                    font
+                )
+                .into_bytes(),
+            );
+        }
+        for &alpha in &self.alphas {
+            let alpha = alpha as f64 / 255.0;
+            objects.push(
+                format!("<< /Type /ExtGState /CA {:.4} /ca {:.4} >>", alpha, alpha).into_bytes(),
+            );
+        }
+
+        for (i, page) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources 3 0 R /Contents {} 0 R >>",
+                    page.size.0,
+                    page.size.1,
+                    pages_start + 2 * i + 1
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(page.content.as_bytes());
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+/// Drawing backend for a single page of a [`PdfDocument`].
+pub struct PdfBackend<'a> {
+    doc: &'a mut PdfDocument,
+    size: (u32, u32),
+    content: String,
+}
+
+impl PdfBackend<'_> {
+    /// Converts a backend coordinate (origin top-left) into PDF user space
+    /// (origin bottom-left).
+    fn point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (x, self.size.1 as f64 - y)
+    }
+
+    fn set_color(&mut self, color: BackendColor, fill: bool) {
+        let gs = self.doc.alpha_resource(color.alpha);
+        let (r, g, b) = color.rgb;
+        let op = if fill { "rg" } else { "RG" };
+        let _ = writeln!(
+            self.content,
+            "/GS{} gs {:.3} {:.3} {:.3} {}",
+            gs,
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0,
+            op
+        );
+    }
+
+    fn set_stroke<S: BackendStyle>(&mut self, style: &S) {
+        self.set_color(style.color(), false);
+        let _ = writeln!(self.content, "{} w", style.stroke_width());
+    }
+
+    fn move_to(&mut self, p: (f64, f64)) {
+        let (x, y) = self.point(p);
+        let _ = writeln!(self.content, "{:.2} {:.2} m", x, y);
+    }
+
+    fn line_to(&mut self, p: (f64, f64)) {
+        let (x, y) = self.point(p);
+        let _ = writeln!(self.content, "{:.2} {:.2} l", x, y);
+    }
+}
+
+fn to_f64((x, y): BackendCoord) -> (f64, f64) {
+    (x as f64, y as f64)
+}
+
+fn base_font(family: FontFamily, style: FontStyle) -> &'static str {
+    let slanted = matches!(style, FontStyle::Italic | FontStyle::Oblique);
+    let bold = matches!(style, FontStyle::Bold);
+    match family {
+        FontFamily::Serif => match (bold, slanted) {
+            (true, _) => "Times-Bold",
+            (_, true) => "Times-Italic",
+            _ => "Times-Roman",
+        },
+        FontFamily::Monospace => match (bold, slanted) {
+            (true, _) => "Courier-Bold",
+            (_, true) => "Courier-Oblique",
+            _ => "Courier",
+        },
+        _ => match (bold, slanted) {
+            (true, _) => "Helvetica-Bold",
+            (_, true) => "Helvetica-Oblique",
+            _ => "Helvetica",
+        },
+    }
+}
+
+/// Encodes text as a PDF literal string in WinAnsi (Latin-1 for our purposes).
+fn pdf_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('(');
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            _ if (c as u32) < 256 => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+            _ => out.push('?'),
+        }
+    }
+    out.push(')');
+    out
+}
+
+impl DrawingBackend for PdfBackend<'_> {
+    type ErrorType = io::Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.content.is_empty() {
+            self.doc.pages.push(Page {
+                size: self.size,
+                content: std::mem::take(&mut self.content),
+            });
+        }
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.draw_rect(point, (point.0 + 1, point.1 + 1), &color, true)
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.draw_path([from, to], style)
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if style.color().alpha == 0.0 {
+            return Ok(());
+        }
+        let (x, y) = self.point((upper_left.0 as f64, bottom_right.1 as f64));
+        let w = (bottom_right.0 - upper_left.0) as f64;
+        let h = (bottom_right.1 - upper_left.1) as f64;
+        if fill {
+            self.set_color(style.color(), true);
+            let _ = writeln!(self.content, "{:.2} {:.2} {:.2} {:.2} re f", x, y, w, h);
+        } else {
+            self.set_stroke(style);
+            let _ = writeln!(self.content, "{:.2} {:.2} {:.2} {:.2} re S", x, y, w, h);
+        }
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if style.color().alpha == 0.0 {
+            return Ok(());
+        }
+        self.set_stroke(style);
+        let mut points = path.into_iter().map(to_f64);
+        if let Some(first) = points.next() {
+            self.move_to(first);
+            for p in points {
+                self.line_to(p);
+            }
+            self.content.push_str("S\n");
+        }
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if style.color().alpha == 0.0 {
+            return Ok(());
+        }
+        if fill {
+            self.set_color(style.color(), true);
+        } else {
+            self.set_stroke(style);
+        }
+        let (cx, cy) = self.point(to_f64(center));
+        let r = radius as f64;
+        let k = r * KAPPA;
+        let _ = writeln!(
+            self.content,
+            "{:.2} {:.2} m\n\
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c\n\
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c\n\
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c\n\
+             {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c\n\
+             {}",
+            cx + r, cy,
+            cx + r, cy + k, cx + k, cy + r, cx, cy + r,
+            cx - k, cy + r, cx - r, cy + k, cx - r, cy,
+            cx - r, cy - k, cx - k, cy - r, cx, cy - r,
+            cx + k, cy - r, cx + r, cy - k, cx + r, cy,
+            if fill { "f" } else { "S" }
+        );
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if style.color().alpha == 0.0 {
+            return Ok(());
+        }
+        self.set_color(style.color(), true);
+        let mut points = vert.into_iter().map(to_f64);
+        if let Some(first) = points.next() {
+            self.move_to(first);
+            for p in points {
+                self.line_to(p);
+            }
+            self.content.push_str("h f\n");
+        }
+        Ok(())
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        // Same scaling the SVG backend applies between plotters' font size and
+        // the nominal font size of the document.
+        let font_size = style.size() / 1.24;
+        let (width, _) = self.estimate_text_size(text, style)?;
+        let dx = match style.anchor().h_pos {
+            HPos::Left => 0.0,
+            HPos::Center => -(width as f64) / 2.0,
+            HPos::Right => -(width as f64),
+        };
+        // Offset from the anchor to the baseline.
+        let dy = match style.anchor().v_pos {
+            VPos::Top => 0.76 * font_size,
+            VPos::Center => 0.35 * font_size,
+            VPos::Bottom => -0.22 * font_size,
+        };
+
+        // Rotations are expressed in screen space, where y grows downwards.
+        let transform = style.transform();
+        let (ox, oy) = transform.transform(dx.round() as i32, dy.round() as i32);
+        let (ax, ay) = transform.transform(1, 0);
+        let (ux, uy) = transform.transform(0, -1);
+        let (x, y) = self.point(((pos.0 + ox) as f64, (pos.1 + oy) as f64));
+
+        let font = self.doc.font_resource(base_font(style.family(), style.style()));
+        self.set_color(color, true);
+        let _ = writeln!(
+            self.content,
+            "BT /F{} {:.2} Tf {} {} {} {} {:.2} {:.2} Tm {} Tj ET",
+            font,
+            font_size,
+            ax,
+            -ay,
+            ux,
+            -uy,
+            x,
+            y,
+            pdf_string(text)
+        );
+        Ok(())
+    }
+}
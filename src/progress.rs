@@ -0,0 +1,86 @@
+//! Progress bars of long loads on stderr: one over every input file and one
+//! per file being read, advanced as its bytes are read. Nothing is drawn
+//! when stderr is not a terminal.
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+thread_local! {
+    /// Bar of the file read on this thread, which [`track`] advances.
+    static CURRENT: RefCell<Option<ProgressBar>> = const { RefCell::new(None) };
+}
+
+/// Bars of one load of input files.
+pub(crate) struct Progress {
+    bars: MultiProgress,
+    /// Bar over every file, when there are several.
+    overall: Option<ProgressBar>,
+}
+
+impl Progress {
+    /// Bars of a load of `files` files, drawn only when `shown`.
+    pub(crate) fn new(files: usize, shown: bool) -> Self {
+        let target = if shown { ProgressDrawTarget::stderr() } else { ProgressDrawTarget::hidden() };
+        let bars = MultiProgress::with_draw_target(target);
+        let overall = (files > 1).then(|| {
+            let style = ProgressStyle::with_template("[{elapsed_precise}] {bar:40} {pos}/{len} files, {eta} left");
+            bars.add(ProgressBar::new(files as u64).with_style(style.expect("valid template")))
+        });
+        Progress { bars, overall }
+    }
+
+    /// Runs `read` with a bar of its own for `path`, advanced by the reads of
+    /// files opened on this thread meanwhile, which counts towards the
+    /// overall bar when done.
+    pub(crate) fn file<T>(&self, path: &Path, read: impl FnOnce() -> T) -> T {
+        let size = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+        let style = ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} {bytes_per_sec}, {eta} left  {msg}");
+        let bar = ProgressBar::new(size).with_style(style.expect("valid template"));
+        let bar = match &self.overall {
+            Some(overall) => self.bars.insert_before(overall, bar),
+            None => self.bars.add(bar),
+        };
+        bar.set_message(path.file_name().unwrap_or_default().to_string_lossy().into_owned());
+        CURRENT.with(|current| *current.borrow_mut() = Some(bar.clone()));
+        let result = read();
+        CURRENT.with(|current| current.borrow_mut().take());
+        bar.finish_and_clear();
+        self.bars.remove(&bar);
+        if let Some(overall) = &self.overall {
+            overall.inc(1);
+        }
+        result
+    }
+
+    /// Clears the bars off the screen.
+    pub(crate) fn finish(&self) {
+        if let Some(overall) = &self.overall {
+            overall.finish_and_clear();
+        }
+    }
+}
+
+/// `file`, advancing the bar of the file read on this thread as it is read,
+/// from the start again when a reader opens it twice.
+pub(crate) fn track(file: File) -> Box<dyn Read> {
+    match CURRENT.with(|current| current.borrow().clone()) {
+        Some(bar) => {
+            bar.set_position(0);
+            Box::new(bar.wrap_read(file))
+        }
+        None => Box::new(file),
+    }
+}
+
+/// Moves the bar of the file read on this thread to `position` bytes, for
+/// files scanned without [`track`].
+pub(crate) fn advance(position: u64) {
+    CURRENT.with(|current| {
+        if let Some(bar) = current.borrow().as_ref() {
+            bar.set_position(position);
+        }
+    });
+}
@@ -0,0 +1,171 @@
+//! Python bindings, built with the `python` feature into a module importable
+//! as `count_fasta_plots`, for pipelines that would rather call the plotting
+//! than run the binary:
+//!
+//! ```python
+//! import count_fasta_plots
+//!
+//! stats = count_fasta_plots.compute_stats(["assemblies/*.fa.gz"])
+//! count_fasta_plots.render(["stats.csv"], "n50.svg", metrics=["n50"], group_by="platform")
+//! ```
+//!
+//! Inputs are read and figures drawn without holding the GIL, on the threads
+//! of the command line. Warnings are logged to stderr; set `RUST_LOG=info` to
+//! also see what is read.
+
+use crate::{fitted_height, render_boxplots, Dataset, LoadOptions, Metric, OutputFormat, PlotConfig, PlotType, Style, Theme};
+use clap::ValueEnum;
+use pyo3::conversion::FromPyObjectOwned;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::path::PathBuf;
+use tracing_subscriber::EnvFilter;
+
+/// Keyword arguments of `render`, named as the options of the command line.
+const RENDER_OPTIONS: &[&str] = &[
+    "plot_type", "metrics", "log", "group_by", "color_by", "theme", "config", "title", "width", "height", "grid",
+    "points", "seed", "mean", "notch",
+];
+
+/// Keyword arguments of a call.
+struct Options<'a, 'py>(Option<&'a Bound<'py, PyDict>>);
+
+impl<'py> Options<'_, 'py> {
+    /// Raises a `TypeError` for any argument but `known`, as Python does.
+    fn check(&self, function: &str, known: &[&str]) -> PyResult<()> {
+        for name in self.0.iter().flat_map(|options| options.keys()) {
+            let name: String = name.extract()?;
+            if !known.contains(&name.as_str()) {
+                return Err(PyTypeError::new_err(format!("{function}() got an unexpected keyword argument {name:?}")));
+            }
+        }
+        Ok(())
+    }
+
+    /// The argument `name`, `None` when not given or given as `None`.
+    fn get<T: FromPyObjectOwned<'py>>(&self, name: &str) -> PyResult<Option<T>> {
+        match self.0.map(|options| options.get_item(name)).transpose()?.flatten() {
+            Some(value) if !value.is_none() => Ok(Some(value.extract().map_err(Into::into)?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Reads `inputs` like the command line does.
+fn load(py: Python<'_>, inputs: Vec<PathBuf>, options: LoadOptions) -> PyResult<Dataset> {
+    let dataset = py.detach(|| Dataset::load(&inputs, &options).map_err(|e| e.to_string()));
+    let dataset = dataset.map_err(PyRuntimeError::new_err)?;
+    if dataset.is_empty() {
+        return Err(PyValueError::new_err("the input contains no records"));
+    }
+    Ok(dataset)
+}
+
+/// Statistics of CSV files, FASTA files, directories of either and glob
+/// patterns, one dict per assembly of its filename, source, group and every
+/// metric but those of reads, None for those it lacks.
+#[pyfunction]
+#[pyo3(signature = (inputs, *, group_by=None))]
+fn compute_stats<'py>(
+    py: Python<'py>,
+    inputs: Vec<PathBuf>,
+    group_by: Option<String>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let dataset = load(py, inputs, LoadOptions { group_by, ..LoadOptions::default() })?;
+    let metrics = Metric::value_variants().iter().filter(|metric| !matches!(metric, Metric::ReadLength | Metric::MeanQuality));
+    dataset
+        .records()
+        .iter()
+        .map(|record| {
+            let stats = PyDict::new(py);
+            stats.set_item("filename", &record.filename)?;
+            stats.set_item("source", &record.source)?;
+            stats.set_item("group", &record.group)?;
+            for metric in metrics.clone() {
+                let value = metric.value(record);
+                stats.set_item(metric.to_string(), (!value.is_nan()).then_some(value))?;
+            }
+            Ok(stats)
+        })
+        .collect()
+}
+
+/// Writes the figure of the inputs to output, as PNG, SVG, PDF, HTML or Vega
+/// by its extension, and returns the paths written. The options are named as
+/// on the command line: plot_type, metrics, log, group_by, color_by, theme,
+/// config (a style file), title, width, height, grid (a (rows, columns)
+/// tuple), points, seed, mean and notch; the height fits the panels by default.
+#[pyfunction]
+#[pyo3(signature = (inputs, output, **options))]
+fn render(
+    py: Python<'_>,
+    inputs: Vec<PathBuf>,
+    output: PathBuf,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<PathBuf>> {
+    let options = Options(options);
+    options.check("render", RENDER_OPTIONS)?;
+    let load_options = LoadOptions {
+        group_by: options.get("group_by")?,
+        color_by: options.get("color_by")?,
+        ..LoadOptions::default()
+    };
+    let dataset = load(py, inputs, load_options)?;
+
+    let theme = match options.get::<String>("theme")? {
+        Some(theme) => Theme::from_str(&theme, true).map_err(PyValueError::new_err)?,
+        None => Theme::default(),
+    };
+    let mut style = match options.get::<PathBuf>("config")? {
+        Some(path) => Style::from_file(&path, theme.style()).map_err(|e| PyValueError::new_err(e.to_string()))?,
+        None => theme.style(),
+    };
+    if let Some(title) = options.get("title")? {
+        style.title = Some(title);
+    }
+    let defaults = PlotConfig::default();
+    let plot_type = match options.get::<String>("plot_type")? {
+        Some(plot_type) => PlotType::from_str(&plot_type, true).map_err(PyValueError::new_err)?,
+        None => defaults.plot_type,
+    };
+    let metrics: Vec<Metric> = match options.get::<Vec<String>>("metrics")? {
+        Some(metrics) => metrics.into_iter().map(Metric::from).collect(),
+        None => defaults.metrics.clone(),
+    };
+    let grid = options.get("grid")?;
+    let panels = metrics.iter().map(|metric| style.span(metric).0).sum();
+    let format = OutputFormat::from_path(&output);
+    let config = PlotConfig {
+        output,
+        width: options.get("width")?.unwrap_or(defaults.width),
+        height: options.get("height")?.unwrap_or_else(|| fitted_height(plot_type, format, false, grid, panels)),
+        plot_type,
+        metrics,
+        log: options.get::<Vec<String>>("log")?.unwrap_or_default().into_iter().map(Metric::from).collect(),
+        grid,
+        points: options.get("points")?.unwrap_or_default(),
+        seed: options.get("seed")?.unwrap_or_default(),
+        mean: options.get("mean")?.unwrap_or_default(),
+        notch: options.get("notch")?.unwrap_or_default(),
+        style,
+        ..defaults
+    };
+    py.detach(|| render_boxplots(&dataset, &config).map_err(|e| e.to_string())).map_err(PyRuntimeError::new_err)
+}
+
+#[pymodule]
+fn count_fasta_plots(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Warnings, such as those of skipped rows, on stderr as from the binary,
+    // unless the host already collects the log or RUST_LOG asks otherwise
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .with_target(false)
+        .try_init();
+    module.add_function(wrap_pyfunction!(compute_stats, module)?)?;
+    module.add_function(wrap_pyfunction!(render, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! QUAST reports, whose `report.tsv` has one row per statistic and one column
+//! per assembly, and whose N content is given per 100 kbp.
+
+use csv::StringRecord;
+
+/// Row of a QUAST report that holds the N content.
+const N_PER_100_KBP: &str = "# N's per 100 kbp";
+
+/// Whether a table whose first line is `headers` may be a QUAST `report.tsv`,
+/// which its first column then tells.
+pub fn may_be_report(headers: &StringRecord) -> bool {
+    headers.get(0) == Some("Assembly")
+}
+
+/// Whether a table whose first line is `headers` and whose first column is
+/// `labels` is laid out like a QUAST `report.tsv`.
+pub fn is_report<'a>(headers: &StringRecord, mut labels: impl Iterator<Item = &'a str>) -> bool {
+    may_be_report(headers) && labels.any(|label| label == "Total length")
+}
+
+/// Header and rows of the usual layout, one row per assembly, from the
+/// `rows` of a QUAST report with `headers` naming the assemblies. The
+/// position of each row is the column it was read from, and the `-` QUAST
+/// writes for undefined values is left empty.
+pub fn transpose(headers: &StringRecord, rows: &[StringRecord]) -> (StringRecord, Vec<StringRecord>) {
+    let mut transposed_headers = StringRecord::from(vec!["Assembly"]);
+    for row in rows {
+        transposed_headers.push_field(row.get(0).unwrap_or_default());
+    }
+    let records = (1..headers.len())
+        .map(|column| {
+            let mut record = StringRecord::from(vec![&headers[column]]);
+            for row in rows {
+                let field = row.get(column).unwrap_or_default();
+                record.push_field(if field == "-" { "" } else { field });
+            }
+            let mut position = csv::Position::new();
+            position.set_line(column as u64 + 1);
+            record.set_position(Some(position));
+            record
+        })
+        .collect();
+    (transposed_headers, records)
+}
+
+/// Index of the N content per 100 kbp when `columns` lack an N percentage.
+pub fn n_per_100_kbp(columns: &StringRecord) -> Option<usize> {
+    if columns.iter().any(|c| c == "N_percentage") {
+        return None;
+    }
+    columns.iter().position(|c| c == N_PER_100_KBP)
+}
+
+/// Percentage of Ns given `field`, a count per 100 kbp; empty when it is.
+pub fn n_percentage(field: &str) -> String {
+    match field.parse::<f64>() {
+        Ok(per_100_kbp) => (per_100_kbp / 1000.0).to_string(),
+        Err(_) => field.to_string(),
+    }
+}
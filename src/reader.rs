@@ -0,0 +1,165 @@
+//! Opening input files, transparently decompressing gzip and bgzip.
+
+use crate::progress;
+use flate2::bufread::MultiGzDecoder;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Extensions that mark a compressed file and are ignored when guessing the
+/// format from the file name.
+const COMPRESSED_EXTENSIONS: &[&str] = &["gz", "bgz", "gzip"];
+
+/// Whether `path` is `-`, which stands for the standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Opens `path`, or the standard input for `-`, for buffered reading. Gzip
+/// files are detected from their magic bytes rather than their name; bgzip
+/// files are multi-member gzip streams, so the same decoder handles both.
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let file: Box<dyn Read> = if is_stdin(path) {
+        Box::new(io::stdin())
+    } else {
+        let file = File::open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        progress::track(file)
+    };
+    let mut reader = BufReader::new(file);
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Reads `bytes`, such as an uploaded file, decompressing them if gzipped.
+pub fn from_bytes(bytes: &[u8]) -> Box<dyn BufRead + '_> {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        Box::new(BufReader::new(MultiGzDecoder::new(bytes)))
+    } else {
+        Box::new(bytes)
+    }
+}
+
+/// Maps `path` into memory when it is an uncompressed regular file of at
+/// least `min_len` bytes, so that it can be scanned without being copied;
+/// `None` for the standard input, compressed and smaller files.
+pub fn map(path: &Path, min_len: u64) -> io::Result<Option<Mmap>> {
+    if is_stdin(path) {
+        return Ok(None);
+    }
+    let file = File::open(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() || metadata.len() < min_len {
+        return Ok(None);
+    }
+    // SAFETY: the map is only read, and inputs are not expected to be
+    // truncated while they are plotted
+    let map = unsafe { Mmap::map(&file)? };
+    if map.starts_with(&GZIP_MAGIC) {
+        return Ok(None);
+    }
+    #[cfg(unix)]
+    map.advise(memmap2::Advice::Sequential)?;
+    Ok(Some(map))
+}
+
+/// Extensions of the statistics tables picked up from input directories.
+const TABLE_EXTENSIONS: &[&str] = &["csv", "tsv", "tab"];
+
+/// Whether `path` looks like a (possibly compressed) CSV or TSV file judging
+/// by its extension.
+pub fn is_table(path: &Path) -> bool {
+    format_extension(path)
+        .is_some_and(|ext| TABLE_EXTENSIONS.iter().any(|t| ext.eq_ignore_ascii_case(t)))
+}
+
+/// Delimiters recognised when sniffing a CSV, in order of preference on a tie.
+const DELIMITERS: &[u8] = b";,\t";
+
+/// Field delimiter implied by the extension of `path`: tab for `.tsv` and
+/// `.tab` files, nothing for `.csv`, which may use either `;` or `,`.
+pub fn extension_delimiter(path: &Path) -> Option<u8> {
+    let ext = format_extension(path)?;
+    (ext.eq_ignore_ascii_case("tsv") || ext.eq_ignore_ascii_case("tab")).then_some(b'\t')
+}
+
+/// Guesses the field delimiter of a CSV from its header line: whichever of
+/// `;`, `,` and tab occurs most often outside of quotes, `;` when none does.
+pub fn sniff_delimiter(reader: &mut dyn BufRead) -> io::Result<u8> {
+    let buffer = reader.fill_buf()?;
+    let header = buffer.split(|&b| b == b'\n').next().unwrap_or_default();
+    let mut counts = [0usize; DELIMITERS.len()];
+    let mut quoted = false;
+    for &byte in header {
+        if byte == b'"' {
+            quoted = !quoted;
+        } else if let (false, Some(i)) = (quoted, DELIMITERS.iter().position(|&d| d == byte)) {
+            counts[i] += 1;
+        }
+    }
+    let best = (0..DELIMITERS.len()).rev().max_by_key(|&i| counts[i]).unwrap_or(0);
+    Ok(if counts[best] == 0 { b';' } else { DELIMITERS[best] })
+}
+
+/// Extension of `path` after dropping a trailing compression suffix, so that
+/// `genome.fa.gz` yields `fa`.
+pub fn format_extension(path: &Path) -> Option<&str> {
+    let ext = path.extension()?.to_str()?;
+    if COMPRESSED_EXTENSIONS.iter().any(|c| ext.eq_ignore_ascii_case(c)) {
+        Path::new(path.file_stem()?).extension()?.to_str()
+    } else {
+        Some(ext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff(table: &str) -> char {
+        sniff_delimiter(&mut table.as_bytes()).unwrap() as char
+    }
+
+    #[test]
+    fn sniffs_the_header() {
+        assert_eq!(sniff("filename;assembly_length;N50\na;1;2\n"), ';');
+        assert_eq!(sniff("filename,assembly_length,N50\r\na,1,2\r\n"), ',');
+        assert_eq!(sniff("filename\tassembly_length\tN50"), '\t');
+        // The rows do not count, only the header
+        assert_eq!(sniff("filename,assembly_length\n\"a;b;c;d\",1\n"), ',');
+    }
+
+    #[test]
+    fn quoted_delimiters() {
+        assert_eq!(sniff("\"a,b\";c\n"), ';');
+        assert_eq!(sniff("\"GC, %\"\t\"N, %\"\tname\n"), '\t');
+        assert_eq!(sniff("\"a;b;c\",d,e\n"), ',');
+    }
+
+    #[test]
+    fn single_column_and_empty_headers() {
+        assert_eq!(sniff("filename\na.fa\n"), ';');
+        assert_eq!(sniff("\"a,b\"\n"), ';');
+        assert_eq!(sniff(""), ';');
+    }
+
+    #[test]
+    fn ties_go_to_the_first_delimiter() {
+        assert_eq!(sniff("a;b,c\n"), ';');
+        assert_eq!(sniff("a,b\tc\n"), ',');
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(format_extension(Path::new("genome.fa.gz")), Some("fa"));
+        assert_eq!(format_extension(Path::new("stats.TSV")), Some("TSV"));
+        assert_eq!(extension_delimiter(Path::new("stats.tsv.gz")), Some(b'\t'));
+        assert_eq!(extension_delimiter(Path::new("stats.csv")), None);
+    }
+}
@@ -0,0 +1,276 @@
+//! Self-contained HTML report with the figures as inline SVG, a sortable
+//! table of the samples, summary statistics and the outliers of every metric.
+
+use crate::html::Numbered;
+use crate::summary::summary_rows;
+use crate::{group_labels, svg_figure, Dataset, GenomeStats, Metric, PlotConfig, Summary};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+/// Sorts the table of samples by the column whose header is clicked, numbers
+/// by the value kept in `data-value` and everything else as text.
+const SORT_SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach((th, column) => {
+  th.addEventListener("click", () => {
+    const body = th.closest("table").tBodies[0];
+    const ascending = th.dataset.order !== "ascending";
+    th.closest("tr").querySelectorAll("th").forEach(other => delete other.dataset.order);
+    th.dataset.order = ascending ? "ascending" : "descending";
+    const key = row => {
+      const cell = row.cells[column];
+      return cell.dataset.value !== undefined ? parseFloat(cell.dataset.value) : cell.textContent;
+    };
+    const rows = Array.from(body.rows).sort((a, b) => {
+      const [x, y] = [key(a), key(b)];
+      const order = typeof x === "number" ? (isNaN(x) ? 1 : isNaN(y) ? -1 : x - y) : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach(row => body.appendChild(row));
+  });
+});
+"#;
+
+const STYLESHEET: &str = r#"
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; padding: 0 1em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; font-size: 0.9em; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.3em 0.6em; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+table.sortable th { cursor: pointer; user-select: none; }
+th[data-order="ascending"]::after { content: " \25B2"; }
+th[data-order="descending"]::after { content: " \25BC"; }
+td.outlier { background: #fde0dc; font-weight: bold; }
+figure { margin: 1em 0; }
+svg { max-width: 100%; height: auto; }
+footer { margin-top: 3em; color: #777; font-size: 0.85em; }
+"#;
+
+/// A value that lies outside the Tukey fences of its group.
+struct Outlier<'a> {
+    row: usize,
+    record: &'a GenomeStats,
+    metric: &'a Metric,
+    value: f64,
+    /// Fences of the group the value was compared with.
+    fences: (f64, f64),
+}
+
+/// Writes the report of `dataset` to `path`, drawing one figure per metric,
+/// or a single one for plot types that fill the figure, with `config`.
+pub fn write_report(path: &Path, dataset: &Dataset, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    let data = dataset.records();
+    if data.is_empty() {
+        return Err("the input contains no records".into());
+    }
+    let groups = group_labels(data);
+    let outliers = find_outliers(data, &config.metrics, config);
+    let title = config.style.title.as_deref().unwrap_or("Assembly QC report");
+
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(html, "<title>{}</title>\n<style>{STYLESHEET}</style>\n</head>\n<body>", escape(title))?;
+    writeln!(html, "<h1>{}</h1>", escape(title))?;
+    write!(html, "<p>{} samples", data.len())?;
+    if !groups.is_empty() {
+        write!(html, " in {} groups", groups.len())?;
+    }
+    writeln!(html, ", {} outlying values.</p>", outliers.len())?;
+
+    writeln!(html, "<h2>Figures</h2>")?;
+    for (figure, first_panel) in figures(config) {
+        writeln!(html, "<figure>{}</figure>", svg_figure(data, &figure, first_panel)?)?;
+    }
+
+    writeln!(html, "<h2>Summary statistics</h2>")?;
+    write_summary_table(&mut html, data, &groups, config)?;
+
+    writeln!(html, "<h2>Outliers</h2>")?;
+    write_outlier_table(&mut html, &outliers, !groups.is_empty(), config)?;
+
+    writeln!(html, "<h2>Samples</h2>\n<p>Click a column header to sort by it.</p>")?;
+    write_sample_table(&mut html, data, &outliers, !groups.is_empty(), config)?;
+
+    if let Some(footer) = &config.style.footer {
+        writeln!(html, "<footer>{}</footer>", escape(footer))?;
+    }
+    writeln!(html, "<script>{SORT_SCRIPT}</script>\n</body>\n</html>")?;
+
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    out.write_all(html.as_bytes())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Configurations of the figures of the report: one per metric, lettered in
+/// turn, unless the plot type draws every metric in one figure.
+fn figures(config: &PlotConfig) -> Vec<(PlotConfig, usize)> {
+    if config.plot_type.is_whole_figure() {
+        return vec![(config.clone(), 0)];
+    }
+    config
+        .metrics
+        .iter()
+        .enumerate()
+        .map(|(i, metric)| (PlotConfig { metrics: vec![metric.clone()], ..config.clone() }, i))
+        .collect()
+}
+
+/// Values outside the fences of their group, or of all the samples when
+/// ungrouped, metric by metric.
+fn find_outliers<'a>(data: &'a [GenomeStats], metrics: &'a [Metric], config: &PlotConfig) -> Vec<Outlier<'a>> {
+    let groups = group_labels(data);
+    let mut outliers = Vec::new();
+    for metric in metrics {
+        let mut subsets: Vec<Vec<Numbered>> = Vec::new();
+        if groups.is_empty() {
+            subsets.push(data.iter().enumerate().collect());
+        }
+        for &group in &groups {
+            subsets.push(data.iter().enumerate().filter(|(_, x)| x.group_name() == group).collect());
+        }
+        for subset in subsets {
+            let values: Vec<f64> = subset.iter().map(|(_, x)| metric.value(x)).filter(|v| v.is_finite()).collect();
+            if values.is_empty() {
+                continue;
+            }
+            let summary = Summary::compute(&values, config.quantile_method);
+            for (row, record) in subset {
+                let value = metric.value(record);
+                if value.is_finite() && summary.is_outlier(value) {
+                    outliers.push(Outlier { row, record, metric, value, fences: summary.fences() });
+                }
+            }
+        }
+    }
+    outliers.sort_by_key(|outlier| outlier.row);
+    outliers
+}
+
+fn write_summary_table(
+    html: &mut String,
+    data: &[GenomeStats],
+    groups: &[&str],
+    config: &PlotConfig,
+) -> std::fmt::Result {
+    write!(html, "<table>\n<thead><tr><th>Metric</th>")?;
+    if !groups.is_empty() {
+        write!(html, "<th>Group</th>")?;
+    }
+    writeln!(
+        html,
+        "<th>n</th><th>Min</th><th>Q1</th><th>Median</th><th>Q3</th><th>Max</th>\
+         <th>Mean</th><th>SD</th><th>Outliers</th></tr></thead>\n<tbody>"
+    )?;
+    for row in summary_rows(data, &config.metrics, groups, config.quantile_method, config.bootstrap) {
+        write!(html, "<tr><td>{}</td>", escape(&config.style.title(row.metric)))?;
+        if let Some(group) = row.group {
+            write!(html, "<td>{}</td>", escape(group))?;
+        }
+        write!(html, "<td>{}</td>", row.count)?;
+        for value in [row.min, row.q1, row.median, row.q3, row.max, row.mean, row.stddev] {
+            write!(html, "<td>{}</td>", number(value))?;
+        }
+        writeln!(html, "<td>{}</td></tr>", row.outliers)?;
+    }
+    writeln!(html, "</tbody>\n</table>")
+}
+
+fn write_outlier_table(
+    html: &mut String,
+    outliers: &[Outlier],
+    grouped: bool,
+    config: &PlotConfig,
+) -> std::fmt::Result {
+    if outliers.is_empty() {
+        return writeln!(html, "<p>No value lies beyond 1.5 IQR of the quartiles.</p>");
+    }
+    writeln!(
+        html,
+        "<p>Values beyond 1.5 IQR of the quartiles of their {}.</p>",
+        if grouped { "group" } else { "metric" }
+    )?;
+    write!(html, "<table>\n<thead><tr><th>Sample</th>")?;
+    if grouped {
+        write!(html, "<th>Group</th>")?;
+    }
+    writeln!(html, "<th>Metric</th><th>Value</th><th>Expected range</th></tr></thead>\n<tbody>")?;
+    for outlier in outliers {
+        write!(html, "<tr><td>{}</td>", escape(&outlier.record.name(outlier.row)))?;
+        if grouped {
+            write!(html, "<td>{}</td>", escape(outlier.record.group_name()))?;
+        }
+        let (low, high) = outlier.fences;
+        writeln!(
+            html,
+            "<td>{}</td><td>{}</td><td>{} to {}</td></tr>",
+            escape(&config.style.title(outlier.metric)),
+            number(outlier.value),
+            number(low),
+            number(high)
+        )?;
+    }
+    writeln!(html, "</tbody>\n</table>")
+}
+
+fn write_sample_table(
+    html: &mut String,
+    data: &[GenomeStats],
+    outliers: &[Outlier],
+    grouped: bool,
+    config: &PlotConfig,
+) -> std::fmt::Result {
+    write!(html, "<table class=\"sortable\">\n<thead><tr><th>Sample</th>")?;
+    if grouped {
+        write!(html, "<th>Group</th>")?;
+    }
+    for metric in &config.metrics {
+        write!(html, "<th>{}</th>", escape(&config.style.title(metric)))?;
+    }
+    writeln!(html, "</tr></thead>\n<tbody>")?;
+    for (row, record) in data.iter().enumerate() {
+        write!(html, "<tr><td>{}</td>", escape(&record.name(row)))?;
+        if grouped {
+            write!(html, "<td>{}</td>", escape(record.group_name()))?;
+        }
+        for metric in &config.metrics {
+            let value = metric.value(record);
+            let outlier = outliers.iter().any(|o| o.row == row && o.metric == metric);
+            write!(
+                html,
+                "<td{} data-value=\"{value}\">{}</td>",
+                if outlier { " class=\"outlier\"" } else { "" },
+                number(value)
+            )?;
+        }
+        writeln!(html, "</tr>")?;
+    }
+    writeln!(html, "</tbody>\n</table>")
+}
+
+/// `value` in full when it is a whole number, such as a length, and to two
+/// decimals otherwise; missing values are left blank.
+pub(crate) fn number(value: f64) -> String {
+    if !value.is_finite() {
+        String::new()
+    } else if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+/// `text` with the characters that are markup in HTML escaped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,30 @@
+//! Tables of `seqkit stats -a -T`, which give the N content as a count of
+//! bases, `sum_n`, rather than as a percentage.
+
+use csv::StringRecord;
+
+/// Whether `headers` are those of `seqkit stats`.
+pub fn is_stats(headers: &StringRecord) -> bool {
+    ["file", "format", "type", "num_seqs", "sum_len"]
+        .iter()
+        .all(|column| headers.iter().any(|h| h == *column))
+}
+
+/// Indices of the N count and of the assembly length when `columns`, once
+/// normalized, lack an N percentage.
+pub fn n_count(columns: &StringRecord) -> Option<(usize, usize)> {
+    if columns.iter().any(|c| c == "N_percentage") {
+        return None;
+    }
+    let n = columns.iter().position(|c| c == "sum_n")?;
+    let length = columns.iter().position(|c| c == "assembly_length")?;
+    Some((n, length))
+}
+
+/// Percentage of Ns in `length` bases, `n` of them N; empty when either is.
+pub fn n_percentage(n: &str, length: &str) -> String {
+    match (n.parse::<f64>(), length.parse::<f64>()) {
+        (Ok(n), Ok(length)) if length > 0.0 => (n / length * 100.0).to_string(),
+        _ => String::new(),
+    }
+}
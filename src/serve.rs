@@ -0,0 +1,323 @@
+//! Serving the figure and the report over HTTP, drawn again from the inputs
+//! at every request, so that the current state of a batch can be looked at
+//! from a browser on any machine.
+
+use crate::cli::{Args, Mode};
+use count_fasta_plots::{render_boxplots, write_report, Dataset, PlotConfig};
+use std::error::Error;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A response: its status, content type and body.
+type Response = (&'static str, &'static str, Vec<u8>);
+
+/// Longest request line and headers, and most headers, accepted.
+const MAX_HEAD: u64 = 16 << 10;
+const MAX_HEADERS: usize = 64;
+
+/// How long a client may take to send the head of its request, and to send
+/// each part of its body or read each part of the response, before it is
+/// dropped.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Most connections answered at once; those past it are turned away.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Answers the requests made to `address`, each on a thread of its own, until
+/// interrupted: `/` draws the figure and shows it, or the report in report
+/// mode, `/report` draws the report and `/figure/N` sends the Nth file of the
+/// figure last drawn. A table POSTed to `/render` is drawn instead of the
+/// inputs and its figure sent back. Figures are drawn one at a time, into a
+/// directory removed when the server stops, and at most [`MAX_CONNECTIONS`]
+/// clients are answered at once.
+pub fn serve(address: &str, args: &Args, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(address).map_err(|e| format!("{address}: {e}"))?;
+    let dir = tempfile::Builder::new().prefix("count-fasta-plots-").tempdir()?;
+    // Ctrl-C ends the process without dropping the directory
+    let path = dir.path().to_path_buf();
+    ctrlc::set_handler(move || {
+        let _ = std::fs::remove_dir_all(&path);
+        std::process::exit(130);
+    })?;
+    let report_config = match args.mode {
+        Mode::Report => config.clone(),
+        _ => Args { mode: Mode::Report, ..args.clone() }.plot_config()?,
+    };
+    tracing::info!("Serving on http://{}, press Ctrl-C to stop", listener.local_addr()?);
+    let figures = Mutex::new(Vec::new());
+    let open = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        let (report_config, dir, figures, open) = (&report_config, dir.path(), &figures, &open);
+        for stream in listener.incoming() {
+            // Such as a client gone before it was accepted, or too many files
+            // open for now
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::warn!("could not accept a connection: {e}");
+                    continue;
+                }
+            };
+            if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                open.fetch_sub(1, Ordering::SeqCst);
+                tracing::warn!("{} 503 Service Unavailable, {MAX_CONNECTIONS} connections open", peer(&stream));
+                // Without waiting on a client that does not read
+                let busy = plain("503 Service Unavailable", "Too many connections, try again later");
+                if stream.set_nonblocking(true).is_ok() {
+                    let _ = respond(&stream, busy);
+                }
+                continue;
+            }
+            scope.spawn(move || {
+                if let Err(e) = answer(stream, args, config, report_config, dir, figures) {
+                    tracing::error!("{e}");
+                }
+                open.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    })
+}
+
+/// Reading from a client, which must have sent everything read before the
+/// deadline, if any, has passed.
+struct Timed<'a> {
+    stream: &'a TcpStream,
+    deadline: Option<Instant>,
+}
+
+impl Read for Timed<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(deadline) = self.deadline {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(ErrorKind::TimedOut.into());
+            }
+            self.stream.set_read_timeout(Some(left))?;
+        }
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+/// Reads one request from `stream` and answers it.
+fn answer(
+    stream: TcpStream,
+    args: &Args,
+    config: &PlotConfig,
+    report_config: &PlotConfig,
+    dir: &Path,
+    figures: &Mutex<Vec<PathBuf>>,
+) -> Result<(), Box<dyn Error>> {
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    // However slowly its bytes arrive, the head must all be sent in time
+    let mut reader = BufReader::new(Timed { stream: &stream, deadline: Some(Instant::now() + TIMEOUT) });
+    let (request, headers) = match read_head(&mut reader) {
+        Ok(head) => head,
+        Err(response) => {
+            tracing::info!("{} {}", peer(&stream), response.0);
+            return respond(&stream, response);
+        }
+    };
+    reader.get_mut().deadline = None;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let header = |name: &str| headers.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str());
+    let mut words = request.split_whitespace();
+    let (method, target) = (words.next().unwrap_or_default(), words.next().unwrap_or_default());
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let (status, content_type, body) = match (method, path) {
+        ("POST", "/render") => match header("content-length").and_then(|length| length.parse::<u64>().ok()) {
+            Some(length) if length <= args.max_body << 20 => {
+                // Read as it arrives, rather than allocated from the length
+                // the client claims
+                let mut table = Vec::new();
+                reader.take(length).read_to_end(&mut table)?;
+                if (table.len() as u64) < length {
+                    plain("400 Bad Request", "The table ended before its Content-Length")
+                } else {
+                    let _drawing = drawing(figures);
+                    render(args, dir, &table, header("content-type").unwrap_or_default(), query)
+                }
+            }
+            Some(_) => plain("413 Payload Too Large", "The table is too large"),
+            None => plain("411 Length Required", "The table needs a Content-Length"),
+        },
+        ("GET", "/") if args.mode == Mode::Report => {
+            let _drawing = drawing(figures);
+            report(args, report_config, dir)
+        }
+        ("GET", "/") => index(args, config, dir, &mut drawing(figures)),
+        ("GET", "/report") => {
+            let _drawing = drawing(figures);
+            report(args, report_config, dir)
+        }
+        ("GET", path) => {
+            let figures = drawing(figures);
+            match path.strip_prefix("/figure/").and_then(|n| figures.get(n.parse::<usize>().ok()?)) {
+                Some(figure) => match std::fs::read(figure) {
+                    Ok(bytes) => ("200 OK", content_type_of(figure), bytes),
+                    Err(e) => failure(e.into()),
+                },
+                None => plain("404 Not Found", "Not found"),
+            }
+        }
+        _ => plain("405 Method Not Allowed", "Only GET, or POST to /render, is allowed"),
+    };
+    tracing::info!("{method} {target} {status}");
+    respond(&stream, (status, content_type, body))
+}
+
+/// The request line and headers read from `reader`, or the response to a
+/// request whose head is too long, cut short or not sent in time.
+fn read_head(reader: &mut impl BufRead) -> Result<(String, Vec<(String, String)>), Response> {
+    let mut head = reader.by_ref().take(MAX_HEAD);
+    let mut line = || {
+        let mut line = String::new();
+        match head.read_line(&mut line) {
+            Ok(_) if line.ends_with('\n') => Ok(line),
+            Ok(_) if head.limit() == 0 => Err(plain("431 Request Header Fields Too Large", "The request is too long")),
+            Ok(_) => Err(plain("400 Bad Request", "The request ended before its headers")),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                Err(plain("408 Request Timeout", "The request was not sent in time"))
+            }
+            Err(e) => Err(plain("400 Bad Request", format!("error: {e}"))),
+        }
+    };
+    let request = line()?;
+    let mut headers = Vec::new();
+    loop {
+        match line()?.trim_end().split_once(':') {
+            Some(_) if headers.len() == MAX_HEADERS => {
+                return Err(plain("431 Request Header Fields Too Large", "The request has too many headers"))
+            }
+            Some((name, value)) => headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string())),
+            None => break,
+        }
+    }
+    Ok((request, headers))
+}
+
+/// Writes `response` to `stream`.
+fn respond(mut stream: &TcpStream, (status, content_type, body): Response) -> Result<(), Box<dyn Error>> {
+    write!(stream, "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n", body.len())?;
+    write!(stream, "Cache-Control: no-store\r\nConnection: close\r\n\r\n")?;
+    stream.write_all(&body)?;
+    Ok(())
+}
+
+/// The address of the client of `stream`, for the log.
+fn peer(stream: &TcpStream) -> String {
+    stream.peer_addr().map_or_else(|_| "-".to_string(), |address| address.to_string())
+}
+
+/// The figures last drawn, held while drawing into the directory of the
+/// server so that requests do not draw over each other's files.
+fn drawing(figures: &Mutex<Vec<PathBuf>>) -> MutexGuard<'_, Vec<PathBuf>> {
+    figures.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The records of the inputs as they are now.
+fn load(args: &Args) -> Result<Dataset, Box<dyn Error>> {
+    let dataset = Dataset::load(&args.inputs, &args.load_options())?;
+    if dataset.is_empty() {
+        return Err("the input contains no records".into());
+    }
+    Ok(dataset)
+}
+
+/// Page showing the figure drawn again into `dir`, whose files are kept in
+/// `figures`.
+fn index(args: &Args, config: &PlotConfig, dir: &Path, figures: &mut Vec<PathBuf>) -> Response {
+    let draw = || {
+        let name = config.output.file_name().unwrap_or("count-fasta.png".as_ref());
+        render_boxplots(&load(args)?, &PlotConfig { output: dir.join(name), ..config.clone() })
+    };
+    *figures = match draw() {
+        Ok(figures) => figures,
+        Err(e) => return failure(e),
+    };
+    let title = escape(config.style.title.as_deref().unwrap_or("Assembly QC"));
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n");
+    html.push_str("<body>\n<p><a href=\"/report\">Report</a></p>\n");
+    for (n, figure) in figures.iter().enumerate() {
+        let name = escape(&figure.file_name().unwrap_or_default().to_string_lossy());
+        if content_type_of(figure).starts_with("image/") {
+            html.push_str(&format!("<p><img src=\"/figure/{n}\" alt=\"{name}\" style=\"max-width: 100%\"></p>\n"));
+        } else {
+            html.push_str(&format!("<p><a href=\"/figure/{n}\">{name}</a></p>\n"));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    ("200 OK", "text/html; charset=utf-8", html.into_bytes())
+}
+
+/// The report drawn again into `dir`.
+fn report(args: &Args, config: &PlotConfig, dir: &Path) -> Response {
+    let path = dir.join("report.html");
+    let html = load(args).and_then(|dataset| write_report(&path, &dataset, config)).and_then(|()| Ok(std::fs::read(&path)?));
+    match html {
+        Ok(html) => ("200 OK", "text/html; charset=utf-8", html),
+        Err(e) => failure(e),
+    }
+}
+
+/// The first file of the figure of `table`, a CSV or TSV, JSON or JSON
+/// Lines by its `content_type`, drawn with the options of the command line as
+/// PNG, or as the `format` of the `query`.
+fn render(args: &Args, dir: &Path, table: &[u8], content_type: &str, query: &str) -> Response {
+    let format = query.split('&').find_map(|pair| pair.strip_prefix("format=")).unwrap_or("png");
+    if !matches!(format, "png" | "svg") {
+        return plain("400 Bad Request", format!("cannot render {format:?}, only png or svg"));
+    }
+    let name = match content_type.split(';').next().unwrap_or_default().trim() {
+        "application/json" => "table.json",
+        "application/x-ndjson" | "application/jsonl" | "application/x-jsonlines" => "table.jsonl",
+        "text/tab-separated-values" => "table.tsv",
+        _ => "table.csv",
+    };
+    let dataset = match Dataset::from_table(name, table, &args.load_options()) {
+        Ok(dataset) if dataset.is_empty() => return plain("400 Bad Request", "error: the table contains no records"),
+        Ok(dataset) => dataset,
+        Err(e) => return plain("400 Bad Request", format!("error: {e}")),
+    };
+    let output = dir.join(format!("render.{format}"));
+    let args = Args { output: Some(output), format: None, split: false, ..args.clone() };
+    match args.plot_config().and_then(|config| render_boxplots(&dataset, &config)) {
+        Ok(figures) => match std::fs::read(&figures[0]) {
+            Ok(figure) => ("200 OK", content_type_of(&figures[0]), figure),
+            Err(e) => failure(e.into()),
+        },
+        Err(e) => failure(e),
+    }
+}
+
+/// Why the inputs could not be drawn, as shown to the browser.
+fn failure(error: Box<dyn Error>) -> Response {
+    tracing::error!("{error}");
+    plain("500 Internal Server Error", format!("error: {error}"))
+}
+
+/// A response of a line of text.
+fn plain(status: &'static str, message: impl Into<String>) -> Response {
+    (status, "text/plain; charset=utf-8", format!("{}\n", message.into()).into_bytes())
+}
+
+fn content_type_of(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()).unwrap_or_default() {
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "html" => "text/html; charset=utf-8",
+        "json" => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,365 @@
+//! Distributions of metrics gathered in a single pass over tables too large
+//! to hold in memory, such as read-level statistics, each kept as a quantile
+//! sketch of bounded size rather than as every value.
+
+use crate::summary::{QuantileMethod, Summary};
+use crate::{fold_inputs, GenomeStats, LoadOptions, Metric};
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Values each level of a sketch holds before half of them are promoted to
+/// the next, which keeps quantiles within about 1% of their rank.
+const CAPACITY: usize = 1024;
+
+/// Compacting quantile sketch after Karnin, Lang and Liberty (2016), with
+/// levels of equal capacity: a full level is sorted and every other value
+/// promoted to the next, where it stands for twice as many. The count,
+/// extremes, mean and standard deviation are exact, and so is the whole
+/// summary until the first level fills up.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Sketch {
+    /// Values kept at each level, each standing for 2^level values.
+    levels: Vec<Vec<f64>>,
+    count: u64,
+    min: f64,
+    max: f64,
+    /// Smallest positive value, for log axes.
+    min_positive: f64,
+    /// Running mean and sum of squared deviations (Welford, 1962).
+    mean: f64,
+    m2: f64,
+    /// Whether the odd rather than the even values of the next level
+    /// compacted are promoted, alternated so that neither half is favoured.
+    odd: bool,
+}
+
+impl Sketch {
+    /// Adds `x`, unless it is missing or infinite.
+    pub(crate) fn insert(&mut self, x: f64) {
+        if !x.is_finite() {
+            return;
+        }
+        if self.count == 0 {
+            (self.min, self.max, self.min_positive) = (x, x, f64::INFINITY);
+        }
+        self.count += 1;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        if x > 0.0 {
+            self.min_positive = self.min_positive.min(x);
+        }
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        self.levels[0].push(x);
+        if self.levels[0].len() >= CAPACITY {
+            self.compact(0);
+        }
+    }
+
+    /// Adds the values of `other`.
+    pub(crate) fn merge(&mut self, other: Sketch) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other;
+            return;
+        }
+        // Chan, Golub and LeVeque (1979)
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2 + delta * delta * self.count as f64 * other.count as f64 / count as f64;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.min_positive = self.min_positive.min(other.min_positive);
+        for (level, values) in other.levels.into_iter().enumerate() {
+            if self.levels.len() <= level {
+                self.levels.push(Vec::new());
+            }
+            self.levels[level].extend(values);
+        }
+        let mut level = 0;
+        while level < self.levels.len() {
+            if self.levels[level].len() >= CAPACITY {
+                self.compact(level);
+            }
+            level += 1;
+        }
+    }
+
+    /// Promotes half of the values of `level` to the next one.
+    fn compact(&mut self, level: usize) {
+        let mut values = std::mem::take(&mut self.levels[level]);
+        values.sort_by(f64::total_cmp);
+        // An odd value out stays behind, so that the weights still add up to
+        // the count
+        if values.len() % 2 == 1 {
+            let left = if self.odd { values.remove(0) } else { values.pop().unwrap_or_default() };
+            self.levels[level].push(left);
+        }
+        let promoted = values.into_iter().skip(usize::from(self.odd)).step_by(2);
+        self.odd = !self.odd;
+        if self.levels.len() == level + 1 {
+            self.levels.push(Vec::new());
+        }
+        self.levels[level + 1].extend(promoted);
+        if self.levels[level + 1].len() >= CAPACITY {
+            self.compact(level + 1);
+        }
+    }
+
+    /// Smallest and largest value and smallest positive one, infinite when
+    /// there is none, or `None` for an empty sketch.
+    pub(crate) fn extent(&self) -> Option<(f64, f64, f64)> {
+        (self.count > 0).then_some((self.min, self.max, self.min_positive))
+    }
+
+    /// Summary of the values, `None` when there are none. Quartiles follow
+    /// `method` while the sketch holds every value and are otherwise the
+    /// kept values at their ranks; the whiskers end at kept values.
+    pub(crate) fn summary(&self, method: QuantileMethod) -> Option<Summary> {
+        if self.count == 0 {
+            return None;
+        }
+        if self.levels.len() == 1 {
+            return Some(Summary::compute(&self.levels[0], method));
+        }
+        let mut weighted: Vec<(f64, u64)> = self
+            .levels
+            .iter()
+            .enumerate()
+            .flat_map(|(level, values)| values.iter().map(move |&x| (x, 1u64 << level)))
+            .collect();
+        weighted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let total: u64 = weighted.iter().map(|&(_, weight)| weight).sum();
+        let quantile = |p: f64| {
+            let rank = p * total as f64;
+            let mut seen = 0;
+            for &(x, weight) in &weighted {
+                seen += weight;
+                if seen as f64 >= rank {
+                    return x;
+                }
+            }
+            self.max
+        };
+        let (q1, q3) = (quantile(0.25), quantile(0.75));
+        let mut summary = Summary {
+            count: self.count as usize,
+            min: self.min,
+            q1,
+            median: quantile(0.5),
+            q3,
+            max: self.max,
+            mean: self.mean,
+            stddev: (self.m2 / (self.count - 1) as f64).sqrt(),
+            whisker_low: q1,
+            whisker_high: q3,
+            outliers: 0,
+        };
+        let (low, high) = summary.fences();
+        let kept = || weighted.iter().map(|&(x, _)| x);
+        summary.whisker_low = if self.min >= low { self.min } else { kept().find(|&x| x >= low).unwrap_or(q1) };
+        summary.whisker_high = if self.max <= high { self.max } else { kept().rev().find(|&x| x <= high).unwrap_or(q3) };
+        let beyond: u64 = weighted.iter().filter(|&&(x, _)| summary.is_outlier(x)).map(|&(_, weight)| weight).sum();
+        summary.outliers = beyond as usize;
+        Some(summary)
+    }
+}
+
+/// Distributions of metrics of the records of some inputs, per group when
+/// they are grouped, gathered in one pass without keeping the records, for
+/// tables too large to load as a [`Dataset`](crate::Dataset).
+#[derive(Debug, Clone, Default)]
+pub struct Sketches {
+    metrics: Vec<Metric>,
+    /// Groups in order of first appearance, `NA` for records without one.
+    groups: Vec<String>,
+    /// Whether any record has a group.
+    grouped: bool,
+    /// Sketch of each metric of each group.
+    sketches: Vec<Vec<Sketch>>,
+}
+
+impl Sketches {
+    /// Reads CSV files, FASTA files, directories of either and glob patterns
+    /// like [`Dataset::load`](crate::Dataset::load), keeping sketches of
+    /// `metrics` only. `--sample` and BUSCO summaries are matched to records
+    /// by name, so `options` must have neither. JSON tables, and those that
+    /// may be QUAST reports, are still read whole before being sketched.
+    pub fn load(inputs: &[PathBuf], options: &LoadOptions, metrics: &[Metric]) -> Result<Self, Box<dyn Error>> {
+        if !options.samples.is_empty() || !options.busco.is_empty() {
+            return Err("samples and BUSCO summaries are matched to records, which streaming does not keep".into());
+        }
+        let empty = || Sketches { metrics: metrics.to_vec(), ..Sketches::default() };
+        let batches = fold_inputs(inputs, options, empty, Sketches::insert)?;
+        Ok(batches.into_iter().fold(empty(), Sketches::merge))
+    }
+
+    fn insert(&mut self, record: GenomeStats) {
+        let group = record.group_name();
+        let g = match self.groups.iter().position(|name| name == group) {
+            Some(g) => g,
+            None => {
+                self.groups.push(group.to_string());
+                self.sketches.push(vec![Sketch::default(); self.metrics.len()]);
+                self.groups.len() - 1
+            }
+        };
+        self.grouped |= record.group.is_some();
+        for (metric, sketch) in self.metrics.iter().zip(&mut self.sketches[g]) {
+            sketch.insert(metric.value(&record));
+        }
+    }
+
+    fn merge(mut self, other: Sketches) -> Sketches {
+        self.grouped |= other.grouped;
+        for (group, sketches) in other.groups.into_iter().zip(other.sketches) {
+            match self.groups.iter().position(|name| *name == group) {
+                Some(g) => {
+                    for (sketch, other) in self.sketches[g].iter_mut().zip(sketches) {
+                        sketch.merge(other);
+                    }
+                }
+                None => {
+                    self.groups.push(group);
+                    self.sketches.push(sketches);
+                }
+            }
+        }
+        self
+    }
+
+    /// Whether no record was read.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Metrics sketched, in the order given.
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// Distinct groups in order of first appearance, or nothing when the
+    /// records are not grouped.
+    pub fn groups(&self) -> Vec<&str> {
+        match self.grouped {
+            true => self.groups.iter().map(String::as_str).collect(),
+            false => Vec::new(),
+        }
+    }
+
+    /// Sketch of `metric` for each group, or for every record when
+    /// ungrouped; `None` for a metric that was not sketched.
+    pub(crate) fn of(&self, metric: &Metric) -> Option<Vec<&Sketch>> {
+        let m = self.metrics.iter().position(|sketched| sketched == metric)?;
+        Some(self.sketches.iter().map(|sketches| &sketches[m]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: usize = 100_000;
+
+    /// The values 0 to [`VALUES`], each its own rank, shuffled by a xorshift.
+    fn shuffled() -> Vec<f64> {
+        let mut values: Vec<f64> = (0..VALUES).map(|x| x as f64).collect();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for i in (1..values.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            values.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+        values
+    }
+
+    /// Asserts that the quartiles of `sketch` are within 1% of the rank of
+    /// the exact ones of `values`, and that its other statistics are exact.
+    fn assert_within_bound(sketch: &Sketch, values: &[f64]) {
+        let exact = Summary::compute(values, QuantileMethod::R7);
+        let summary = sketch.summary(QuantileMethod::R7).unwrap();
+        // Each value is its rank
+        let bound = 0.01 * values.len() as f64;
+        for (name, estimate, exact) in
+            [("q1", summary.q1, exact.q1), ("median", summary.median, exact.median), ("q3", summary.q3, exact.q3)]
+        {
+            assert!((estimate - exact).abs() <= bound, "{name} is {estimate}, not within {bound} of {exact}");
+        }
+        assert_eq!((summary.count, summary.min, summary.max), (exact.count, exact.min, exact.max));
+        assert!((summary.mean - exact.mean).abs() < 1e-6);
+        assert!((summary.stddev - exact.stddev).abs() < 1e-6);
+    }
+
+    #[test]
+    fn small_sketches_are_exact() {
+        let mut sketch = Sketch::default();
+        let values = [5.0, 1.0, 4.0, 2.0, 3.0];
+        values.iter().for_each(|&x| sketch.insert(x));
+        sketch.insert(f64::NAN);
+        let (exact, summary) = (Summary::compute(&values, QuantileMethod::R6), sketch.summary(QuantileMethod::R6).unwrap());
+        assert_eq!((summary.q1, summary.median, summary.q3), (exact.q1, exact.median, exact.q3));
+        assert_eq!(summary.count, 5);
+        assert_eq!(sketch.extent(), Some((1.0, 5.0, 1.0)));
+        assert!(Sketch::default().summary(QuantileMethod::R7).is_none());
+    }
+
+    #[test]
+    fn shuffled_values_within_bound() {
+        let values = shuffled();
+        let mut sketch = Sketch::default();
+        values.iter().for_each(|&x| sketch.insert(x));
+        assert!(sketch.levels.len() > 1);
+        assert_within_bound(&sketch, &values);
+    }
+
+    #[test]
+    fn sorted_values_within_bound() {
+        let values: Vec<f64> = (0..VALUES).map(|x| x as f64).collect();
+        for ordered in [values.clone(), values.iter().rev().copied().collect()] {
+            let mut sketch = Sketch::default();
+            ordered.iter().for_each(|&x| sketch.insert(x));
+            assert_within_bound(&sketch, &values);
+        }
+    }
+
+    #[test]
+    fn merged_halves_as_one_sketch() {
+        let values = shuffled();
+        let (first, second) = values.split_at(VALUES / 3);
+        let (mut merged, mut other, mut whole) = (Sketch::default(), Sketch::default(), Sketch::default());
+        first.iter().for_each(|&x| merged.insert(x));
+        second.iter().for_each(|&x| other.insert(x));
+        values.iter().for_each(|&x| whole.insert(x));
+        merged.merge(other);
+        assert_within_bound(&merged, &values);
+        let (merged, whole) = (merged.summary(QuantileMethod::R7).unwrap(), whole.summary(QuantileMethod::R7).unwrap());
+        assert_eq!((merged.count, merged.min, merged.max), (whole.count, whole.min, whole.max));
+        assert!((merged.mean - whole.mean).abs() < 1e-6);
+        assert!((merged.stddev - whole.stddev).abs() < 1e-6);
+        for (merged, whole) in [(merged.q1, whole.q1), (merged.median, whole.median), (merged.q3, whole.q3)] {
+            assert!((merged - whole).abs() <= 0.02 * VALUES as f64, "{merged} and {whole}");
+        }
+    }
+
+    #[test]
+    fn merging_small_sketches_is_exact() {
+        let (mut a, mut b) = (Sketch::default(), Sketch::default());
+        [1.0, 7.0, 3.0].iter().for_each(|&x| a.insert(x));
+        [2.0, 9.0].iter().for_each(|&x| b.insert(x));
+        a.merge(b);
+        a.merge(Sketch::default());
+        let exact = Summary::compute(&[1.0, 7.0, 3.0, 2.0, 9.0], QuantileMethod::R7);
+        let summary = a.summary(QuantileMethod::R7).unwrap();
+        assert_eq!((summary.q1, summary.median, summary.q3), (exact.q1, exact.median, exact.q3));
+        assert!((summary.mean - exact.mean).abs() < 1e-12);
+    }
+}
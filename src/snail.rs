@@ -0,0 +1,90 @@
+//! Summaries of an assembly binned by the share of it they cover, longest
+//! sequences first, as drawn around the snail plots of BlobToolKit.
+
+use plotters::style::RGBColor;
+
+/// Bins of a snail plot, each a thousandth of the assembly.
+pub const BINS: usize = 1000;
+
+/// Color of the longest sequence.
+pub const LONGEST: RGBColor = RGBColor(0xe3, 0x1a, 0x1c);
+/// Color of the sequences of the N50.
+pub const N50: RGBColor = RGBColor(0xff, 0x7f, 0x0e);
+/// Color of the further sequences of the N90.
+pub const N90: RGBColor = RGBColor(0xfd, 0xbf, 0x6f);
+/// Color of the shortest sequences, beyond the N90.
+pub const REST: RGBColor = RGBColor(0x40, 0x40, 0x40);
+/// Colors of the GC and AT shares of the outer ring.
+pub const GC: RGBColor = RGBColor(0x1f, 0x78, 0xb4);
+pub const AT: RGBColor = RGBColor(0xa6, 0xce, 0xe3);
+
+/// One assembly cut into [`BINS`] bins of equal length.
+#[derive(Debug, Default)]
+pub struct Snail {
+    /// Length of the sequence in the middle of each bin, the Nx at its
+    /// percentage.
+    pub lengths: Vec<u64>,
+    /// Sequences starting up to the end of each bin.
+    pub counts: Vec<u64>,
+    /// GC percentage of the bases of each bin, NaN when none has a known
+    /// GC.
+    pub gc: Vec<f64>,
+}
+
+/// Bins an assembly with `sorted_lengths`, longest first, and the GC
+/// percentage of each of its sequences in the same order; empty when the
+/// assembly is.
+pub fn bin(sorted_lengths: &[u64], sequence_gc: &[f64]) -> Snail {
+    let total = sorted_lengths.iter().sum::<u64>() as f64;
+    if total == 0.0 {
+        return Snail::default();
+    }
+    let width = total / BINS as f64;
+    let ends: Vec<f64> = sorted_lengths
+        .iter()
+        .scan(0.0, |end, &len| {
+            *end += len as f64;
+            Some(*end)
+        })
+        .collect();
+
+    let mut snail = Snail::default();
+    let (mut covering, mut started) = (0, 0);
+    for k in 0..BINS {
+        let middle = (k as f64 + 0.5) * width;
+        while ends[covering] < middle && covering + 1 < ends.len() {
+            covering += 1;
+        }
+        snail.lengths.push(sorted_lengths[covering]);
+        // A sequence starts where the previous one ends
+        let end = (k + 1) as f64 * width;
+        while started < ends.len() && (started == 0 || ends[started - 1] < end) {
+            started += 1;
+        }
+        snail.counts.push(started as u64);
+    }
+
+    let (mut gc_bases, mut known) = (vec![0.0; BINS], vec![0.0; BINS]);
+    for (i, &len) in sorted_lengths.iter().enumerate() {
+        let gc = sequence_gc.get(i).copied().unwrap_or(f64::NAN);
+        if gc.is_nan() || len == 0 {
+            continue;
+        }
+        let (start, end) = (ends[i] - len as f64, ends[i]);
+        let first = (start / width) as usize;
+        let last = ((end / width).ceil() as usize).min(BINS);
+        for k in first..last {
+            let overlap = end.min((k + 1) as f64 * width) - start.max(k as f64 * width);
+            if overlap > 0.0 {
+                gc_bases[k] += overlap * gc;
+                known[k] += overlap;
+            }
+        }
+    }
+    snail.gc = gc_bases
+        .iter()
+        .zip(&known)
+        .map(|(gc, known)| if *known > 0.0 { gc / known } else { f64::NAN })
+        .collect();
+    snail
+}
@@ -0,0 +1,130 @@
+//! SQLite databases of results, built with the `sqlite` feature: the
+//! statistics are read from one of their tables, whose columns are named and
+//! recognised as those of a CSV, and the summary statistics written back into
+//! another.
+
+use crate::summary::Row;
+use crate::SendError;
+use csv::StringRecord;
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// First bytes of every SQLite database.
+const MAGIC: &[u8] = b"SQLite format 3\0";
+
+/// Whether `path` is a SQLite database, told by its first bytes whatever its
+/// name.
+pub fn is_database(path: &Path) -> bool {
+    let mut magic = [0; MAGIC.len()];
+    !crate::reader::is_stdin(path)
+        && File::open(path).and_then(|mut file| file.read_exact(&mut magic)).is_ok()
+        && magic == MAGIC
+}
+
+/// `name` quoted as an SQL identifier.
+#[cfg(feature = "sqlite")]
+fn identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// The header and rows of `table` of the database `path`, each row
+/// positioned at its number.
+#[cfg(feature = "sqlite")]
+pub fn read_table(path: &Path, table: &str) -> Result<(StringRecord, Vec<StringRecord>), SendError> {
+    use crate::{Invalid, InvalidInput};
+    use rusqlite::types::ValueRef;
+    use rusqlite::{Connection, OpenFlags};
+
+    let error = |e: rusqlite::Error| format!("{}: {}", path.display(), e);
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(error)?;
+    let mut statement = connection.prepare(&format!("SELECT * FROM {}", identifier(table))).map_err(error)?;
+    let headers: StringRecord = statement.column_names().into_iter().collect();
+    let mut rows = Vec::new();
+    let mut results = statement.query([]).map_err(error)?;
+    while let Some(result) = results.next().map_err(error)? {
+        let n = rows.len() + 1;
+        let mut row = StringRecord::new();
+        for (i, column) in headers.iter().enumerate() {
+            match result.get_ref(i).map_err(error)? {
+                ValueRef::Null => row.push_field(""),
+                ValueRef::Integer(value) => row.push_field(&value.to_string()),
+                ValueRef::Real(value) => row.push_field(&value.to_string()),
+                ValueRef::Text(text) => row.push_field(&String::from_utf8_lossy(text)),
+                ValueRef::Blob(_) => {
+                    let message = format!("{}: row {}: {:?} is not a value", path.display(), n, column);
+                    return Err(InvalidInput::new(Invalid::UnparseableRow, message).into());
+                }
+            }
+        }
+        let mut position = csv::Position::new();
+        position.set_line(n as u64);
+        row.set_position(Some(position));
+        rows.push(row);
+    }
+    Ok((headers, rows))
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn read_table(path: &Path, _table: &str) -> Result<(StringRecord, Vec<StringRecord>), SendError> {
+    Err(unsupported(path).into())
+}
+
+/// Writes `rows` into `table` of the database `path`, created if need be,
+/// replacing the rows of a previous run in a single transaction.
+#[cfg(feature = "sqlite")]
+pub fn write_rows(path: &Path, table: &str, rows: &[Row]) -> Result<(), Box<dyn Error>> {
+    use rusqlite::{params, Connection};
+
+    let error = |e: rusqlite::Error| format!("{}: {}", path.display(), e);
+    let mut connection = Connection::open(path).map_err(error)?;
+    let transaction = connection.transaction().map_err(error)?;
+    let table = identifier(table);
+    transaction
+        .execute_batch(&format!(
+            "DROP TABLE IF EXISTS {table};
+            CREATE TABLE {table} (
+                metric TEXT NOT NULL, \"group\" TEXT, count INTEGER NOT NULL, min REAL, q1 REAL, median REAL,
+                q3 REAL, max REAL, mean REAL, stddev REAL, outliers INTEGER NOT NULL, median_ci_low REAL,
+                median_ci_high REAL
+            );"
+        ))
+        .map_err(error)?;
+    {
+        let mut insert = transaction
+            .prepare(&format!("INSERT INTO {table} VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"))
+            .map_err(error)?;
+        for row in rows {
+            insert
+                .execute(params![
+                    row.metric.to_string(),
+                    row.group,
+                    row.count as i64,
+                    row.min,
+                    row.q1,
+                    row.median,
+                    row.q3,
+                    row.max,
+                    row.mean,
+                    row.stddev,
+                    row.outliers as i64,
+                    row.median_ci_low,
+                    row.median_ci_high,
+                ])
+                .map_err(error)?;
+        }
+    }
+    transaction.commit().map_err(error)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn write_rows(path: &Path, _table: &str, _rows: &[Row]) -> Result<(), Box<dyn Error>> {
+    Err(unsupported(path).into())
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn unsupported(path: &Path) -> String {
+    format!("{}: SQLite databases are only read and written when built with the sqlite feature", path.display())
+}
@@ -0,0 +1,91 @@
+//! Statistics as plain text, for a quick check in a terminal: a block per
+//! assembly in the layout of `assembly-stats`, or the summary of every metric
+//! per group.
+
+use crate::fasta::covering;
+use crate::report::number;
+use crate::{group_labels, Dataset, GenomeStats, PlotConfig, Summary};
+use std::error::Error;
+use std::io::Write;
+
+/// Prints a block of statistics per record of `dataset` to `out`, as
+/// `assembly-stats` does: N50 to N100 when the sequence lengths are known,
+/// the N50 and L50 of the table otherwise.
+pub fn write_stats(out: &mut dyn Write, dataset: &Dataset) -> Result<(), Box<dyn Error>> {
+    for (row, record) in dataset.records().iter().enumerate() {
+        if row > 0 {
+            writeln!(out)?;
+        }
+        write_record(out, record, row)?;
+    }
+    Ok(())
+}
+
+fn write_record(out: &mut dyn Write, record: &GenomeStats, row: usize) -> Result<(), Box<dyn Error>> {
+    let (sum, n) = (record.assembly_length, record.number_of_sequences);
+    writeln!(out, "stats for {}", record.name(row))?;
+    write!(out, "sum = {}, n = {}, ave = {:.2}", number(sum), number(n), if n > 0.0 { sum / n } else { 0.0 })?;
+    match record.largest_contig.or(record.lengths.first().map(|&len| len as f64)) {
+        Some(largest) => writeln!(out, ", largest = {}", number(largest))?,
+        None => writeln!(out)?,
+    }
+    if record.lengths.is_empty() {
+        match record.l50 {
+            Some(l50) => writeln!(out, "N50 = {}, n = {}", number(record.n50), number(l50))?,
+            None => writeln!(out, "N50 = {}", number(record.n50))?,
+        }
+    } else {
+        for percent in (50..=100).step_by(10) {
+            let (len, count) = covering(&record.lengths, percent);
+            writeln!(out, "N{percent} = {len}, n = {count}")?;
+        }
+    }
+    let n_count = record.gap_length.unwrap_or((sum * record.n_percentage / 100.0).round());
+    writeln!(out, "N_count = {}", number(n_count))?;
+    if let Some(gaps) = record.gaps {
+        writeln!(out, "Gaps = {}", number(gaps))?;
+    }
+    writeln!(out, "GC = {:.2}%", record.gc_percentage)?;
+    Ok(())
+}
+
+/// Prints the summary of every metric of `config` to `out`, a block per
+/// group when grouped and one of every record otherwise.
+pub fn write_aggregate_stats(out: &mut dyn Write, dataset: &Dataset, config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    let data = dataset.records();
+    let groups = group_labels(data);
+    let subsets: Vec<(Option<&str>, Vec<&GenomeStats>)> = if groups.is_empty() {
+        vec![(None, data.iter().collect())]
+    } else {
+        groups.iter().map(|&group| (Some(group), data.iter().filter(|x| x.group_name() == group).collect())).collect()
+    };
+    for (i, (group, records)) in subsets.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        let noun = if records.len() == 1 { "assembly" } else { "assemblies" };
+        match group {
+            Some(group) => writeln!(out, "stats for {group}, {} {noun}", records.len())?,
+            None => writeln!(out, "stats for {} {noun}", records.len())?,
+        }
+        for metric in &config.metrics {
+            let values: Vec<f64> = records.iter().map(|x| metric.value(x)).filter(|v| v.is_finite()).collect();
+            if values.is_empty() {
+                writeln!(out, "{metric}: no values")?;
+                continue;
+            }
+            let Summary { min, q1, median, q3, max, mean, .. } = Summary::compute(&values, config.quantile_method);
+            writeln!(
+                out,
+                "{metric}: min = {}, q1 = {}, median = {}, q3 = {}, max = {}, mean = {}",
+                number(min),
+                number(q1),
+                number(median),
+                number(q3),
+                number(max),
+                number(mean),
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,449 @@
+//! House style of the figures: one of the built-in [`Theme`]s, optionally
+//! adjusted by a TOML file given with `--config`.
+//!
+//! ```toml
+//! font = "serif"
+//! caption_size = 24
+//! margin = 10
+//! box_color = "#1f77b4"
+//! group_colors = ["#1b9e77", "#d95f02", "#7570b3"]
+//!
+//! [label_area]
+//! left = 60
+//!
+//! [metrics.n50]
+//! caption = "Contig N50"
+//! range = [0, 5e7]
+//! ```
+
+use crate::{ticks, Locale, Metric, Notation};
+use clap::ValueEnum;
+use plotters::chart::{MeshStyle, SeriesLabelStyle};
+use plotters::coord::ranged1d::Ranged;
+use plotters::coord::CoordTranslate;
+use plotters::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Captions, colors, margins and fonts shared by every chart. Anything left
+/// out keeps the built-in look.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    /// Title above the whole figure.
+    pub title: Option<String>,
+    /// Line below the whole figure, for run metadata.
+    pub footer: Option<String>,
+    /// Family of all text; generic families and families that are not
+    /// installed are drawn with the bundled DejaVu Sans.
+    pub font: String,
+    /// TrueType or OpenType file to draw `font` from instead of the system's.
+    pub font_file: Option<PathBuf>,
+    pub caption_size: u32,
+    /// Size of tick, axis and legend labels.
+    pub label_size: u32,
+    /// Margin around every chart, in pixels; each kind of chart has its own
+    /// default.
+    pub margin: Option<u32>,
+    pub label_area: LabelArea,
+    pub background: HexColor,
+    /// Leave the figure see-through behind the charts, as for dark slides;
+    /// `background` then only fills legends and the like.
+    pub transparent: bool,
+    /// Color of text, axes and grid lines.
+    pub foreground: HexColor,
+    /// Fill of ungrouped boxes, violins and bars.
+    pub box_color: HexColor,
+    pub median_color: HexColor,
+    pub whisker_color: HexColor,
+    pub outlier_color: HexColor,
+    /// Colors of the groups, cycled when there are more groups; the default
+    /// palette is used when empty.
+    pub group_colors: Vec<HexColor>,
+    pub metrics: HashMap<Metric, MetricStyle>,
+    /// Factor applied to the image size and to every font, margin, marker
+    /// and line, as `2` for a print version of a screen figure.
+    pub scale: f64,
+    /// Where legends are drawn; each kind of chart has its own default
+    /// corner.
+    pub legend: Option<LegendPosition>,
+    /// Decimal mark and thousands separator of tick labels and annotations.
+    pub locale: Locale,
+    /// Where the values of `--quartile-labels` are written, and how large.
+    pub quartile_labels: QuartileLabels,
+}
+
+/// Placement of the legend of a chart, selected with `--legend`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LegendPosition {
+    UpperLeft,
+    UpperMiddle,
+    UpperRight,
+    MiddleLeft,
+    MiddleRight,
+    LowerLeft,
+    LowerMiddle,
+    LowerRight,
+    /// Once for the whole figure, right of its panels, see
+    /// `label_area.right`; charts drawn alone keep theirs in their corner
+    Outside,
+    /// No legend at all
+    None,
+}
+
+/// Width of the left and height of the bottom label areas, in pixels, and
+/// width of the room right of the panels for an outside legend.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelArea {
+    pub left: Option<u32>,
+    pub bottom: Option<u32>,
+    pub right: Option<u32>,
+}
+
+/// Placement and size of the values of the quartiles and median written
+/// next to each box.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuartileLabels {
+    pub position: QuartilePosition,
+    /// Font size, that of the outlier labels by default.
+    pub size: Option<u32>,
+}
+
+/// Side of the box the quartile labels are written on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum QuartilePosition {
+    #[default]
+    Above,
+    Below,
+}
+
+/// Overrides for the panel of one metric.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricStyle {
+    pub caption: Option<String>,
+    pub xlabel: Option<String>,
+    /// Fixed axis range; values outside of it are not drawn.
+    pub range: Option<(f64, f64)>,
+    /// Draw the values on a log10 axis, like `--log`.
+    pub log: bool,
+    /// Value at which to draw a reference line, such as a minimum N50.
+    pub threshold: Option<f64>,
+    /// Label of the reference line, the value itself by default.
+    pub threshold_label: Option<String>,
+    /// Bandwidth of the density estimates, in decades on a log axis, instead
+    /// of Silverman's rule of thumb.
+    pub bandwidth: Option<f64>,
+    /// Rows and columns of the page grid the panel covers, one cell by
+    /// default.
+    pub span: Option<(usize, usize)>,
+    /// Number of ticks of the value axis, about, instead of the number
+    /// plotters picks.
+    pub ticks: Option<usize>,
+    /// Decimal places of the tick labels, instead of three significant
+    /// digits.
+    pub decimals: Option<usize>,
+    /// How the tick labels are written, in units unless `--raw-ticks`.
+    pub notation: Option<Notation>,
+}
+
+/// An RGB color written as `#rrggbb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor(pub RGBColor);
+
+impl From<HexColor> for String {
+    fn from(HexColor(RGBColor(r, g, b)): HexColor) -> Self {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("expected a color such as #1f77b4, got {s:?}");
+        let hex = s.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii()).ok_or_else(invalid)?;
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+        Ok(HexColor(RGBColor(channel(0)?, channel(2)?, channel(4)?)))
+    }
+}
+
+/// Built-in color schemes selected with `--theme`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    /// Black on white
+    #[default]
+    Light,
+    /// Light text and bright colors on a dark gray background
+    Dark,
+    /// Saturated colors on black, for projectors and low vision
+    HighContrast,
+    /// The Okabe-Ito palette, distinguishable with any color vision
+    Colorblind,
+}
+
+impl Theme {
+    pub fn style(self) -> Style {
+        let hex = |s: &str| HexColor::try_from(s.to_string()).expect("valid theme color");
+        let light = Style {
+            title: None,
+            footer: None,
+            font: "sans-serif".to_string(),
+            font_file: None,
+            caption_size: 20,
+            label_size: 12,
+            margin: None,
+            label_area: LabelArea::default(),
+            background: HexColor(WHITE),
+            transparent: false,
+            foreground: HexColor(BLACK),
+            box_color: HexColor(BLUE),
+            median_color: HexColor(RED),
+            whisker_color: HexColor(BLACK),
+            outlier_color: HexColor(BLACK),
+            group_colors: Vec::new(),
+            metrics: HashMap::new(),
+            scale: 1.0,
+            legend: None,
+            locale: Locale::default(),
+            quartile_labels: QuartileLabels::default(),
+        };
+        match self {
+            Theme::Light => light,
+            Theme::Dark => Style {
+                background: hex("#1e1e1e"),
+                foreground: hex("#d4d4d4"),
+                box_color: hex("#4fc3f7"),
+                median_color: hex("#ff8a65"),
+                whisker_color: hex("#d4d4d4"),
+                outlier_color: hex("#d4d4d4"),
+                group_colors: ["#4fc3f7", "#ff8a65", "#aed581", "#ffd54f", "#ba68c8", "#4db6ac"]
+                    .map(hex)
+                    .to_vec(),
+                ..light
+            },
+            Theme::HighContrast => Style {
+                background: HexColor(BLACK),
+                foreground: HexColor(WHITE),
+                box_color: hex("#ffff00"),
+                median_color: hex("#00ffff"),
+                whisker_color: HexColor(WHITE),
+                outlier_color: hex("#ff00ff"),
+                group_colors: ["#ffff00", "#00ffff", "#ff00ff", "#00ff00", "#ff8000", "#ffffff"]
+                    .map(hex)
+                    .to_vec(),
+                ..light
+            },
+            Theme::Colorblind => Style {
+                box_color: hex("#0072b2"),
+                median_color: hex("#d55e00"),
+                group_colors: [
+                    "#e69f00", "#56b4e9", "#009e73", "#f0e442", "#0072b2", "#d55e00", "#cc79a7",
+                    "#000000",
+                ]
+                .map(hex)
+                .to_vec(),
+                ..light
+            },
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Theme::Light.style()
+    }
+}
+
+impl Style {
+    /// Reads `path` on top of `base`, so that the file only needs the keys it
+    /// changes.
+    pub fn from_file(path: &Path, base: Style) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Style::from_toml(&text, base).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// Reads the TOML `text` of a style file on top of `base`, like
+    /// [`Style::from_file`].
+    pub fn from_toml(text: &str, base: Style) -> Result<Self, Box<dyn Error>> {
+        // Parsed on its own first for errors that point into the file
+        toml::from_str::<Style>(text)?;
+        let overrides: toml::Table = toml::from_str(text)?;
+        let mut table = toml::Table::try_from(base)?;
+        merge(&mut table, overrides);
+        Ok(toml::Value::Table(table).try_into()?)
+    }
+
+    pub fn group_color(&self, index: usize) -> RGBColor {
+        if self.group_colors.is_empty() {
+            let (r, g, b) = Palette99::pick(index).rgb();
+            RGBColor(r, g, b)
+        } else {
+            self.group_colors[index % self.group_colors.len()].0
+        }
+    }
+
+    pub fn title<'a>(&'a self, metric: &'a Metric) -> Cow<'a, str> {
+        match self.metrics.get(metric).and_then(|m| m.caption.as_deref()) {
+            Some(caption) => Cow::Borrowed(caption),
+            None => metric.title(),
+        }
+    }
+
+    pub fn xlabel<'a>(&'a self, metric: &'a Metric) -> Cow<'a, str> {
+        match self.metrics.get(metric).and_then(|m| m.xlabel.as_deref()) {
+            Some(xlabel) => Cow::Borrowed(xlabel),
+            None => metric.xlabel(),
+        }
+    }
+
+    pub fn range(&self, metric: &Metric) -> Option<(f64, f64)> {
+        self.metrics.get(metric).and_then(|m| m.range)
+    }
+
+    /// Rows and columns of the page grid covered by the panel of `metric`.
+    pub fn span(&self, metric: &Metric) -> (usize, usize) {
+        self.metrics.get(metric).and_then(|m| m.span).unwrap_or((1, 1))
+    }
+
+    /// Number of ticks of the value axis of `metric`, when configured.
+    pub fn ticks(&self, metric: &Metric) -> Option<usize> {
+        self.metrics.get(metric).and_then(|m| m.ticks)
+    }
+
+    /// Decimal places of the tick labels of `metric`, when configured.
+    pub fn decimals(&self, metric: &Metric) -> Option<usize> {
+        self.metrics.get(metric).and_then(|m| m.decimals)
+    }
+
+    /// Notation of the tick labels of `metric`, when configured.
+    pub fn notation(&self, metric: &Metric) -> Option<Notation> {
+        self.metrics.get(metric).and_then(|m| m.notation)
+    }
+
+    /// Configured bandwidth of the density estimates of `metric`.
+    pub fn bandwidth(&self, metric: &Metric) -> Option<f64> {
+        self.metrics.get(metric).and_then(|m| m.bandwidth)
+    }
+
+    /// Reference line of the `metric` panel, as its value and label.
+    pub fn threshold(&self, metric: &Metric) -> Option<(f64, String)> {
+        let style = self.metrics.get(metric)?;
+        let value = style.threshold?;
+        let label = style.threshold_label.clone().unwrap_or_else(|| ticks::format(value, metric.unit(), self.locale));
+        Some((value, label))
+    }
+
+    /// `size` pixels at the scale of the figure.
+    pub fn px(&self, size: u32) -> u32 {
+        (size as f64 * self.scale).round() as u32
+    }
+
+    /// Size of the image of a `width` by `height` figure.
+    pub fn canvas(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        (self.px(width), self.px(height))
+    }
+
+    /// A chart builder with the margin and label areas of the style, falling
+    /// back to those given for this kind of chart, all at scale.
+    pub fn chart<'a, 'b, DB: DrawingBackend>(
+        &'b self,
+        area: &'a DrawingArea<DB, plotters::coord::Shift>,
+        margin: u32,
+        (left, bottom): (u32, u32),
+    ) -> ChartBuilder<'a, 'b, DB> {
+        let mut builder = ChartBuilder::on(area);
+        builder
+            .margin(self.px(self.margin.unwrap_or(margin)))
+            .set_label_area_size(LabelAreaPosition::Left, self.px(self.label_area.left.unwrap_or(left)))
+            .set_label_area_size(
+                LabelAreaPosition::Bottom,
+                self.px(self.label_area.bottom.unwrap_or(bottom)),
+            );
+        builder
+    }
+
+    /// Font of chart captions, scaled from the default caption size.
+    pub fn caption_font(&self, default_size: u32) -> TextStyle<'_> {
+        self.text(default_size * self.caption_size / 20)
+    }
+
+    /// Font of the style at `size` points before scaling.
+    pub fn text(&self, size: u32) -> TextStyle<'_> {
+        (self.font.as_str(), self.px(size)).into_font().color(&self.foreground.0)
+    }
+
+    pub fn apply_to_mesh<'b, X: Ranged, Y: Ranged, DB: DrawingBackend>(
+        &'b self,
+        mesh: &mut MeshStyle<'_, 'b, X, Y, DB>,
+    ) {
+        let (foreground, line) = (self.foreground.0, self.px(1));
+        mesh.label_style(self.text(self.label_size))
+            .axis_desc_style(self.text(self.label_size))
+            .axis_style(foreground.stroke_width(line))
+            .bold_line_style(foreground.mix(0.2).stroke_width(line))
+            .light_line_style(foreground.mix(0.1).stroke_width(line));
+    }
+
+    pub fn apply_to_legend<'a, 'b, DB: DrawingBackend + 'a, CT: CoordTranslate>(
+        &'b self,
+        labels: &mut SeriesLabelStyle<'a, 'b, DB, CT>,
+    ) {
+        labels
+            .label_font(self.text(self.label_size))
+            .background_style(self.background.0.mix(0.8))
+            .border_style(self.foreground.0.stroke_width(self.px(1)))
+            .margin(self.px(10))
+            .legend_area_size(self.px(30));
+    }
+
+    /// Draws the series labels of `chart` where the style puts legends, or
+    /// at `default` when it leaves that to the chart.
+    pub fn draw_legend<'a, DB: DrawingBackend + 'a, CT: CoordTranslate>(
+        &self,
+        chart: &mut ChartContext<'a, DB, CT>,
+        default: SeriesLabelPosition,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        let position = match self.legend {
+            None | Some(LegendPosition::Outside) => default,
+            Some(LegendPosition::None) => return Ok(()),
+            Some(LegendPosition::UpperLeft) => SeriesLabelPosition::UpperLeft,
+            Some(LegendPosition::UpperMiddle) => SeriesLabelPosition::UpperMiddle,
+            Some(LegendPosition::UpperRight) => SeriesLabelPosition::UpperRight,
+            Some(LegendPosition::MiddleLeft) => SeriesLabelPosition::MiddleLeft,
+            Some(LegendPosition::MiddleRight) => SeriesLabelPosition::MiddleRight,
+            Some(LegendPosition::LowerLeft) => SeriesLabelPosition::LowerLeft,
+            Some(LegendPosition::LowerMiddle) => SeriesLabelPosition::LowerMiddle,
+            Some(LegendPosition::LowerRight) => SeriesLabelPosition::LowerRight,
+        };
+        let mut labels = chart.configure_series_labels();
+        labels.position(position);
+        self.apply_to_legend(&mut labels);
+        labels.draw()?;
+        Ok(())
+    }
+}
+
+/// Overwrites the keys of `base` with those of `overrides`, descending into
+/// tables present in both.
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,445 @@
+//! Box plot statistics and their export as a machine-readable table.
+
+use crate::{GenomeStats, Metric, Sketches};
+use clap::ValueEnum;
+use serde::Serialize;
+use statistical::{mean, median, standard_deviation};
+use std::error::Error;
+use std::path::Path;
+
+/// Summary of one distribution, as drawn by a box plot.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    /// Last values inside the Tukey fences, where the whiskers end.
+    pub whisker_low: f64,
+    pub whisker_high: f64,
+    pub outliers: usize,
+}
+
+/// Percentile bootstrap of the confidence interval of a median.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bootstrap {
+    /// Number of resamples.
+    pub resamples: usize,
+    /// Confidence level of the interval, such as 0.95.
+    pub level: f64,
+}
+
+impl Bootstrap {
+    /// Confidence interval of the median of `data`, which must not be empty,
+    /// from the medians of resamples drawn with replacement. The resamples
+    /// follow a fixed seed, so that the same data always draws the same
+    /// figure.
+    pub fn median_interval(&self, data: &[f64]) -> (f64, f64) {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        // SplitMix64 (Steele et al., 2014), as good as needed for resampling
+        let mut next = |n: usize| {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            (((z ^ (z >> 31)) as u128 * n as u128) >> 64) as usize
+        };
+        let mut resample = vec![0.0; data.len()];
+        let mut medians = Vec::with_capacity(self.resamples);
+        for _ in 0..self.resamples.max(1) {
+            for x in &mut resample {
+                *x = data[next(data.len())];
+            }
+            resample.sort_by(f64::total_cmp);
+            medians.push(median(&resample));
+        }
+        medians.sort_by(f64::total_cmp);
+        let tail = (1.0 - self.level) / 2.0;
+        (quantile(&medians, tail, QuantileMethod::R7), quantile(&medians, 1.0 - tail, QuantileMethod::R7))
+    }
+}
+
+/// Sample quantile definitions of Hyndman and Fan (1996), numbered as in R's
+/// `quantile()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lower")]
+pub enum QuantileMethod {
+    /// Inverse of the empirical distribution function
+    R1,
+    /// Like r1, averaging at discontinuities
+    R2,
+    /// Nearest even order statistic (SAS)
+    R3,
+    /// Linear interpolation of the empirical distribution function
+    R4,
+    /// Piecewise linear, with knots halfway through the steps
+    R5,
+    /// Linear, p(k) = k / (n + 1) (Minitab, SPSS)
+    R6,
+    /// Linear, p(k) = (k - 1) / (n - 1) (R, NumPy and Excel default)
+    #[default]
+    R7,
+    /// Approximately median-unbiased whatever the distribution
+    R8,
+    /// Approximately unbiased for normally distributed data
+    R9,
+}
+
+/// The `p` quantile of `sorted`, which must not be empty, following the
+/// algorithm of R's `quantile()`.
+pub fn quantile(sorted: &[f64], p: f64, method: QuantileMethod) -> f64 {
+    let n = sorted.len() as f64;
+    // Order statistic k, counting from one and clamped to the sample
+    let x = |k: f64| sorted[(k.max(1.0).min(n) as usize) - 1];
+    // Guards the comparisons below against rounding, as R does
+    let fuzz = 4.0 * f64::EPSILON;
+    match method {
+        QuantileMethod::R1 | QuantileMethod::R2 | QuantileMethod::R3 => {
+            let m = if method == QuantileMethod::R3 { -0.5 } else { 0.0 };
+            let j = (n * p + m + fuzz).floor();
+            let at_knot = (n * p + m - j).abs() < fuzz;
+            let gamma = match method {
+                QuantileMethod::R1 => if at_knot { 0.0 } else { 1.0 },
+                QuantileMethod::R2 => if at_knot { 0.5 } else { 1.0 },
+                _ => if at_knot && j % 2.0 == 0.0 { 0.0 } else { 1.0 },
+            };
+            (1.0 - gamma) * x(j) + gamma * x(j + 1.0)
+        }
+        _ => {
+            let (a, b) = match method {
+                QuantileMethod::R4 => (0.0, 1.0),
+                QuantileMethod::R5 => (0.5, 0.5),
+                QuantileMethod::R6 => (0.0, 0.0),
+                QuantileMethod::R7 => (1.0, 1.0),
+                QuantileMethod::R8 => (1.0 / 3.0, 1.0 / 3.0),
+                _ => (3.0 / 8.0, 3.0 / 8.0),
+            };
+            let h = a + p * (n + 1.0 - a - b);
+            let j = (h + fuzz).floor();
+            let g = if (h - j).abs() < fuzz { 0.0 } else { h - j };
+            (1.0 - g) * x(j) + g * x(j + 1.0)
+        }
+    }
+}
+
+impl Summary {
+    /// Summarizes `data`, which must not be empty, with quartiles estimated
+    /// by `method`.
+    pub fn compute(data: &[f64], method: QuantileMethod) -> Self {
+        let mut sorted_data = data.to_vec();
+        sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let q1 = quantile(&sorted_data, 0.25, method);
+        let q3 = quantile(&sorted_data, 0.75, method);
+        let med = median(&sorted_data);
+
+        let mut summary = Summary {
+            count: sorted_data.len(),
+            min: sorted_data[0],
+            q1,
+            median: med,
+            q3,
+            max: sorted_data[sorted_data.len() - 1],
+            mean: mean(&sorted_data),
+            stddev: 0.0,
+            whisker_low: q1,
+            whisker_high: q3,
+            outliers: 0,
+        };
+        if sorted_data.len() > 1 {
+            summary.stddev = standard_deviation(&sorted_data, Some(summary.mean));
+        }
+
+        // Find actual whisker ends (last non-outlier points)
+        let (lower_bound, upper_bound) = summary.fences();
+        if let Some(&x) = sorted_data.iter().find(|&&x| x >= lower_bound) {
+            summary.whisker_low = x;
+        }
+        if let Some(&x) = sorted_data.iter().rev().find(|&&x| x <= upper_bound) {
+            summary.whisker_high = x;
+        }
+        summary.outliers = sorted_data.iter().filter(|&&x| summary.is_outlier(x)).count();
+        summary
+    }
+
+    /// Tukey fences, 1.5 IQR beyond the quartiles.
+    pub fn fences(&self) -> (f64, f64) {
+        let iqr = self.q3 - self.q1;
+        (self.q1 - 1.5 * iqr, self.q3 + 1.5 * iqr)
+    }
+
+    /// Notches of a notched box plot, 1.58 IQR / sqrt(n) either side of the
+    /// median: roughly a 95% confidence interval of the median (McGill et al.,
+    /// 1978), so boxes whose notches do not overlap have different medians.
+    pub fn notch(&self) -> (f64, f64) {
+        let half_width = 1.58 * (self.q3 - self.q1) / (self.count as f64).sqrt();
+        (self.median - half_width, self.median + half_width)
+    }
+
+    pub fn is_outlier(&self, x: f64) -> bool {
+        let (lower_bound, upper_bound) = self.fences();
+        x < lower_bound || x > upper_bound
+    }
+}
+
+/// One row of the exported table. The csv crate cannot serialize flattened
+/// structs, so the summary fields are repeated here.
+#[derive(Serialize)]
+pub(crate) struct Row<'a> {
+    pub metric: &'a Metric,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<&'a str>,
+    pub count: usize,
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub outliers: usize,
+    /// Bootstrap confidence interval of the median, when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_ci_low: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_ci_high: Option<f64>,
+}
+
+impl<'a> Row<'a> {
+    fn new(
+        metric: &'a Metric,
+        group: Option<&'a str>,
+        values: &[f64],
+        method: QuantileMethod,
+        bootstrap: Option<Bootstrap>,
+    ) -> Self {
+        let interval = bootstrap.map(|bootstrap| bootstrap.median_interval(values));
+        Row::with_interval(metric, group, &Summary::compute(values, method), interval)
+    }
+
+    /// Row of a summary already computed, with the confidence `interval` of
+    /// its median if any.
+    fn with_interval(metric: &'a Metric, group: Option<&'a str>, s: &Summary, interval: Option<(f64, f64)>) -> Self {
+        Row {
+            metric,
+            group,
+            count: s.count,
+            min: s.min,
+            q1: s.q1,
+            median: s.median,
+            q3: s.q3,
+            max: s.max,
+            mean: s.mean,
+            stddev: s.stddev,
+            outliers: s.outliers,
+            median_ci_low: interval.map(|(low, _)| low),
+            median_ci_high: interval.map(|(_, high)| high),
+        }
+    }
+}
+
+/// Summary of every metric, per group when grouped, leaving out missing
+/// values and the groups that have none, with the confidence interval of
+/// each median when `bootstrap` is given.
+pub(crate) fn summary_rows<'a>(
+    data: &[GenomeStats],
+    metrics: &'a [Metric],
+    groups: &[&'a str],
+    method: QuantileMethod,
+    bootstrap: Option<Bootstrap>,
+) -> Vec<Row<'a>> {
+    let mut rows = Vec::new();
+    let ungrouped = [None];
+    let subsets: Vec<Option<&str>> = match groups.is_empty() {
+        true => ungrouped.to_vec(),
+        false => groups.iter().copied().map(Some).collect(),
+    };
+    for metric in metrics {
+        for &group in &subsets {
+            let values: Vec<f64> = data
+                .iter()
+                .filter(|x| group.is_none_or(|group| x.group_name() == group))
+                .map(|x| metric.value(x))
+                .filter(|v| v.is_finite())
+                .collect();
+            if !values.is_empty() {
+                rows.push(Row::new(metric, group, &values, method, bootstrap));
+            }
+        }
+    }
+    rows
+}
+
+/// Writes the summary of every metric, per group when grouped, as JSON when
+/// `path` ends in `.json` and as CSV (tab-separated for `.tsv`) otherwise.
+pub fn write_summaries(
+    path: &Path,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    groups: &[&str],
+    method: QuantileMethod,
+    bootstrap: Option<Bootstrap>,
+) -> Result<(), Box<dyn Error>> {
+    write_rows(path, summary_rows(data, metrics, groups, method, bootstrap))
+}
+
+/// Writes the summary of every metric of `sketches`, per group when grouped,
+/// like [`write_summaries`]; the quartiles of large groups are estimates.
+pub fn write_sketch_summaries(path: &Path, sketches: &Sketches, method: QuantileMethod) -> Result<(), Box<dyn Error>> {
+    write_rows(path, sketch_rows(sketches, method))
+}
+
+/// Writes the summary of every metric, per group when grouped, into `table`
+/// of the SQLite database `database`, replacing that of a previous run.
+pub fn write_summary_table(
+    database: &Path,
+    table: &str,
+    data: &[GenomeStats],
+    metrics: &[Metric],
+    groups: &[&str],
+    method: QuantileMethod,
+    bootstrap: Option<Bootstrap>,
+) -> Result<(), Box<dyn Error>> {
+    crate::sqlite::write_rows(database, table, &summary_rows(data, metrics, groups, method, bootstrap))
+}
+
+/// Writes the summary of every metric of `sketches` into `table` of the
+/// SQLite database `database`, like [`write_summary_table`].
+pub fn write_sketch_summary_table(
+    database: &Path,
+    table: &str,
+    sketches: &Sketches,
+    method: QuantileMethod,
+) -> Result<(), Box<dyn Error>> {
+    crate::sqlite::write_rows(database, table, &sketch_rows(sketches, method))
+}
+
+fn sketch_rows(sketches: &Sketches, method: QuantileMethod) -> Vec<Row<'_>> {
+    let groups = sketches.groups();
+    let mut rows = Vec::new();
+    for metric in sketches.metrics() {
+        for (g, sketch) in sketches.of(metric).unwrap_or_default().into_iter().enumerate() {
+            if let Some(summary) = sketch.summary(method) {
+                rows.push(Row::with_interval(metric, groups.get(g).copied(), &summary, None));
+            }
+        }
+    }
+    rows
+}
+
+/// Writes `rows` as JSON when `path` ends in `.json` and as CSV
+/// (tab-separated for `.tsv`) otherwise.
+fn write_rows(path: &Path, rows: Vec<Row>) -> Result<(), Box<dyn Error>> {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if extension.eq_ignore_ascii_case("json") {
+        let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(out, &rows)?;
+    } else {
+        let delimiter = if extension.eq_ignore_ascii_case("tsv") { b'\t' } else { b',' };
+        let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_path(path)?;
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Probabilities of each row of expected quantiles.
+    const PROBABILITIES: [f64; 7] = [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+
+    /// Quantiles of `data` at [`PROBABILITIES`] by each method, as given by
+    /// R's `quantile(data, PROBABILITIES, type = k)`.
+    type Expected = [(QuantileMethod, [f64; 7]); 9];
+
+    fn assert_quantiles(data: &[f64], expected: &Expected) {
+        let mut sorted = data.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        for (method, quantiles) in expected {
+            for (&p, &expected) in PROBABILITIES.iter().zip(quantiles) {
+                let actual = quantile(&sorted, p, *method);
+                assert!((actual - expected).abs() < 1e-9, "{method:?} at {p} of {data:?}: {actual}, not {expected}");
+            }
+        }
+    }
+
+    #[test]
+    fn odd_count() {
+        assert_quantiles(
+            &[11.0, 2.0, 7.0, 3.0, 5.0],
+            &[
+                (QuantileMethod::R1, [2.0, 2.0, 3.0, 5.0, 7.0, 11.0, 11.0]),
+                (QuantileMethod::R2, [2.0, 2.0, 3.0, 5.0, 7.0, 11.0, 11.0]),
+                (QuantileMethod::R3, [2.0, 2.0, 2.0, 3.0, 7.0, 7.0, 11.0]),
+                (QuantileMethod::R4, [2.0, 2.0, 2.25, 4.0, 6.5, 9.0, 11.0]),
+                (QuantileMethod::R5, [2.0, 2.0, 2.75, 5.0, 8.0, 11.0, 11.0]),
+                (QuantileMethod::R6, [2.0, 2.0, 2.5, 5.0, 9.0, 11.0, 11.0]),
+                (QuantileMethod::R7, [2.0, 2.4, 3.0, 5.0, 7.0, 9.4, 11.0]),
+                (QuantileMethod::R8, [2.0, 2.0, 2.6666666666666665, 5.0, 8.333333333333334, 11.0, 11.0]),
+                (QuantileMethod::R9, [2.0, 2.0, 2.6875, 5.0, 8.25, 11.0, 11.0]),
+            ],
+        );
+    }
+
+    #[test]
+    fn even_count() {
+        assert_quantiles(
+            &[36.0, 1.0, 16.0, 4.0, 25.0, 9.0],
+            &[
+                (QuantileMethod::R1, [1.0, 1.0, 4.0, 9.0, 25.0, 36.0, 36.0]),
+                (QuantileMethod::R2, [1.0, 1.0, 4.0, 12.5, 25.0, 36.0, 36.0]),
+                (QuantileMethod::R3, [1.0, 1.0, 4.0, 9.0, 16.0, 25.0, 36.0]),
+                (QuantileMethod::R4, [1.0, 1.0, 2.5, 9.0, 20.5, 29.4, 36.0]),
+                (QuantileMethod::R5, [1.0, 1.3, 4.0, 12.5, 25.0, 34.9, 36.0]),
+                (QuantileMethod::R6, [1.0, 1.0, 3.25, 12.5, 27.75, 36.0, 36.0]),
+                (QuantileMethod::R7, [1.0, 2.5, 5.25, 12.5, 22.75, 30.5, 36.0]),
+                (QuantileMethod::R8, [1.0, 1.0, 3.75, 12.5, 25.916666666666668, 36.0, 36.0]),
+                (QuantileMethod::R9, [1.0, 1.0, 3.8125, 12.5, 25.6875, 36.0, 36.0]),
+            ],
+        );
+    }
+
+    #[test]
+    fn single_sample() {
+        assert_quantiles(
+            &[42.0],
+            &[
+                (QuantileMethod::R1, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R2, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R3, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R4, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R5, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R6, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R7, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R8, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+                (QuantileMethod::R9, [42.0, 42.0, 42.0, 42.0, 42.0, 42.0, 42.0]),
+            ],
+        );
+    }
+
+    #[test]
+    fn tied_samples() {
+        assert_quantiles(
+            &[3.0, 9.0, 3.0, 5.0, 3.0, 9.0, 5.0],
+            &[
+                (QuantileMethod::R1, [3.0, 3.0, 3.0, 5.0, 9.0, 9.0, 9.0]),
+                (QuantileMethod::R2, [3.0, 3.0, 3.0, 5.0, 9.0, 9.0, 9.0]),
+                (QuantileMethod::R3, [3.0, 3.0, 3.0, 5.0, 5.0, 9.0, 9.0]),
+                (QuantileMethod::R4, [3.0, 3.0, 3.0, 4.0, 6.0, 9.0, 9.0]),
+                (QuantileMethod::R5, [3.0, 3.0, 3.0, 5.0, 8.0, 9.0, 9.0]),
+                (QuantileMethod::R6, [3.0, 3.0, 3.0, 5.0, 9.0, 9.0, 9.0]),
+                (QuantileMethod::R7, [3.0, 3.0, 3.0, 5.0, 7.0, 9.0, 9.0]),
+                (QuantileMethod::R8, [3.0, 3.0, 3.0, 5.0, 8.333333333333334, 9.0, 9.0]),
+                (QuantileMethod::R9, [3.0, 3.0, 3.0, 5.0, 8.25, 9.0, 9.0]),
+            ],
+        );
+    }
+}
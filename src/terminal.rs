@@ -0,0 +1,162 @@
+//! Compact Unicode boxplots and histograms printed to a terminal, for a quick
+//! look at results on a remote machine.
+
+use crate::{group_labels, panel_letter, ticks, Dataset, GenomeStats, Locale, Metric, PlotConfig, PlotType, Summary};
+use std::error::Error;
+use std::io::Write;
+
+/// Heights of histogram bars, from one eighth of a cell to a full one.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Longest group label before it is cut short.
+const MAX_LABEL: usize = 16;
+
+/// Prints one chart per metric to `out`, `columns` characters wide: a boxplot
+/// per group, or a histogram with `--plot-type histogram`.
+pub fn write_terminal(
+    out: &mut dyn Write,
+    dataset: &Dataset,
+    config: &PlotConfig,
+    columns: usize,
+) -> Result<(), Box<dyn Error>> {
+    let data = dataset.records();
+    let histogram = match config.plot_type {
+        PlotType::Histogram => true,
+        PlotType::Boxplot | PlotType::Violin => false,
+        _ => return Err("terminal output draws boxplots and histograms only".into()),
+    };
+    let groups = group_labels(data);
+    let label_width = groups.iter().map(|g| g.chars().count()).max().unwrap_or(0).min(MAX_LABEL);
+    // Room for the labels, the gap after them and the counts of histograms
+    let width = columns.saturating_sub(label_width + 2 + if histogram { 7 } else { 0 }).max(10);
+
+    if let Some(title) = &config.style.title {
+        writeln!(out, "{title}\n")?;
+    }
+    for (i, metric) in config.metrics.iter().enumerate() {
+        let panel = panel_letter(i);
+        writeln!(out, "{panel}. {}", config.style.title(metric))?;
+        let subsets: Vec<(&str, Vec<f64>)> = if groups.is_empty() {
+            vec![("", values(data.iter(), metric))]
+        } else {
+            groups
+                .iter()
+                .map(|&group| (group, values(data.iter().filter(|x| x.group_name() == group), metric)))
+                .collect()
+        };
+        let all: Vec<f64> = subsets.iter().flat_map(|(_, values)| values.iter().copied()).collect();
+        if all.is_empty() {
+            writeln!(out, "  no values\n")?;
+            continue;
+        }
+        let axis = Axis::new(&all, metric, config, width);
+        for (group, values) in &subsets {
+            let label: String = group.chars().take(label_width).collect();
+            let line = if values.is_empty() {
+                String::new()
+            } else if histogram {
+                let counts = axis.bins(values, (config.bins as usize).min(width));
+                format!("{} {:>5}", sparkline(&counts, width), values.len())
+            } else {
+                axis.boxplot(&Summary::compute(values, config.quantile_method), values)
+            };
+            writeln!(out, "{label:<label_width$}  {}", line.trim_end())?;
+        }
+        let (lo, hi) = (axis.label(axis.lo), axis.label(axis.hi));
+        let gap = width.saturating_sub(lo.chars().count() + hi.chars().count()).max(1);
+        writeln!(out, "{:label_width$}  {lo}{:gap$}{hi}\n", "", "")?;
+    }
+    if let Some(footer) = &config.style.footer {
+        writeln!(out, "{footer}")?;
+    }
+    Ok(())
+}
+
+fn values<'a>(records: impl Iterator<Item = &'a GenomeStats>, metric: &Metric) -> Vec<f64> {
+    records.map(|x| metric.value(x)).filter(|v| v.is_finite()).collect()
+}
+
+/// Value axis of one chart, mapping values to character cells.
+struct Axis {
+    lo: f64,
+    hi: f64,
+    log: bool,
+    width: usize,
+    raw: bool,
+    locale: Locale,
+    metric: Metric,
+}
+
+impl Axis {
+    fn new(values: &[f64], metric: &Metric, config: &PlotConfig, width: usize) -> Self {
+        let log = config.is_log(metric) && values.iter().all(|&v| v > 0.0);
+        let (lo, hi) = config.style.range(metric).unwrap_or_else(|| {
+            let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (lo, hi)
+        });
+        Axis { lo, hi, log, width, raw: config.raw_ticks, locale: config.style.locale, metric: metric.clone() }
+    }
+
+    /// Fraction of the axis at which `value` lies, clamped to its ends.
+    fn fraction(&self, value: f64) -> f64 {
+        let t = |v: f64| if self.log { v.log10() } else { v };
+        if self.hi <= self.lo {
+            return 0.5;
+        }
+        ((t(value) - t(self.lo)) / (t(self.hi) - t(self.lo))).clamp(0.0, 1.0)
+    }
+
+    fn cell(&self, value: f64) -> usize {
+        (self.fraction(value) * (self.width - 1) as f64).round() as usize
+    }
+
+    fn label(&self, value: f64) -> String {
+        if self.raw {
+            self.locale.number(&value.to_string())
+        } else {
+            ticks::format(value, self.metric.unit(), self.locale)
+        }
+    }
+
+    /// Whiskers, box, median and outliers, as in `├──███┃██──┤  •`.
+    fn boxplot(&self, summary: &Summary, values: &[f64]) -> String {
+        let mut cells = vec![' '; self.width];
+        let (low, high) = (self.cell(summary.whisker_low), self.cell(summary.whisker_high));
+        cells[low..=high].fill('─');
+        cells[low] = '├';
+        cells[high] = '┤';
+        cells[self.cell(summary.q1)..=self.cell(summary.q3)].fill('█');
+        cells[self.cell(summary.median)] = '┃';
+        for &value in values.iter().filter(|&&v| summary.is_outlier(v)) {
+            cells[self.cell(value)] = '•';
+        }
+        cells.into_iter().collect()
+    }
+
+    /// Number of `values` in each of `bins` equally wide bins of the axis.
+    fn bins(&self, values: &[f64], bins: usize) -> Vec<usize> {
+        let mut counts = vec![0; bins];
+        for &value in values {
+            let bin = (self.fraction(value) * bins as f64) as usize;
+            counts[bin.min(bins - 1)] += 1;
+        }
+        counts
+    }
+}
+
+/// Bars of `counts` scaled to the largest, each spread over its share of
+/// `width` cells.
+fn sparkline(counts: &[usize], width: usize) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    (0..width)
+        .map(|cell| {
+            let count = counts[cell * counts.len() / width];
+            if count == 0 {
+                ' '
+            } else {
+                BARS[((count * BARS.len()).div_ceil(max) - 1).min(BARS.len() - 1)]
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,236 @@
+//! Tick labels of the value axes.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// What the values of a metric measure, which decides how they are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Lengths, written as `800 bp`, `250 kb`, `15 Mb` or `2.3 Gb`.
+    BasePairs,
+    /// Counts, written as `800`, `2.5k` or `1.2M`.
+    Count,
+    Percent,
+}
+
+/// How the tick labels of a panel are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    /// In the unit of the metric, as 15 Mb or 2.5k
+    Units,
+    /// As plain numbers, as 15000000
+    Plain,
+    /// In scientific notation with a superscript exponent, as 1.5×10⁷
+    Scientific,
+    /// In E notation, as 1.5e7
+    E,
+    /// With an SI prefix and no unit, as 15M
+    Si,
+}
+
+/// How numbers are written: their decimal mark and the separator of their
+/// thousands, selected with `--locale`. The default `C` locale writes them as
+/// Rust does, with a point and no separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(into = "String")]
+pub struct Locale {
+    name: &'static str,
+    pub decimal: char,
+    pub group: Option<char>,
+}
+
+/// Languages with their decimal mark and thousands separator, the space
+/// being a no-break one so that numbers are never wrapped.
+const LOCALES: [(&str, char, Option<char>); 23] = [
+    ("C", '.', None),
+    ("en", '.', Some(',')),
+    ("ja", '.', Some(',')),
+    ("ko", '.', Some(',')),
+    ("zh", '.', Some(',')),
+    ("da", ',', Some('.')),
+    ("de", ',', Some('.')),
+    ("es", ',', Some('.')),
+    ("id", ',', Some('.')),
+    ("it", ',', Some('.')),
+    ("nl", ',', Some('.')),
+    ("pt", ',', Some('.')),
+    ("tr", ',', Some('.')),
+    ("cs", ',', Some('\u{a0}')),
+    ("fi", ',', Some('\u{a0}')),
+    ("fr", ',', Some('\u{a0}')),
+    ("nb", ',', Some('\u{a0}')),
+    ("no", ',', Some('\u{a0}')),
+    ("pl", ',', Some('\u{a0}')),
+    ("ru", ',', Some('\u{a0}')),
+    ("sk", ',', Some('\u{a0}')),
+    ("sv", ',', Some('\u{a0}')),
+    ("uk", ',', Some('\u{a0}')),
+];
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale { name: "C", decimal: '.', group: None }
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        locale.name.to_string()
+    }
+}
+
+// By hand, as the derived impl would borrow the name from the input
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Locale::try_from(String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    /// Reads a language such as `de`, or a locale such as `es_CO.UTF-8` of
+    /// which only the language counts.
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let language = match s.split(['_', '-', '.', '@']).next().unwrap_or_default() {
+            "POSIX" => "C",
+            language => language,
+        };
+        let (name, decimal, group) = LOCALES
+            .iter()
+            .find(|(name, ..)| name.eq_ignore_ascii_case(language))
+            .ok_or_else(|| {
+                let names: Vec<&str> = LOCALES.iter().map(|(name, ..)| *name).collect();
+                format!("unknown locale {s:?}, expected one of {}", names.join(", "))
+            })?;
+        Ok(Locale { name, decimal: *decimal, group: *group })
+    }
+}
+
+impl Locale {
+    /// `text`, a number as Rust writes it such as `-1234.5` or `2.5e6`, with
+    /// the decimal mark and thousands separator of the locale.
+    pub fn number(self, text: &str) -> String {
+        let (sign, unsigned) = text.strip_prefix('-').map_or(("", text), |rest| ("-", rest));
+        let (integer, rest) = unsigned.split_at(unsigned.find(|c: char| !c.is_ascii_digit()).unwrap_or(unsigned.len()));
+        let mut out = String::from(sign);
+        for (i, digit) in integer.chars().enumerate() {
+            if let Some(group) = self.group.filter(|_| i > 0 && (integer.len() - i) % 3 == 0) {
+                out.push(group);
+            }
+            out.push(digit);
+        }
+        match rest.strip_prefix('.') {
+            Some(fraction) => {
+                out.push(self.decimal);
+                out.push_str(fraction);
+            }
+            None => out.push_str(rest),
+        }
+        out
+    }
+}
+
+/// Human-readable label of `value`.
+pub fn format(value: f64, unit: Unit, locale: Locale) -> String {
+    write(value, unit, Notation::Units, None, locale)
+}
+
+/// Label of `value` of a `unit` in `notation`, with `decimals` decimal places
+/// or three significant digits.
+pub fn write(value: f64, unit: Unit, notation: Notation, decimals: Option<usize>, locale: Locale) -> String {
+    let digits = |value: f64| match decimals {
+        Some(decimals) => format!("{value:.decimals$}"),
+        None => trimmed(value),
+    };
+    match (notation, unit) {
+        _ if value == 0.0 => locale.number(&digits(0.0)),
+        (Notation::Units, Unit::BasePairs) => {
+            let (scale, suffix) = scale(value, [" bp", " kb", " Mb", " Gb"]);
+            format!("{}{}", locale.number(&digits(value / scale)), suffix)
+        }
+        (Notation::Units, Unit::Count) => {
+            let (scale, suffix) = scale(value, ["", "k", "M", "G"]);
+            format!("{}{}", locale.number(&digits(value / scale)), suffix)
+        }
+        (Notation::Si, _) => {
+            let (scale, suffix) = scale(value, ["", "k", "M", "G"]);
+            format!("{}{}", locale.number(&digits(value / scale)), suffix)
+        }
+        (Notation::Units, Unit::Percent) | (Notation::Plain, _) => locale.number(&digits(value)),
+        (Notation::Scientific | Notation::E, _) => {
+            let mut exponent = value.abs().log10().floor() as i32;
+            let mut mantissa = digits(value / 10f64.powi(exponent));
+            // Rounded up to 10, as 9.996e2 to two decimals
+            if mantissa.trim_start_matches('-').starts_with("10") {
+                exponent += 1;
+                mantissa = digits(value / 10f64.powi(exponent));
+            }
+            match notation {
+                Notation::E => locale.number(&format!("{mantissa}e{exponent}")),
+                // Powers of ten alone, as on log axes
+                _ if mantissa == "1" => format!("10{}", superscript(exponent)),
+                _ => locale.number(&format!("{mantissa}×10{}", superscript(exponent))),
+            }
+        }
+    }
+}
+
+/// `n` in superscript digits, as ⁻³ for -3.
+fn superscript(n: i32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    n.to_string()
+        .chars()
+        .map(|c| c.to_digit(10).map_or('⁻', |digit| DIGITS[digit as usize]))
+        .collect()
+}
+
+/// Label of a log-scale axis tick when raw numbers are asked for: plain for
+/// small numbers such as `0.5` or `20`, otherwise in scientific notation such
+/// as `2e6` or `2.5e6`.
+pub fn scientific(value: f64, locale: Locale) -> String {
+    if value <= 0.0 {
+        return locale.number(&value.to_string());
+    }
+    let mut exponent = value.log10().floor();
+    let mut mantissa = (value / 10f64.powf(exponent) * 100.0).round() / 100.0;
+    if mantissa >= 10.0 {
+        mantissa /= 10.0;
+        exponent += 1.0;
+    }
+    if (-2.0..4.0).contains(&exponent) {
+        locale.number(&trimmed(mantissa * 10f64.powf(exponent)))
+    } else {
+        locale.number(&format!("{mantissa}e{exponent}"))
+    }
+}
+
+/// Largest power of a thousand not above `value`, up to 10^9, with its suffix.
+fn scale(value: f64, suffixes: [&'static str; 4]) -> (f64, &'static str) {
+    let magnitude = value.abs();
+    if magnitude >= 1e9 {
+        (1e9, suffixes[3])
+    } else if magnitude >= 1e6 {
+        (1e6, suffixes[2])
+    } else if magnitude >= 1e3 {
+        (1e3, suffixes[1])
+    } else {
+        (1.0, suffixes[0])
+    }
+}
+
+/// `value` with three significant digits, or all of its integer digits, and
+/// no trailing zeros.
+fn trimmed(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let decimals = (2 - value.abs().log10().floor() as i32).max(0) as usize;
+    let text = format!("{:.*}", decimals, value);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
@@ -0,0 +1,63 @@
+//! Squarified treemaps (Bruls, Huizing and van Wijk, 2000), which tile a
+//! rectangle with one rectangle per value, as close to square as they can.
+
+/// A rectangle as its left and top edges, width and height.
+pub type Tile = (f64, f64, f64, f64);
+
+/// Tiles of `values`, largest first, partitioning `bounds` in proportion to
+/// them and returned in the same order.
+pub fn squarify(values: &[f64], bounds: Tile) -> Vec<Tile> {
+    let total: f64 = values.iter().sum();
+    let (mut x, mut y, mut width, mut height) = bounds;
+    if total <= 0.0 || width <= 0.0 || height <= 0.0 {
+        return Vec::new();
+    }
+    let scale = width * height / total;
+    let areas: Vec<f64> = values.iter().map(|v| v * scale).collect();
+
+    let mut tiles = Vec::with_capacity(values.len());
+    let mut start = 0;
+    while start < areas.len() {
+        // Grow the row along the shorter side while that makes its tiles
+        // squarer
+        let side = width.min(height);
+        let mut end = start + 1;
+        let mut sum = areas[start];
+        while end < areas.len() {
+            let extended = sum + areas[end];
+            if worst(sum, areas[start], areas[end - 1], side) < worst(extended, areas[start], areas[end], side) {
+                break;
+            }
+            sum = extended;
+            end += 1;
+        }
+
+        let thickness = if side > 0.0 { sum / side } else { 0.0 };
+        let mut offset = 0.0;
+        for &area in &areas[start..end] {
+            let length = if thickness > 0.0 { area / thickness } else { 0.0 };
+            if width >= height {
+                tiles.push((x, y + offset, thickness, length));
+            } else {
+                tiles.push((x + offset, y, length, thickness));
+            }
+            offset += length;
+        }
+        if width >= height {
+            x += thickness;
+            width -= thickness;
+        } else {
+            y += thickness;
+            height -= thickness;
+        }
+        start = end;
+    }
+    tiles
+}
+
+/// Worst aspect ratio of a row of tiles of total area `sum`, the largest
+/// `max` and the smallest `min`, laid along a side of length `side`.
+fn worst(sum: f64, max: f64, min: f64, side: f64) -> f64 {
+    let (side, sum) = (side * side, sum * sum);
+    (side * max / sum).max(sum / (side * min))
+}
@@ -0,0 +1,216 @@
+//! Vega-Lite specifications of the boxplots, with the statistics computed
+//! here so that restyling them does not change the boxes.
+
+use crate::html::Numbered;
+use crate::{group_labels, GenomeStats, HexColor, Locale, Metric, PlotConfig, PlotType, Summary};
+use plotters::style::RGBColor;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::path::Path;
+
+const SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// Writes a Vega-Lite specification with one boxplot per metric, stacked
+/// vertically. Each panel inlines a row of statistics per box and the values
+/// of its outliers, or of every sample with `--points`.
+pub fn write_vega(path: &Path, data: &[GenomeStats], config: &PlotConfig) -> Result<(), Box<dyn Error>> {
+    if config.plot_type != PlotType::Boxplot {
+        return Err("Vega-Lite output only describes boxplots".into());
+    }
+    let panels: Vec<Value> = config.metrics.iter().map(|metric| panel(data, metric, config)).collect();
+    let style = &config.style;
+    let mut spec = json!({
+        "$schema": SCHEMA,
+        "background": if style.transparent { "transparent".to_string() } else { color(style.background.0) },
+        "vconcat": panels,
+        "config": {
+            "font": style.font,
+            "title": { "color": color(style.foreground.0), "fontSize": style.caption_size },
+            "axis": {
+                "labelColor": color(style.foreground.0),
+                "titleColor": color(style.foreground.0),
+                "domainColor": color(style.foreground.0),
+                "tickColor": color(style.foreground.0),
+                "labelFontSize": style.label_size,
+                "titleFontSize": style.label_size,
+            },
+            "legend": {
+                "labelColor": color(style.foreground.0),
+                "titleColor": color(style.foreground.0),
+                "labelFontSize": style.label_size,
+            },
+            "view": { "stroke": null },
+        },
+    });
+    if let Some(title) = &style.title {
+        spec["title"] = json!({ "text": title, "anchor": "middle" });
+    }
+    if let Some(footer) = &style.footer {
+        spec["description"] = json!(footer);
+    }
+    if style.locale != Locale::default() {
+        let group = style.locale.group.map(String::from).unwrap_or_default();
+        spec["config"]["locale"] =
+            json!({ "number": { "decimal": style.locale.decimal.to_string(), "thousands": group, "grouping": [3] } });
+    }
+    let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    serde_json::to_writer_pretty(out, &spec)?;
+    Ok(())
+}
+
+/// Layers drawing the boxes of `metric`, one per group when grouped.
+fn panel(data: &[GenomeStats], metric: &Metric, config: &PlotConfig) -> Value {
+    let style = &config.style;
+    let groups = group_labels(data);
+    let subsets: Vec<(Option<&str>, Vec<Numbered>)> = if groups.is_empty() {
+        vec![(None, data.iter().enumerate().collect())]
+    } else {
+        groups
+            .iter()
+            .map(|&group| {
+                let records = data.iter().enumerate().filter(|(_, x)| x.group_name() == group).collect();
+                (Some(group), records)
+            })
+            .collect()
+    };
+
+    let mut boxes = Vec::new();
+    let mut samples = Vec::new();
+    for (group, records) in &subsets {
+        let values: Vec<f64> = records.iter().map(|(_, x)| metric.value(x)).filter(|v| v.is_finite()).collect();
+        if values.is_empty() {
+            continue;
+        }
+        let summary = Summary::compute(&values, config.quantile_method);
+        boxes.push(json!({
+            "group": group,
+            "count": summary.count,
+            "whisker_low": summary.whisker_low,
+            "q1": summary.q1,
+            "median": summary.median,
+            "q3": summary.q3,
+            "whisker_high": summary.whisker_high,
+            "mean": summary.mean,
+        }));
+        for &(row, record) in records {
+            let value = metric.value(record);
+            let outlier = summary.is_outlier(value);
+            if value.is_finite() && (outlier || config.points) {
+                samples.push(json!({
+                    "group": group,
+                    "sample": record.name(row),
+                    "value": value,
+                    "outlier": outlier,
+                }));
+            }
+        }
+    }
+
+    let mut x = json!({ "type": "quantitative", "title": style.xlabel(metric), "scale": { "zero": false } });
+    if config.is_log(metric) {
+        x["scale"]["type"] = json!("log");
+    }
+    if let Some((lo, hi)) = style.range(metric) {
+        x["scale"]["domain"] = json!([lo, hi]);
+    }
+    let y = if groups.is_empty() {
+        Value::Null
+    } else {
+        json!({ "field": "group", "type": "nominal", "title": null, "sort": groups })
+    };
+    let fill = if groups.is_empty() {
+        json!({ "value": color(style.box_color.0) })
+    } else {
+        let colors: Vec<String> = (0..groups.len()).map(|i| color(style.group_color(i))).collect();
+        json!({
+            "field": "group",
+            "type": "nominal",
+            "title": null,
+            "scale": { "domain": groups, "range": colors },
+        })
+    };
+    let encode = |field: &str, extra: Value| {
+        let mut encoding = json!({ "x": x.clone() });
+        encoding["x"]["field"] = json!(field);
+        if !y.is_null() {
+            encoding["y"] = y.clone();
+        }
+        if let Value::Object(extra) = extra {
+            encoding.as_object_mut().expect("encoding is an object").extend(extra);
+        }
+        encoding
+    };
+    let stats = json!({ "values": boxes });
+    let mut layers = vec![
+        json!({
+            "data": stats,
+            "mark": { "type": "rule", "color": color(style.whisker_color.0), "clip": true },
+            "encoding": encode("whisker_low", json!({ "x2": { "field": "whisker_high" } })),
+        }),
+        json!({
+            "data": stats,
+            "mark": { "type": "bar", "size": 24, "stroke": color(style.foreground.0), "clip": true },
+            "encoding": encode("q1", json!({
+                "x2": { "field": "q3" },
+                "color": fill,
+                "tooltip": [
+                    { "field": "count", "title": "n" },
+                    { "field": "q1", "title": "Q1" },
+                    { "field": "median", "title": "Median" },
+                    { "field": "q3", "title": "Q3" },
+                    { "field": "mean", "title": "Mean" },
+                ],
+            })),
+        }),
+        json!({
+            "data": stats,
+            "mark": { "type": "tick", "size": 24, "thickness": 2, "color": color(style.median_color.0), "clip": true },
+            "encoding": encode("median", json!({})),
+        }),
+    ];
+    if config.mean {
+        layers.push(json!({
+            "data": stats,
+            "mark": { "type": "point", "shape": "diamond", "filled": true, "color": color(style.foreground.0), "clip": true },
+            "encoding": encode("mean", json!({})),
+        }));
+    }
+    if !samples.is_empty() {
+        layers.push(json!({
+            "data": { "values": samples },
+            "mark": { "type": "point", "color": color(style.outlier_color.0), "clip": true },
+            "encoding": encode("value", json!({
+                "tooltip": [{ "field": "sample" }, { "field": "value" }],
+                "opacity": { "condition": { "test": "datum.outlier", "value": 1 }, "value": 0.4 },
+            })),
+        }));
+    }
+
+    if let Some((value, label)) = style.threshold(metric) {
+        let mut x = x.clone();
+        x["field"] = json!("threshold");
+        let reference = json!({ "values": [{ "threshold": value, "label": label }] });
+        layers.push(json!({
+            "data": reference,
+            "mark": { "type": "rule", "strokeDash": [4, 3], "color": color(style.foreground.0), "clip": true },
+            "encoding": { "x": x },
+        }));
+        layers.push(json!({
+            "data": reference,
+            "mark": { "type": "text", "align": "left", "baseline": "top", "dx": 3, "dy": 2, "fontSize": 10, "color": color(style.foreground.0) },
+            "encoding": { "x": x, "y": { "value": 0 }, "text": { "field": "label" } },
+        }));
+    }
+
+    let panel_height = config.height / config.metrics.len().max(1) as u32;
+    json!({
+        "title": style.title(metric),
+        "width": config.width.saturating_sub(120),
+        "height": panel_height.saturating_sub(90).max(40),
+        "layer": layers,
+    })
+}
+
+fn color(rgb: RGBColor) -> String {
+    HexColor(rgb).into()
+}
@@ -0,0 +1,66 @@
+//! Re-rendering the figure whenever one of its inputs changes.
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// How long the inputs must stay unchanged before rendering, so that a file
+/// written in several steps is read once, when complete.
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// Calls `render` whenever one of `inputs` changes, until interrupted. The
+/// directories holding the inputs are watched rather than the files, which
+/// pipelines often replace instead of appending to. Errors of `render` are
+/// reported without stopping.
+pub fn watch(
+    inputs: &[PathBuf],
+    mut render: impl FnMut() -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    if inputs.iter().any(|input| input.as_os_str() == "-") {
+        return Err("the standard input cannot be watched".into());
+    }
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let mut targets = Vec::new();
+    let mut watched = Vec::new();
+    for input in inputs {
+        let target = input
+            .canonicalize()
+            .map_err(|e| format!("{}: {}", input.display(), e))?;
+        let dir = if target.is_dir() {
+            target.clone()
+        } else {
+            target.parent().unwrap_or(Path::new("/")).to_path_buf()
+        };
+        if !watched.contains(&dir) {
+            watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            watched.push(dir);
+        }
+        targets.push(target);
+    }
+    // A change to an input file, or to a file inside an input directory
+    let concerns_inputs = |event: &Event| {
+        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_))
+            && event.paths.iter().any(|path| {
+                targets.iter().any(|target| path == target || path.parent() == Some(target))
+            })
+    };
+
+    info!("Watching {} input(s) for changes, press Ctrl-C to stop", inputs.len());
+    loop {
+        let event = events.recv()??;
+        if !concerns_inputs(&event) {
+            continue;
+        }
+        while events.recv_timeout(SETTLE).is_ok() {}
+        if let Err(e) = render() {
+            error!("{e}");
+        }
+        // Drops the events of the figure being written, which may land in a
+        // watched directory
+        while events.recv_timeout(SETTLE).is_ok() {}
+    }
+}
@@ -0,0 +1,50 @@
+//! Figures drawn in the browser. Built for `wasm32-unknown-unknown`, the
+//! library draws the figure of a table uploaded to a web page on one of its
+//! canvases, as the command line would write it to a PNG:
+//!
+//! ```js
+//! import init, { draw_table } from "./pkg/count_fasta_plots.js";
+//!
+//! await init();
+//! const table = new Uint8Array(await file.arrayBuffer());
+//! draw_table(document.getElementById("figure"), file.name, table, `
+//!     plot_type = "violin"
+//!     metrics = ["n50", "gc_percentage"]
+//!     group_by = "platform"
+//! `);
+//! ```
+
+use crate::embed::{first_page, Options};
+use crate::{Dataset, Figure, OutputFormat, PlotConfig};
+use plotters::prelude::*;
+use plotters_canvas::CanvasBackend;
+use std::error::Error;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlCanvasElement;
+
+/// Draws the figure of the CSV, TSV or JSON `table`, possibly gzipped, on the
+/// whole of `canvas`. `name` stands for the path of the table and `options`
+/// is a TOML table of the options of the figure, see [`crate::embed`].
+#[wasm_bindgen]
+pub fn draw_table(canvas: HtmlCanvasElement, name: &str, table: &[u8], options: &str) -> Result<(), JsError> {
+    draw(canvas, name, table, options).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn draw(canvas: HtmlCanvasElement, name: &str, table: &[u8], options: &str) -> Result<(), Box<dyn Error>> {
+    let options = Options::parse(options)?;
+    let dataset = Dataset::from_table(name, table, &options.load_options())?;
+    if dataset.is_empty() {
+        return Err("the input contains no records".into());
+    }
+    let config = options.plot_config(OutputFormat::Png)?;
+    let config = PlotConfig {
+        width: (canvas.width() as f64 / config.style.scale).round() as u32,
+        height: (canvas.height() as f64 / config.style.scale).round() as u32,
+        ..config
+    };
+    let root = CanvasBackend::with_canvas_object(canvas).ok_or("the canvas has no 2D context")?.into_drawing_area();
+    let (grid, metrics) = first_page(&config);
+    dataset.records().draw(&root, &config.metrics[metrics], 0, grid, &config)?;
+    root.present()?;
+    Ok(())
+}
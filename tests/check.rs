@@ -0,0 +1,33 @@
+//! Exit statuses of --check on inputs of formats other than CSV.
+
+use std::path::Path;
+use std::process::Command;
+
+fn check(input: &Path, args: &[&str]) -> Option<i32> {
+    let output = Command::new(env!("CARGO_BIN_EXE_count-fasta-plots"))
+        .arg("--check")
+        .args(args)
+        .arg(input)
+        .output()
+        .unwrap();
+    output.status.code()
+}
+
+#[test]
+fn ncbi_report_without_a_statistic() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = dir.path().join("GCF_000001.1_test_assembly_stats.txt");
+    let rows = ["total-length\t1000", "scaffold-count\t3", "gc-perc\t40", "total-gap-length\t0"];
+    let rows: Vec<String> = rows.iter().map(|row| format!("all\tall\tall\tall\t{row}\n")).collect();
+    std::fs::write(&report, format!("# Organism name:  Danaus plexippus\n{}", rows.concat())).unwrap();
+    assert_eq!(check(&report, &["--missing", "fail"]), Some(3));
+    assert_eq!(check(&report, &["--missing", "zero"]), Some(0));
+}
+
+#[test]
+fn fasta_without_a_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let fasta = dir.path().join("headless.fa");
+    std::fs::write(&fasta, "ACGT\n>a\nACGT\n").unwrap();
+    assert_eq!(check(&fasta, &[]), Some(4));
+}
@@ -0,0 +1,27 @@
+//! Text statistics of the stats subcommand.
+
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn indexed_fasta_with_base_composition() {
+    let dir = tempfile::tempdir().unwrap();
+    let fasta = dir.path().join("indexed.fa");
+    std::fs::write(&fasta, ">a\nACGTNNGG\n>b\nAC\n").unwrap();
+    let index = dir.path().join("indexed.fa.fai");
+    std::fs::write(&index, "a\t8\t3\t8\t9\nb\t2\t15\t2\t3\n").unwrap();
+    let later = SystemTime::now() + Duration::from_secs(60);
+    std::fs::File::options().write(true).open(&index).unwrap().set_modified(later).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_count-fasta-plots"))
+        .args(["stats", "--metrics", "n50"])
+        .arg(&fasta)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("sum = 10, n = 2"), "{text}");
+    assert!(text.contains("N_count = 2\n"), "{text}");
+    assert!(text.contains("Gaps = 1\n"), "{text}");
+    assert!(text.contains("GC = 50.00%\n"), "{text}");
+}